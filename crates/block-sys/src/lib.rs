@@ -176,7 +176,38 @@ pub const BLOCK_ALL_COPY_DISPOSE_FLAGS: block_assign_dispose_flags = BLOCK_FIELD
     | BLOCK_FIELD_IS_WEAK
     | BLOCK_BYREF_CALLER;
 
-// TODO: BLOCK_LAYOUT_X
+/// Values for the operators in the extended layout string.
+///
+/// Each byte in the layout string is of the form `0xPN`, where `P` is the
+/// operator, and `N` is a parameter for the operator (usually the number of
+/// bytes or words minus one).
+///
+/// This is a helper type, in the sources this type does not have a name!
+#[cfg(any(doc, feature = "apple"))]
+#[allow(non_camel_case_types)]
+pub type block_layout_op = u8;
+
+/// `N = 0` halts, the rest of the block is non-pointer data.
+#[cfg(any(doc, feature = "apple"))]
+pub const BLOCK_LAYOUT_ESCAPE: block_layout_op = 0;
+/// `N + 1` bytes of non-objects.
+#[cfg(any(doc, feature = "apple"))]
+pub const BLOCK_LAYOUT_NON_OBJECT_BYTES: block_layout_op = 1;
+/// `N + 1` words of non-objects.
+#[cfg(any(doc, feature = "apple"))]
+pub const BLOCK_LAYOUT_NON_OBJECT_WORDS: block_layout_op = 2;
+/// `N + 1` words of strong pointers.
+#[cfg(any(doc, feature = "apple"))]
+pub const BLOCK_LAYOUT_STRONG: block_layout_op = 3;
+/// `N + 1` words of byref pointers.
+#[cfg(any(doc, feature = "apple"))]
+pub const BLOCK_LAYOUT_BYREF: block_layout_op = 4;
+/// `N + 1` words of weak pointers.
+#[cfg(any(doc, feature = "apple"))]
+pub const BLOCK_LAYOUT_WEAK: block_layout_op = 5;
+/// `N + 1` words of unretained pointers.
+#[cfg(any(doc, feature = "apple"))]
+pub const BLOCK_LAYOUT_UNRETAINED: block_layout_op = 6;
 
 extern "C" {
     // the raw data space for runtime classes for blocks
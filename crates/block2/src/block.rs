@@ -1,10 +1,12 @@
 use core::marker::PhantomData;
 use core::mem;
+use std::ffi::CStr;
 
 use objc2::encode::__unstable::EncodeReturn;
-use objc2::encode::{Encode, Encoding, RefEncode};
+use objc2::encode::{Encode, Encoding, EncodingBox, RefEncode};
 
 use crate::ffi;
+use crate::verify::{parse_signature, verify_block_signature, VerificationError};
 
 /// Types that may be used as the arguments of an Objective-C block.
 ///
@@ -17,6 +19,10 @@ use crate::ffi;
 /// This is a sealed trait, and should not need to be implemented. Open an
 /// issue if you know a use-case where this restrition should be lifted!
 pub unsafe trait BlockArguments: Sized {
+    /// The encodings of the arguments.
+    #[doc(hidden)]
+    const __ENCODINGS: &'static [Encoding];
+
     /// Calls the given method the block and arguments.
     #[doc(hidden)]
     unsafe fn __call_block<R: EncodeReturn>(
@@ -29,6 +35,8 @@ pub unsafe trait BlockArguments: Sized {
 macro_rules! block_args_impl {
     ($($a:ident: $t:ident),*) => (
         unsafe impl<$($t: Encode),*> BlockArguments for ($($t,)*) {
+            const __ENCODINGS: &'static [Encoding] = &[$($t::ENCODING),*];
+
             #[inline]
             unsafe fn __call_block<R: EncodeReturn>(
                 invoke: unsafe extern "C" fn(),
@@ -102,9 +110,87 @@ unsafe impl<A: BlockArguments, R: EncodeReturn> RefEncode for Block<A, R> {
     const ENCODING_REF: Encoding = Encoding::Block;
 }
 
+impl<A, R> Block<A, R> {
+    /// The Objective-C type-encoding of the block's return type and
+    /// arguments, if it has one.
+    ///
+    /// This is an [`EncodingBox::Block`] containing the return type, the
+    /// block itself and the arguments along with their qualifiers, without
+    /// any stack offsets; e.g. `@?<v@?i>` for a block that takes an `int`
+    /// and returns `void`.
+    ///
+    /// The signature is present on blocks created by this crate, as well as
+    /// on blocks created by newer compilers. Returns `None` if the block
+    /// doesn't have a signature, or if it could not be parsed.
+    pub fn signature(&self) -> Option<EncodingBox> {
+        let items = parse_signature(self.signature_str()?).ok()?;
+        Some(EncodingBox::Block(Some(items)))
+    }
+
+    /// The signature as it is stored in the descriptor, which may contain
+    /// stack offsets.
+    pub(crate) fn signature_str(&self) -> Option<&str> {
+        let ptr: *const Self = self;
+        let layout = unsafe { ptr.cast::<ffi::Block_layout>().as_ref().unwrap_unchecked() };
+        if layout.flags & ffi::BLOCK_HAS_SIGNATURE == 0 || layout.descriptor.is_null() {
+            return None;
+        }
+        // Compact descriptors store relative offsets instead of pointers,
+        // and have a different layout.
+        #[cfg(feature = "apple")]
+        if layout.flags & ffi::BLOCK_SMALL_DESCRIPTOR != 0 {
+            return None;
+        }
+
+        let encoding = if layout.flags & ffi::BLOCK_HAS_COPY_DISPOSE != 0 {
+            let descriptor = layout
+                .descriptor
+                .cast::<ffi::Block_descriptor_with_signature>();
+            // SAFETY: The flags tell us the type of the descriptor.
+            unsafe { (*descriptor).encoding }
+        } else {
+            let descriptor = layout.descriptor.cast::<ffi::Block_descriptor_basic>();
+            // SAFETY: Same as above.
+            unsafe { (*descriptor).encoding }
+        };
+
+        if encoding.is_null() {
+            None
+        } else {
+            // SAFETY: The encoding is a valid C string.
+            unsafe { CStr::from_ptr(encoding) }.to_str().ok()
+        }
+    }
+}
+
 impl<A: BlockArguments, R: EncodeReturn> Block<A, R> {
+    /// Verify that the block's [signature][Self::signature] matches the
+    /// argument types `A` and return type `R`.
+    ///
+    /// This is useful for blocks received from other code, since there is
+    /// nothing else that ensures that their types are correct.
+    ///
+    /// If the `verify` feature and debug assertions are enabled, this is
+    /// done automatically by [`call`][Self::call] for blocks that have a
+    /// signature.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the block doesn't have a signature, or if it
+    /// doesn't match.
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        verify_block_signature(self.signature_str(), A::__ENCODINGS, &R::ENCODING_RETURN)
+    }
+
     /// Call self with the given arguments.
     ///
+    /// # Panics
+    ///
+    /// Panics if the `verify` feature and debug assertions are enabled, and
+    /// the block's signature doesn't match the argument and return types.
+    ///
+    ///
     /// # Safety
     ///
     /// This invokes foreign code that the caller must verify doesn't violate
@@ -113,6 +199,13 @@ impl<A: BlockArguments, R: EncodeReturn> Block<A, R> {
     /// For example, if this block is shared with multiple references, the
     /// caller must ensure that calling it will not cause a data race.
     pub unsafe fn call(&self, args: A) -> R {
+        #[cfg(all(debug_assertions, feature = "verify"))]
+        if self.signature_str().is_some() {
+            if let Err(err) = self.verify() {
+                panic!("invalid block call: {err}");
+            }
+        }
+
         let ptr: *const Self = self;
         let layout = unsafe { ptr.cast::<ffi::Block_layout>().as_ref().unwrap_unchecked() };
         // TODO: Is `invoke` actually ever null?
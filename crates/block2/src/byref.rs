@@ -0,0 +1,234 @@
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ptr::{self, NonNull};
+
+use crate::ffi;
+
+/// The storage of a `__block` variable, laid out like `Block_byref`
+/// followed by the value.
+#[repr(C)]
+pub(crate) struct ByRefStorage<T> {
+    byref: ffi::Block_byref,
+    /// Logically uninitialized once the variable has been moved to the heap.
+    value: ManuallyDrop<T>,
+}
+
+impl<T> ByRefStorage<T> {
+    /// Get a pointer to the current location of the value.
+    ///
+    /// # Safety
+    ///
+    /// The storage must be valid.
+    unsafe fn value_ptr(this: *mut Self) -> *mut T {
+        // SAFETY: The forwarding pointer points to either the storage
+        // itself, or to the copy that the runtime made on the heap, which
+        // has the same layout.
+        unsafe {
+            let forwarding: *mut Self = (*this).byref.header.forwarding.cast();
+            ptr::addr_of_mut!((*forwarding).value).cast()
+        }
+    }
+}
+
+/// Called by the runtime after copying the header to the heap.
+unsafe extern "C" fn byref_keep<T>(dst: *mut c_void, src: *mut c_void) {
+    let dst: *mut ByRefStorage<T> = dst.cast();
+    let src: *mut ByRefStorage<T> = src.cast();
+    // SAFETY: The value is moved to the heap, after which the original is
+    // only accessed through the forwarding pointer.
+    unsafe {
+        ptr::copy_nonoverlapping(
+            ptr::addr_of!((*src).value),
+            ptr::addr_of_mut!((*dst).value),
+            1,
+        )
+    }
+}
+
+/// Called by the runtime when the last reference to the heap copy is
+/// released.
+unsafe extern "C" fn byref_destroy<T>(this: *mut c_void) {
+    let this: *mut ByRefStorage<T> = this.cast();
+    // SAFETY: The value was moved here in `byref_keep`, and is never used
+    // again.
+    unsafe { ManuallyDrop::drop(&mut (*this).value) }
+}
+
+/// A variable that is shared between the current scope and the blocks that
+/// capture it, like a `__block` variable in C.
+///
+/// The variable can be captured in a [`CapturingBlock`] using
+/// [`capture`][Self::capture]. When such a block is copied to the heap (or
+/// the variable is otherwise passed to `_Block_object_assign` with
+/// `BLOCK_FIELD_IS_BYREF`), the value is moved to a reference-counted heap
+/// allocation that is shared between all the copies, and the forwarding
+/// pointer is updated to point to it. Reads and writes always go through the
+/// forwarding pointer, so every user sees the same value.
+///
+/// The value is dropped when both this and all heap copies of the blocks
+/// that captured it have been dropped.
+///
+/// [`CapturingBlock`]: crate::CapturingBlock
+///
+///
+/// # Example
+///
+/// ```
+/// use block2::{BlockByRef, ByRef, CapturingBlock};
+///
+/// let counter = BlockByRef::new(0);
+/// let block = CapturingBlock::new((counter.capture(),), |(counter,): &(ByRef<'_, i32>,)| {
+///     counter.set(counter.get() + 1);
+/// });
+/// let block = block.copy();
+///
+/// unsafe { block.call(()) };
+/// unsafe { block.call(()) };
+/// assert_eq!(counter.get(), 2);
+/// ```
+pub struct BlockByRef<T> {
+    // Boxed, since the forwarding pointer points to the storage itself until
+    // it has been copied to the heap by the runtime.
+    ptr: NonNull<ByRefStorage<T>>,
+    p: PhantomData<T>,
+}
+
+impl<T> BlockByRef<T> {
+    /// Create a new shared variable with the given initial value.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alignment of `T` is larger than what the runtime's
+    /// allocator guarantees (that of two pointers).
+    pub fn new(value: T) -> Self {
+        assert!(
+            mem::align_of::<ByRefStorage<T>>() <= 2 * mem::size_of::<usize>(),
+            "alignment of `__block` variable is too large",
+        );
+        let storage = Box::into_raw(Box::new(ByRefStorage {
+            byref: ffi::Block_byref {
+                header: ffi::Block_byref_header {
+                    isa: ptr::null(),
+                    forwarding: ptr::null_mut(),
+                    // Same value as `BLOCK_BYREF_HAS_COPY_DISPOSE`
+                    flags: ffi::BLOCK_HAS_COPY_DISPOSE,
+                    size: mem::size_of::<ByRefStorage<T>>() as _,
+                },
+                keep: Some(byref_keep::<T>),
+                destroy: Some(byref_destroy::<T>),
+            },
+            value: ManuallyDrop::new(value),
+        }));
+        // SAFETY: The pointer came from `Box`, and is therefore valid.
+        unsafe { (*storage).byref.header.forwarding = storage.cast() };
+        Self {
+            // SAFETY: The pointer came from `Box`, and is therefore non-null.
+            ptr: unsafe { NonNull::new_unchecked(storage) },
+            p: PhantomData,
+        }
+    }
+
+    /// A pointer to the current location of the value.
+    ///
+    /// The pointer may change when a block that captured the variable is
+    /// copied, and is valid until then, or until this is dropped.
+    pub fn as_ptr(&self) -> *mut T {
+        // SAFETY: The storage is valid while `self` is alive.
+        unsafe { ByRefStorage::value_ptr(self.ptr.as_ptr()) }
+    }
+
+    /// Capture the variable, for storing in a [`CapturingBlock`].
+    ///
+    /// Blocks that capture the variable can only be copied to the heap if
+    /// `T: 'static`, since the value is then moved to the heap as well.
+    ///
+    /// [`CapturingBlock`]: crate::CapturingBlock
+    pub fn capture(&self) -> ByRef<'_, T> {
+        ByRef {
+            ptr: self.ptr.as_ptr(),
+            p: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static> BlockByRef<T> {
+    /// A pointer to the `__block` variable structure, suitable for passing
+    /// to `_Block_object_assign` with `BLOCK_FIELD_IS_BYREF`.
+    ///
+    /// Doing so moves the value to the heap, where it may outlive `self`, so
+    /// the value must be `'static`.
+    pub fn as_byref_ptr(&self) -> *mut ffi::Block_byref {
+        self.ptr.as_ptr().cast()
+    }
+}
+
+impl<T: Copy> BlockByRef<T> {
+    /// Get a copy of the current value.
+    pub fn get(&self) -> T {
+        // SAFETY: The value is initialized, and `Copy`.
+        unsafe { *self.as_ptr() }
+    }
+
+    /// Set the value.
+    pub fn set(&self, value: T) {
+        // SAFETY: The value is `Copy`, so we don't have to drop the old one.
+        unsafe { *self.as_ptr() = value }
+    }
+}
+
+impl<T> Drop for BlockByRef<T> {
+    fn drop(&mut self) {
+        let ptr = self.ptr.as_ptr();
+        // SAFETY: The storage is valid, and the runtime only frees the heap
+        // copy, not the storage itself.
+        unsafe {
+            let forwarding: *mut ByRefStorage<T> = (*ptr).byref.header.forwarding.cast();
+            // Release our reference to the heap copy, like Clang does at the
+            // end of the scope of a `__block` variable. Does nothing if the
+            // variable wasn't copied.
+            ffi::_Block_object_dispose(ptr.cast(), ffi::BLOCK_FIELD_IS_BYREF);
+            if forwarding == ptr {
+                // The value was never moved to the heap
+                ManuallyDrop::drop(&mut (*ptr).value);
+            }
+            drop(Box::from_raw(ptr));
+        }
+    }
+}
+
+/// A captured [`BlockByRef`].
+///
+/// When the block containing this is copied, the copy refers to the shared
+/// heap allocation instead, which keeps the value alive for as long as the
+/// copy is.
+#[repr(transparent)]
+pub struct ByRef<'a, T> {
+    pub(crate) ptr: *mut ByRefStorage<T>,
+    p: PhantomData<&'a BlockByRef<T>>,
+}
+
+impl<T> ByRef<'_, T> {
+    /// A pointer to the current location of the value.
+    pub fn as_ptr(&self) -> *mut T {
+        // SAFETY: The storage is either borrowed from the `BlockByRef`, or
+        // retained by the block containing this.
+        unsafe { ByRefStorage::value_ptr(self.ptr) }
+    }
+}
+
+impl<T: Copy> ByRef<'_, T> {
+    /// Get a copy of the current value.
+    pub fn get(&self) -> T {
+        // SAFETY: The value is initialized, and `Copy`.
+        unsafe { *self.as_ptr() }
+    }
+
+    /// Set the value.
+    pub fn set(&self, value: T) {
+        // SAFETY: The value is `Copy`, so we don't have to drop the old one.
+        unsafe { *self.as_ptr() = value }
+    }
+}
@@ -0,0 +1,749 @@
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr;
+use std::os::raw::c_ulong;
+
+use objc2::encode::__unstable::EncodeReturn;
+use objc2::encode::{Encode, Encoding, RefEncode};
+#[cfg(feature = "objc2-runtime")]
+use objc2::rc::{Id, Shared, WeakId};
+#[cfg(feature = "objc2-runtime")]
+use objc2::Message;
+
+use crate::signature::Signature;
+use crate::{ffi, Block, BlockArguments, ByRef, RcBlock};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The kind of a captured value in the block's extended layout.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum __CaptureKind {
+    Strong,
+    ByRef,
+    NonObject,
+}
+
+/// Values that may be captured by a [`CapturingBlock`].
+///
+/// Like the variables that Clang captures in blocks, these are copied and
+/// disposed using `_Block_object_assign` and `_Block_object_dispose` when
+/// the block is copied to or released from the heap, and are described in
+/// the block's extended layout.
+///
+/// This is implemented for:
+/// - [`Id<T, Shared>`][objc2::rc::Id], which is captured strongly, like an object pointer
+///   (`BLOCK_FIELD_IS_OBJECT`).
+/// - [`RcBlock`], which is captured like a nested block
+///   (`BLOCK_FIELD_IS_BLOCK`).
+/// - [`WeakId`][objc2::rc::WeakId], which is copied with `objc_copyWeak`, like a `__weak`
+///   variable.
+/// - [`ByRef`], which is shared between the block copies, like a `__block`
+///   variable (`BLOCK_FIELD_IS_BYREF`).
+///
+/// Capturing objects requires the `objc2-runtime` feature, which is not
+/// available with the `compiler-rt` runtime, since that doesn't retain
+/// objects.
+///
+///
+/// # Safety
+///
+/// This is a sealed trait, and should not need to be implemented. Open an
+/// issue if you know a use-case where this restrition should be lifted!
+pub unsafe trait BlockCapture: private::Sealed + Sized {
+    #[doc(hidden)]
+    const __KIND: __CaptureKind;
+
+    /// The parts of `Self` that must be `'static` for the block to be
+    /// copied to the heap.
+    ///
+    /// This is `Self`, except for [`ByRef`], where it is only the value,
+    /// since the heap copy keeps the variable alive on its own, so the
+    /// borrow of the [`BlockByRef`] may end earlier.
+    ///
+    /// [`BlockByRef`]: crate::BlockByRef
+    #[doc(hidden)]
+    type __Escaping;
+
+    /// Called after the runtime has copied the bytes of `src` to `dst`.
+    #[doc(hidden)]
+    unsafe fn __copy(dst: *mut Self, src: *const Self);
+
+    #[doc(hidden)]
+    unsafe fn __dispose(this: *mut Self);
+}
+
+#[cfg(feature = "objc2-runtime")]
+impl<T: Message> private::Sealed for Id<T, Shared> {}
+#[cfg(feature = "objc2-runtime")]
+unsafe impl<T: Message> BlockCapture for Id<T, Shared> {
+    const __KIND: __CaptureKind = __CaptureKind::Strong;
+    type __Escaping = Self;
+
+    unsafe fn __copy(dst: *mut Self, src: *const Self) {
+        // SAFETY: `Id` is `#[repr(transparent)]` over a pointer, and the
+        // object pointer is valid, so the runtime retains it.
+        unsafe {
+            let obj = Id::as_ptr(&*src);
+            ffi::_Block_object_assign(dst.cast(), obj.cast(), ffi::BLOCK_FIELD_IS_OBJECT)
+        }
+    }
+
+    unsafe fn __dispose(this: *mut Self) {
+        // SAFETY: Balances the retain in `__copy`.
+        unsafe {
+            let obj = Id::as_ptr(&*this);
+            ffi::_Block_object_dispose(obj.cast(), ffi::BLOCK_FIELD_IS_OBJECT)
+        }
+    }
+}
+
+impl<A, R> private::Sealed for RcBlock<A, R> {}
+unsafe impl<A, R> BlockCapture for RcBlock<A, R> {
+    const __KIND: __CaptureKind = __CaptureKind::Strong;
+    type __Escaping = Self;
+
+    unsafe fn __copy(dst: *mut Self, src: *const Self) {
+        // SAFETY: `RcBlock` is `#[repr(transparent)]` over a pointer, and
+        // the block is valid, so the runtime copies (retains) it.
+        unsafe {
+            let block = (*src).ptr;
+            ffi::_Block_object_assign(dst.cast(), block.cast(), ffi::BLOCK_FIELD_IS_BLOCK)
+        }
+    }
+
+    unsafe fn __dispose(this: *mut Self) {
+        // SAFETY: Balances the copy in `__copy`.
+        unsafe { ffi::_Block_object_dispose((*this).ptr.cast(), ffi::BLOCK_FIELD_IS_BLOCK) }
+    }
+}
+
+#[cfg(feature = "objc2-runtime")]
+impl<T: Message> private::Sealed for WeakId<T> {}
+#[cfg(feature = "objc2-runtime")]
+unsafe impl<T: Message> BlockCapture for WeakId<T> {
+    // `WeakId` stores the weak pointer in a separate allocation
+    const __KIND: __CaptureKind = __CaptureKind::NonObject;
+    type __Escaping = Self;
+
+    unsafe fn __copy(dst: *mut Self, src: *const Self) {
+        // The bytes in `dst` are a bitwise copy of `src`, so overwrite them
+        // without dropping.
+        unsafe { ptr::write(dst, (*src).clone()) }
+    }
+
+    unsafe fn __dispose(this: *mut Self) {
+        unsafe { ptr::drop_in_place(this) }
+    }
+}
+
+impl<T> private::Sealed for ByRef<'_, T> {}
+unsafe impl<T> BlockCapture for ByRef<'_, T> {
+    const __KIND: __CaptureKind = __CaptureKind::ByRef;
+    // The value is moved to the heap, and may outlive the borrow
+    type __Escaping = T;
+
+    unsafe fn __copy(dst: *mut Self, src: *const Self) {
+        // SAFETY: `ByRef` is `#[repr(transparent)]` over a pointer to the
+        // variable, so the runtime moves it to the heap (if it isn't
+        // already), and stores a retained pointer to the heap copy.
+        unsafe {
+            ffi::_Block_object_assign(dst.cast(), (*src).ptr.cast(), ffi::BLOCK_FIELD_IS_BYREF)
+        }
+    }
+
+    unsafe fn __dispose(this: *mut Self) {
+        // SAFETY: Balances the retain in `__copy`.
+        unsafe { ffi::_Block_object_dispose((*this).ptr.cast(), ffi::BLOCK_FIELD_IS_BYREF) }
+    }
+}
+
+/// Tuples of [`BlockCapture`] values, that a [`CapturingBlock`] can store.
+///
+/// This is implemented for tuples of up to 8 values.
+///
+///
+/// # Safety
+///
+/// This is a sealed trait, and should not need to be implemented. Open an
+/// issue if you know a use-case where this restrition should be lifted!
+pub unsafe trait BlockCaptures: private::Sealed + Sized {
+    /// The [`BlockCapture::__Escaping`] of each captured value.
+    #[doc(hidden)]
+    type __Escaping;
+
+    #[doc(hidden)]
+    unsafe fn __copy(dst: *mut Self, src: *const Self);
+
+    #[doc(hidden)]
+    unsafe fn __dispose(this: *mut Self);
+
+    /// The kind of each captured value, in the order they are laid out in
+    /// memory (which may differ from the order in the tuple).
+    #[doc(hidden)]
+    const __KINDS: &'static [__CaptureKind];
+}
+
+macro_rules! block_captures_impl {
+    ($n:literal; $($i:tt: $t:ident),*) => {
+        impl<$($t: BlockCapture),*> private::Sealed for ($($t,)*) {}
+
+        unsafe impl<$($t: BlockCapture),*> BlockCaptures for ($($t,)*) {
+            type __Escaping = ($($t::__Escaping,)*);
+
+            #[allow(unused_variables)]
+            unsafe fn __copy(dst: *mut Self, src: *const Self) {
+                $(unsafe {
+                    $t::__copy(ptr::addr_of_mut!((*dst).$i), ptr::addr_of!((*src).$i))
+                };)*
+            }
+
+            #[allow(unused_variables)]
+            unsafe fn __dispose(this: *mut Self) {
+                $(unsafe { $t::__dispose(ptr::addr_of_mut!((*this).$i)) };)*
+            }
+
+            #[allow(unused_comparisons)]
+            const __KINDS: &'static [__CaptureKind] = &{
+                // Every capture is a single pointer, so the tuple can be
+                // viewed as an array of words, where each word stores its
+                // own index. Reading each field then gives its position.
+                assert!(mem::size_of::<Self>() == $n * mem::size_of::<usize>());
+                let mut words = [0usize; $n];
+                let mut i = 0;
+                while i < $n {
+                    words[i] = i;
+                    i += 1;
+                }
+                #[allow(unused_variables)]
+                let this: *const Self = (&words as *const [usize; $n]).cast();
+                #[allow(unused_mut)]
+                let mut kinds = [__CaptureKind::NonObject; $n];
+                $(
+                    // SAFETY: The field is a pointer-sized value at a word
+                    // offset within `words`.
+                    kinds[unsafe { *ptr::addr_of!((*this).$i).cast::<usize>() }] = $t::__KIND;
+                )*
+                kinds
+            };
+        }
+    };
+}
+
+block_captures_impl!(0;);
+block_captures_impl!(1; 0: A);
+block_captures_impl!(2; 0: A, 1: B);
+block_captures_impl!(3; 0: A, 1: B, 2: C);
+block_captures_impl!(4; 0: A, 1: B, 2: C, 3: D);
+block_captures_impl!(5; 0: A, 1: B, 2: C, 3: D, 4: E);
+block_captures_impl!(6; 0: A, 1: B, 2: C, 3: D, 4: E, 5: F);
+block_captures_impl!(7; 0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G);
+block_captures_impl!(8; 0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H);
+
+/// Whether any of the captured values are objects or `__block` variables.
+#[cfg(feature = "apple")]
+const fn has_objects(kinds: &[__CaptureKind]) -> bool {
+    let mut i = 0;
+    while i < kinds.len() {
+        if !matches!(kinds[i], __CaptureKind::NonObject) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// The capacity of [`extended_layout`].
+///
+/// At most 8 values are captured, each of which needs at most one operator
+/// (every operator but the first describes a pointer-sized capture, which
+/// is at most 16 bytes of non-object data), plus the padding before the
+/// captures and the terminator.
+#[cfg(feature = "apple")]
+const EXTENDED_LAYOUT_CAPACITY: usize = 2 * 8 + 1;
+
+/// Build the extended layout string for the captured values.
+///
+/// `start` is the number of bytes between the `Block_layout` header and the
+/// captures, and the string describes the data after the header. Only
+/// meaningful if [`has_objects`], since Clang doesn't emit an extended
+/// layout otherwise.
+///
+/// The rest of the array is filled with `BLOCK_LAYOUT_ESCAPE`, which
+/// terminates the string.
+#[cfg(feature = "apple")]
+const fn extended_layout(start: usize, kinds: &[__CaptureKind]) -> [u8; EXTENDED_LAYOUT_CAPACITY] {
+    let mut res = [ffi::BLOCK_LAYOUT_ESCAPE; EXTENDED_LAYOUT_CAPACITY];
+    let mut len = 0;
+
+    // Push `count` words or bytes, the parameter of each operator is at
+    // most 16.
+    macro_rules! push_op {
+        ($op:expr, $count:expr) => {{
+            let mut count = $count;
+            while count > 0 {
+                let n = if count < 16 { count } else { 16 };
+                res[len] = ($op << 4) | (n - 1) as u8;
+                len += 1;
+                count -= n;
+            }
+        }};
+    }
+
+    // The number of non-object bytes that have not been described yet
+    let mut gap = start;
+    // The operator and count of consecutive captures of the same kind
+    let mut run_op = ffi::BLOCK_LAYOUT_ESCAPE;
+    let mut run_count = 0;
+
+    let mut i = 0;
+    while i < kinds.len() {
+        let op = match kinds[i] {
+            __CaptureKind::Strong => ffi::BLOCK_LAYOUT_STRONG,
+            __CaptureKind::ByRef => ffi::BLOCK_LAYOUT_BYREF,
+            __CaptureKind::NonObject => {
+                gap += mem::size_of::<usize>();
+                i += 1;
+                continue;
+            }
+        };
+        if gap > 0 {
+            push_op!(run_op, run_count);
+            run_count = 0;
+            push_op!(ffi::BLOCK_LAYOUT_NON_OBJECT_BYTES, gap);
+            gap = 0;
+        }
+        if run_op != op {
+            push_op!(run_op, run_count);
+            run_op = op;
+            run_count = 0;
+        }
+        run_count += 1;
+        i += 1;
+    }
+    push_op!(run_op, run_count);
+
+    // The rest is non-object data, and the string is already terminated
+    res
+}
+
+/// A descriptor followed by the extended layout, which requires
+/// `BLOCK_HAS_EXTENDED_LAYOUT`.
+#[cfg(feature = "apple")]
+#[repr(C)]
+struct DescriptorWithLayout {
+    descriptor: ffi::Block_descriptor_with_signature,
+    layout: *const u8,
+}
+
+mod into_private {
+    pub trait Sealed<C, A> {}
+}
+
+/// Types that may be converted into a [`CapturingBlock`].
+///
+/// This is implemented for [`Fn`] closures of up to 12 arguments (in
+/// addition to the captures), where each argument and the return type
+/// implements [`Encode`], and where the closure is [`Copy`].
+///
+///
+/// # Safety
+///
+/// This is a sealed trait, and should not need to be implemented. Open an
+/// issue if you know a use-case where this restrition should be lifted!
+pub unsafe trait IntoCapturingBlock<C: BlockCaptures, A: BlockArguments>:
+    into_private::Sealed<C, A> + Sized
+{
+    /// The return type of the resulting `CapturingBlock`.
+    type Output: EncodeReturn;
+
+    #[doc(hidden)]
+    fn __into_capturing_block(self, captures: C) -> CapturingBlock<A, Self::Output, C, Self>;
+}
+
+macro_rules! capturing_block_impl {
+    ($f:ident) => (
+        capturing_block_impl!($f,);
+    );
+    ($f:ident, $($a:ident : $t:ident),*) => (
+        impl<Cap, $($t: Encode,)* R: EncodeReturn, X> into_private::Sealed<Cap, ($($t,)*)> for X
+        where
+            Cap: BlockCaptures,
+            X: Fn(&Cap, $($t,)*) -> R + Copy,
+        {}
+
+        unsafe impl<Cap, $($t: Encode,)* R: EncodeReturn, X> IntoCapturingBlock<Cap, ($($t,)*)> for X
+        where
+            Cap: BlockCaptures,
+            X: Fn(&Cap, $($t,)*) -> R + Copy,
+        {
+            type Output = R;
+
+            fn __into_capturing_block(self, captures: Cap) -> CapturingBlock<($($t,)*), R, Cap, X> {
+                extern "C" fn $f<Cap, $($t,)* R, X>(
+                    block: &CapturingBlock<($($t,)*), R, Cap, X>,
+                    $($a: $t,)*
+                ) -> R
+                where
+                    X: Fn(&Cap, $($t,)*) -> R,
+                {
+                    (block.closure)(&block.captures, $($a),*)
+                }
+
+                let f: extern "C" fn(&CapturingBlock<($($t,)*), R, Cap, X>, $($a: $t,)*) -> R = $f;
+                let f: unsafe extern "C" fn() = unsafe { mem::transmute(f) };
+                unsafe { CapturingBlock::with_invoke(f, captures, self) }
+            }
+        }
+    );
+}
+
+capturing_block_impl!(capturing_block_invoke_args0);
+capturing_block_impl!(capturing_block_invoke_args1, a: A);
+capturing_block_impl!(capturing_block_invoke_args2, a: A, b: B);
+capturing_block_impl!(capturing_block_invoke_args3, a: A, b: B, c: C);
+capturing_block_impl!(capturing_block_invoke_args4, a: A, b: B, c: C, d: D);
+capturing_block_impl!(capturing_block_invoke_args5, a: A, b: B, c: C, d: D, e: E);
+capturing_block_impl!(
+    capturing_block_invoke_args6,
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+    e: E,
+    f: F
+);
+capturing_block_impl!(
+    capturing_block_invoke_args7,
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+    e: E,
+    f: F,
+    g: G
+);
+capturing_block_impl!(
+    capturing_block_invoke_args8,
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+    e: E,
+    f: F,
+    g: G,
+    h: H
+);
+capturing_block_impl!(
+    capturing_block_invoke_args9,
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+    e: E,
+    f: F,
+    g: G,
+    h: H,
+    i: I
+);
+capturing_block_impl!(
+    capturing_block_invoke_args10,
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+    e: E,
+    f: F,
+    g: G,
+    h: H,
+    i: I,
+    j: J
+);
+capturing_block_impl!(
+    capturing_block_invoke_args11,
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+    e: E,
+    f: F,
+    g: G,
+    h: H,
+    i: I,
+    j: J,
+    k: K
+);
+capturing_block_impl!(
+    capturing_block_invoke_args12,
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+    e: E,
+    f: F,
+    g: G,
+    h: H,
+    i: I,
+    j: J,
+    k: K,
+    l: L
+);
+
+/// An Objective-C block that captures Objective-C objects and other blocks
+/// the same way that blocks created by Clang do.
+///
+/// A [`ConcreteBlock`] moves its closure to the heap when copied, and
+/// doesn't tell the runtime about the objects it contains. In contrast, the
+/// captures of this block are retained through `_Block_object_assign` when
+/// the block is copied, and released through `_Block_object_dispose` when
+/// the copy is released, so it can be copied and inspected by Objective-C
+/// code just like any other block.
+///
+/// The closure itself must be [`Copy`], and receives a reference to the
+/// captured values as its first argument.
+///
+/// [`ConcreteBlock`]: crate::ConcreteBlock
+///
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "objc2-runtime")]
+/// # fn main() {
+/// use block2::CapturingBlock;
+/// use objc2::rc::{Id, Shared};
+/// use objc2::runtime::{NSObject, NSObjectProtocol};
+///
+/// let obj: Id<NSObject, Shared> = NSObject::new().into();
+/// let block = CapturingBlock::new((obj,), |(obj,): &(Id<NSObject, Shared>,), x: i32| {
+///     assert!(obj.is_kind_of::<NSObject>());
+///     x + 1
+/// });
+/// assert_eq!(unsafe { block.call((1,)) }, 2);
+///
+/// // Retains `obj`, like copying a Clang block would
+/// let block = block.copy();
+/// assert_eq!(unsafe { block.call((2,)) }, 3);
+/// # }
+/// # #[cfg(not(feature = "objc2-runtime"))]
+/// # fn main() {}
+/// ```
+#[repr(C)]
+pub struct CapturingBlock<A, R, C, F> {
+    p: PhantomData<Block<A, R>>,
+    layout: ffi::Block_layout,
+    captures: C,
+    closure: F,
+}
+
+unsafe impl<A: BlockArguments, R: EncodeReturn, C, F> RefEncode for CapturingBlock<A, R, C, F> {
+    const ENCODING_REF: Encoding = Encoding::Block;
+}
+
+impl<A, R, C, F> CapturingBlock<A, R, C, F>
+where
+    A: BlockArguments,
+    R: EncodeReturn,
+    C: BlockCaptures,
+    F: IntoCapturingBlock<C, A, Output = R>,
+{
+    /// Constructs a `CapturingBlock` with the given captures and closure.
+    ///
+    /// When the block is called, it will return the value that results from
+    /// calling the closure with a reference to the captures.
+    pub fn new(captures: C, closure: F) -> Self {
+        closure.__into_capturing_block(captures)
+    }
+}
+
+impl<A: BlockArguments, R: EncodeReturn, C: BlockCaptures, F> CapturingBlock<A, R, C, F> {
+    /// The number of bytes between the `Block_layout` header and the
+    /// captures.
+    #[cfg(feature = "apple")]
+    const CAPTURES_PADDING: usize = {
+        let header = mem::size_of::<ffi::Block_layout>();
+        let align = mem::align_of::<C>();
+        (header + align - 1) / align * align - header
+    };
+
+    const FLAGS: ffi::block_flags = {
+        #[allow(unused_mut)]
+        let mut flags = Signature::<A, R>::FLAGS | ffi::BLOCK_HAS_COPY_DISPOSE;
+        #[cfg(feature = "apple")]
+        if has_objects(C::__KINDS) {
+            flags |= ffi::BLOCK_HAS_EXTENDED_LAYOUT;
+        }
+        flags
+    };
+
+    const DESCRIPTOR_WITH_SIGNATURE: ffi::Block_descriptor_with_signature =
+        ffi::Block_descriptor_with_signature {
+            header: ffi::Block_descriptor_header {
+                reserved: 0,
+                size: mem::size_of::<Self>() as c_ulong,
+            },
+            copy: Some(block_captures_copy::<Self>),
+            dispose: Some(block_captures_dispose::<Self>),
+            encoding: Signature::<A, R>::ENCODING,
+        };
+
+    #[cfg(feature = "apple")]
+    const EXTENDED_LAYOUT: &'static [u8; EXTENDED_LAYOUT_CAPACITY] =
+        &extended_layout(Self::CAPTURES_PADDING, C::__KINDS);
+
+    #[cfg(feature = "apple")]
+    const DESCRIPTOR: &'static DescriptorWithLayout = &DescriptorWithLayout {
+        descriptor: Self::DESCRIPTOR_WITH_SIGNATURE,
+        layout: if has_objects(C::__KINDS) {
+            Self::EXTENDED_LAYOUT.as_ptr()
+        } else {
+            ptr::null()
+        },
+    };
+
+    #[cfg(not(feature = "apple"))]
+    const DESCRIPTOR: &'static ffi::Block_descriptor_with_signature =
+        &Self::DESCRIPTOR_WITH_SIGNATURE;
+
+    /// Unsafe because the caller must ensure the invoke function takes the
+    /// correct arguments.
+    unsafe fn with_invoke(invoke: unsafe extern "C" fn(), captures: C, closure: F) -> Self {
+        let descriptor: *const _ = Self::DESCRIPTOR;
+        let layout = ffi::Block_layout {
+            isa: unsafe { &ffi::_NSConcreteStackBlock },
+            flags: Self::FLAGS,
+            reserved: 0,
+            invoke: Some(invoke),
+            descriptor: descriptor.cast(),
+        };
+        Self {
+            p: PhantomData,
+            layout,
+            captures,
+            closure,
+        }
+    }
+}
+
+impl<A, R, C: BlockCaptures, F: 'static> CapturingBlock<A, R, C, F>
+where
+    C::__Escaping: 'static,
+{
+    /// Copy self onto the heap as an `RcBlock`.
+    ///
+    /// The captures are retained by the copy, and `self` is dropped as usual.
+    ///
+    /// The captures must be `'static`, except for the borrow of a
+    /// [`BlockByRef`](crate::BlockByRef), since the copy keeps the variable
+    /// alive (the value of the variable must still be `'static`).
+    pub fn copy(&self) -> RcBlock<A, R> {
+        let ptr: *const Self = self;
+        unsafe { RcBlock::copy(ptr as *mut Block<A, R>) }
+    }
+}
+
+impl<A, R, C, F> Deref for CapturingBlock<A, R, C, F> {
+    type Target = Block<A, R>;
+
+    fn deref(&self) -> &Self::Target {
+        let ptr: *const Self = self;
+        let ptr: *const Block<A, R> = ptr.cast();
+        // SAFETY: The pointer comes from a reference, so it is non-null and
+        // valid for as long as `self` is borrowed. `CapturingBlock` is
+        // `#[repr(C)]` and starts with a `Block_layout` (`PhantomData` is
+        // zero-sized), which is what `Block` is a view of, and `Block` is
+        // zero-sized with an alignment of 1, so the cast pointer is aligned.
+        // The layout was initialized in `with_invoke` with the invoke
+        // function and descriptor matching `A` and `R`.
+        unsafe { ptr.as_ref().unwrap_unchecked() }
+    }
+}
+
+unsafe extern "C" fn block_captures_copy<B: HasCaptures>(dst: *mut c_void, src: *mut c_void) {
+    unsafe { B::Captures::__copy(B::captures(dst.cast()), B::captures(src.cast())) }
+}
+
+unsafe extern "C" fn block_captures_dispose<B: HasCaptures>(block: *mut c_void) {
+    unsafe { B::Captures::__dispose(B::captures(block.cast())) }
+}
+
+/// Helper for finding the captures in the copy and dispose helpers.
+trait HasCaptures {
+    type Captures: BlockCaptures;
+
+    unsafe fn captures(this: *mut Self) -> *mut Self::Captures;
+}
+
+impl<A, R, C: BlockCaptures, F> HasCaptures for CapturingBlock<A, R, C, F> {
+    type Captures = C;
+
+    unsafe fn captures(this: *mut Self) -> *mut C {
+        unsafe { ptr::addr_of_mut!((*this).captures) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kinds() {
+        use __CaptureKind::{NonObject, Strong};
+
+        assert_eq!(<()>::__KINDS, []);
+        assert_eq!(<(RcBlock<(), ()>,)>::__KINDS, [Strong]);
+        // The order of the kinds matches the memory layout
+        type Mixed<'a> = (ByRef<'a, i32>, RcBlock<(), ()>, ByRef<'a, u8>);
+        let mut kinds = [NonObject; 3];
+        let this = mem::MaybeUninit::<Mixed<'_>>::uninit();
+        let base = this.as_ptr();
+        let offsets = unsafe {
+            [
+                ptr::addr_of!((*base).0) as usize - base as usize,
+                ptr::addr_of!((*base).1) as usize - base as usize,
+                ptr::addr_of!((*base).2) as usize - base as usize,
+            ]
+        };
+        let word = mem::size_of::<usize>();
+        kinds[offsets[0] / word] = __CaptureKind::ByRef;
+        kinds[offsets[1] / word] = Strong;
+        kinds[offsets[2] / word] = __CaptureKind::ByRef;
+        assert_eq!(Mixed::__KINDS, kinds);
+    }
+
+    #[test]
+    #[cfg(feature = "apple")]
+    fn test_extended_layout() {
+        use __CaptureKind::*;
+        const WORD: usize = mem::size_of::<usize>();
+
+        fn layout(start: usize, kinds: &[__CaptureKind]) -> alloc::vec::Vec<u8> {
+            assert!(has_objects(kinds));
+            let res = extended_layout(start, kinds);
+            let len = res
+                .iter()
+                .position(|op| *op == ffi::BLOCK_LAYOUT_ESCAPE)
+                .unwrap();
+            res[..=len].to_vec()
+        }
+
+        assert!(!has_objects(&[]));
+        assert!(!has_objects(&[NonObject]));
+        assert_eq!(layout(0, &[Strong]), [0x30, 0x00]);
+        assert_eq!(layout(0, &[Strong, Strong]), [0x31, 0x00]);
+        assert_eq!(
+            layout(0, &[Strong, NonObject, Strong]),
+            [0x30, 0x10 | (WORD - 1) as u8, 0x30, 0x00]
+        );
+        assert_eq!(layout(0, &[Strong, ByRef, ByRef]), [0x30, 0x41, 0x00]);
+        assert_eq!(
+            layout(4, &[NonObject, ByRef]),
+            [0x10 | (WORD + 3) as u8, 0x40, 0x00]
+        );
+        assert_eq!(layout(0, &[Strong; 17]), [0x3f, 0x30, 0x00]);
+    }
+}
@@ -0,0 +1,299 @@
+//! Bridging completion handlers to Rust's [`Future`]s.
+//!
+//! Many Objective-C APIs take a block as their last argument, which is
+//! called exactly once when the operation finishes (a "completion handler").
+//! The functions in this module create such a block along with a
+//! [`Future`], which resolves when the block is called.
+//!
+//!
+//! # Example
+//!
+//! ```
+//! use block2::completion;
+//!
+//! # async fn example() {
+//! let (block, completion) = completion::handler(|value: i32| value * 2);
+//!
+//! // Pass `&*block` to the method taking the completion handler; the block
+//! // is called once the method finishes.
+//! unsafe { block.call((21,)) };
+//!
+//! assert_eq!(completion.await, 42);
+//! # }
+//! ```
+use alloc::sync::Arc;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::sync::{Mutex, PoisonError};
+
+use objc2::encode::Encode;
+#[cfg(feature = "objc2-runtime")]
+use objc2::rc::{Id, Shared};
+#[cfg(feature = "objc2-runtime")]
+use objc2::Message;
+
+use crate::{BlockArguments, RcBlock};
+
+struct State<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    /// The block has been called or dropped.
+    closed: bool,
+}
+
+type Channel<T> = Arc<Mutex<State<T>>>;
+
+fn lock<T>(channel: &Channel<T>) -> std::sync::MutexGuard<'_, State<T>> {
+    channel.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The sending half, stored in the block.
+#[doc(hidden)]
+pub struct Sender<T> {
+    channel: Channel<T>,
+}
+
+impl<T> Sender<T> {
+    fn send(self, value: T) {
+        lock(&self.channel).value = Some(value);
+        // Closes the channel and wakes the task
+        drop(self);
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = lock(&self.channel);
+            state.closed = true;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// A [`Future`] that resolves with the value produced when the completion
+/// handler is called.
+///
+/// See the [module-level documentation](self) for details.
+///
+///
+/// # Panics
+///
+/// Polling the future panics if the completion handler was dropped without
+/// being called.
+pub struct Completion<T> {
+    channel: Channel<T>,
+}
+
+// The value is never pinned
+impl<T> Unpin for Completion<T> {}
+
+impl<T> Future for Completion<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = lock(&self.channel);
+        if let Some(value) = state.value.take() {
+            Poll::Ready(value)
+        } else if state.closed {
+            panic!("completion handler was dropped without being called")
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> fmt::Debug for Completion<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = lock(&self.channel);
+        f.debug_struct("Completion")
+            .field("ready", &state.value.is_some())
+            .field("closed", &state.closed)
+            .finish_non_exhaustive()
+    }
+}
+
+fn channel<T>() -> (Sender<T>, Completion<T>) {
+    let channel = Arc::new(Mutex::new(State {
+        value: None,
+        waker: None,
+        closed: false,
+    }));
+    let sender = Sender {
+        channel: channel.clone(),
+    };
+    (sender, Completion { channel })
+}
+
+mod private {
+    pub trait Sealed<A> {}
+}
+
+/// Types that may be used with [`handler`].
+///
+/// This is implemented for [`FnOnce`] closures of up to 12 arguments, where
+/// each argument implements [`Encode`], and where the closure and its
+/// return value are [`Send`] (since completion handlers are often called on
+/// a different thread).
+///
+///
+/// # Safety
+///
+/// This is a sealed trait, and should not need to be implemented. Open an
+/// issue if you know a use-case where this restrition should be lifted!
+pub unsafe trait IntoCompletionHandler<A: BlockArguments>:
+    private::Sealed<A> + Sized
+{
+    /// The value that the [`Completion`] resolves with.
+    type Output;
+
+    #[doc(hidden)]
+    fn __into_completion_handler(self, sender: Sender<Self::Output>) -> RcBlock<A, ()>;
+}
+
+macro_rules! completion_handler_impl {
+    ($($a:ident : $t:ident),*) => (
+        impl<$($t: Encode,)* R, X> private::Sealed<($($t,)*)> for X
+        where
+            X: FnOnce($($t,)*) -> R + Send + 'static,
+            R: Send,
+        {}
+
+        unsafe impl<$($t: Encode,)* R: Send + 'static, X> IntoCompletionHandler<($($t,)*)> for X
+        where
+            X: FnOnce($($t,)*) -> R + Send + 'static,
+        {
+            type Output = R;
+
+            fn __into_completion_handler(self, sender: Sender<R>) -> RcBlock<($($t,)*), ()> {
+                RcBlock::new_once(move |$($a: $t),*| sender.send(self($($a),*)))
+            }
+        }
+    );
+}
+
+completion_handler_impl!();
+completion_handler_impl!(a: A);
+completion_handler_impl!(a: A, b: B);
+completion_handler_impl!(a: A, b: B, c: C);
+completion_handler_impl!(a: A, b: B, c: C, d: D);
+completion_handler_impl!(a: A, b: B, c: C, d: D, e: E);
+completion_handler_impl!(a: A, b: B, c: C, d: D, e: E, f: F);
+completion_handler_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G);
+completion_handler_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H);
+completion_handler_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I);
+completion_handler_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J);
+completion_handler_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J, k: K);
+completion_handler_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J, k: K, l: L);
+
+/// Create a completion handler, and a [`Completion`] that resolves with the
+/// value that the given closure returns when the handler is called.
+///
+/// The closure is called with the arguments of the block, and should
+/// convert them to owned values; in particular, objects passed to completion
+/// handlers are usually only valid for the duration of the call, and must be
+/// retained (e.g. with [`Id::retain`][objc2::rc::Id::retain]) if they are to be used afterwards.
+///
+/// The block aborts the process if it is called more than once, see
+/// [`RcBlock::new_once`].
+pub fn handler<A, F>(closure: F) -> (RcBlock<A, ()>, Completion<F::Output>)
+where
+    A: BlockArguments,
+    F: IntoCompletionHandler<A>,
+{
+    let (sender, completion) = channel();
+    (closure.__into_completion_handler(sender), completion)
+}
+
+/// Create a completion handler for the common `(T *result, NSError *error)`
+/// pattern.
+///
+/// Like methods with an `NSError **` parameter in `objc2`, the result is
+/// considered successful if it is not `NULL`, otherwise the error must be
+/// set.
+///
+///
+/// # Panics
+///
+/// Polling the future panics if both the result and the error are `NULL`.
+#[cfg(feature = "objc2-runtime")]
+pub fn result_handler<T, E>() -> (ResultHandler<T, E>, ResultCompletion<T, E>)
+where
+    T: Message + Send + Sync + 'static,
+    E: Message + Send + Sync + 'static,
+{
+    let (block, completion) = handler(|res: *mut T, err: *mut E| {
+        // SAFETY: The objects passed to completion handlers are valid, and
+        // it is safe to retain them.
+        if let Some(res) = unsafe { Id::retain(res) } {
+            Ok(res)
+        } else {
+            Err(unsafe { Id::retain(err) })
+        }
+    });
+    (block, ResultCompletion { completion })
+}
+
+/// Create a completion handler for the `(NSError *error)` pattern, where
+/// the operation succeeded if the error is `NULL`.
+#[cfg(feature = "objc2-runtime")]
+pub fn error_handler<E>() -> (ErrorHandler<E>, ErrorCompletion<E>)
+where
+    E: Message + Send + Sync + 'static,
+{
+    handler(|err: *mut E| {
+        // SAFETY: The error passed to completion handlers is valid, and it
+        // is safe to retain.
+        match unsafe { Id::retain(err) } {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    })
+}
+
+/// The block type of a `(T *result, NSError *error)` completion handler.
+#[cfg(feature = "objc2-runtime")]
+pub type ResultHandler<T, E> = RcBlock<(*mut T, *mut E), ()>;
+
+/// The block type of a `(NSError *error)` completion handler.
+#[cfg(feature = "objc2-runtime")]
+pub type ErrorHandler<E> = RcBlock<(*mut E,), ()>;
+
+/// The [`Future`] returned by [`error_handler`].
+#[cfg(feature = "objc2-runtime")]
+pub type ErrorCompletion<E> = Completion<Result<(), Id<E, Shared>>>;
+
+/// The [`Future`] returned by [`result_handler`].
+///
+/// This resolves with the retained result, or the retained error.
+#[cfg(feature = "objc2-runtime")]
+pub struct ResultCompletion<T: Message, E: Message> {
+    #[allow(clippy::type_complexity)]
+    completion: Completion<Result<Id<T, Shared>, Option<Id<E, Shared>>>>,
+}
+
+#[cfg(feature = "objc2-runtime")]
+impl<T: Message, E: Message> Future for ResultCompletion<T, E> {
+    type Output = Result<Id<T, Shared>, Id<E, Shared>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.completion).poll(cx).map(|res| {
+            res.map_err(|err| err.expect("error parameter should be set if the result is NULL"))
+        })
+    }
+}
+
+#[cfg(feature = "objc2-runtime")]
+impl<T: Message, E: Message> fmt::Debug for ResultCompletion<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResultCompletion")
+            .field("completion", &self.completion)
+            .finish()
+    }
+}
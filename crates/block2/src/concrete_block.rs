@@ -8,6 +8,7 @@ use std::os::raw::c_ulong;
 use objc2::encode::__unstable::EncodeReturn;
 use objc2::encode::{Encode, Encoding, RefEncode};
 
+use crate::signature::Signature;
 use crate::{ffi, Block, BlockArguments, RcBlock};
 
 mod private {
@@ -185,29 +186,47 @@ where
     }
 }
 
-impl<A, R, F> ConcreteBlock<A, R, F> {
-    // TODO: Use new ABI with BLOCK_HAS_SIGNATURE
-    const FLAGS: ffi::block_flags = if mem::needs_drop::<Self>() {
-        ffi::BLOCK_HAS_COPY_DISPOSE
+impl<A: BlockArguments, R: EncodeReturn, F> ConcreteBlock<A, R, F> {
+    const FLAGS: ffi::block_flags = {
+        let mut flags = Signature::<A, R>::FLAGS;
+        if mem::needs_drop::<Self>() {
+            flags |= ffi::BLOCK_HAS_COPY_DISPOSE;
+        }
+        flags
+    };
+
+    const HEADER: ffi::Block_descriptor_header = ffi::Block_descriptor_header {
+        reserved: 0,
+        size: mem::size_of::<Self>() as c_ulong,
+    };
+
+    const DESCRIPTOR_WITH_COPY_DISPOSE: &'static ffi::Block_descriptor_with_signature =
+        &ffi::Block_descriptor_with_signature {
+            header: Self::HEADER,
+            copy: Some(block_context_copy::<Self>),
+            dispose: Some(block_context_dispose::<Self>),
+            encoding: Signature::<A, R>::ENCODING,
+        };
+
+    const DESCRIPTOR_BASIC: &'static ffi::Block_descriptor_basic = &ffi::Block_descriptor_basic {
+        header: Self::HEADER,
+        encoding: Signature::<A, R>::ENCODING,
+    };
+
+    const DESCRIPTOR: *const c_void = if mem::needs_drop::<Self>() {
+        (Self::DESCRIPTOR_WITH_COPY_DISPOSE as *const ffi::Block_descriptor_with_signature).cast()
     } else {
-        0
+        (Self::DESCRIPTOR_BASIC as *const ffi::Block_descriptor_basic).cast()
     };
 
-    const DESCRIPTOR: ffi::Block_descriptor = ffi::Block_descriptor {
-        header: ffi::Block_descriptor_header {
-            reserved: 0,
-            size: mem::size_of::<Self>() as c_ulong,
-        },
-        copy: if mem::needs_drop::<Self>() {
-            Some(block_context_copy::<Self>)
-        } else {
-            None
-        },
-        dispose: if mem::needs_drop::<Self>() {
-            Some(block_context_dispose::<Self>)
-        } else {
-            None
-        },
+    const NOESCAPE_FLAGS: ffi::block_flags = {
+        #[allow(unused_mut)]
+        let mut flags = Self::FLAGS & !ffi::BLOCK_HAS_COPY_DISPOSE;
+        #[cfg(feature = "apple")]
+        {
+            flags |= ffi::BLOCK_IS_NOESCAPE;
+        }
+        flags
     };
 
     /// Constructs a `ConcreteBlock` with the given invoke function and closure.
@@ -219,7 +238,7 @@ impl<A, R, F> ConcreteBlock<A, R, F> {
             flags: Self::FLAGS,
             reserved: 0,
             invoke: Some(invoke),
-            descriptor: &Self::DESCRIPTOR as *const ffi::Block_descriptor as *mut c_void,
+            descriptor: Self::DESCRIPTOR,
         };
         Self {
             p: PhantomData,
@@ -227,6 +246,14 @@ impl<A, R, F> ConcreteBlock<A, R, F> {
             closure,
         }
     }
+
+    /// Mark the block as never being copied, and remove the copy and
+    /// dispose helpers, since the closure is dropped with the block.
+    pub(crate) fn set_noescape(&mut self) {
+        self.layout.flags = Self::NOESCAPE_FLAGS;
+        let descriptor: *const ffi::Block_descriptor_basic = Self::DESCRIPTOR_BASIC;
+        self.layout.descriptor = descriptor.cast();
+    }
 }
 
 impl<A, R, F: 'static> ConcreteBlock<A, R, F> {
@@ -243,7 +270,18 @@ impl<A, R, F: 'static> ConcreteBlock<A, R, F> {
 
 impl<A, R, F: Clone> Clone for ConcreteBlock<A, R, F> {
     fn clone(&self) -> Self {
-        unsafe { Self::with_invoke(self.layout.invoke.unwrap(), self.closure.clone()) }
+        let layout = ffi::Block_layout {
+            isa: unsafe { &ffi::_NSConcreteStackBlock },
+            flags: self.layout.flags,
+            reserved: 0,
+            invoke: self.layout.invoke,
+            descriptor: self.layout.descriptor,
+        };
+        Self {
+            p: PhantomData,
+            layout,
+            closure: self.closure.clone(),
+        }
     }
 }
 
@@ -4,7 +4,9 @@ use core::fmt::{Debug, DebugStruct, Error, Formatter};
 use core::ptr;
 use std::ffi::CStr;
 
-use crate::{ffi, Block, ConcreteBlock, GlobalBlock, RcBlock};
+use crate::{
+    ffi, Block, ConcreteBlock, GlobalBlock, NoEscapeBlock, RcBlock, SafeBlock, ScopedBlock,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 struct Isa(*const ffi::Class);
@@ -71,6 +73,17 @@ impl<A, R> Debug for RcBlock<A, R> {
     }
 }
 
+impl<A, R> Debug for SafeBlock<A, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let mut f = f.debug_struct("SafeBlock");
+        let block: &Block<A, R> = self;
+        let ptr: *const Block<A, R> = block;
+        let layout = unsafe { ptr.cast::<ffi::Block_layout>().as_ref().unwrap() };
+        debug_block_layout(layout, &mut f);
+        f.finish_non_exhaustive()
+    }
+}
+
 impl<A, R, F: Debug> Debug for ConcreteBlock<A, R, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
         let mut f = f.debug_struct("ConcreteBlock");
@@ -80,6 +93,26 @@ impl<A, R, F: Debug> Debug for ConcreteBlock<A, R, F> {
     }
 }
 
+impl<A, R> Debug for NoEscapeBlock<A, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let mut f = f.debug_struct("NoEscapeBlock");
+        let ptr: *const Self = self;
+        let layout = unsafe { ptr.cast::<ffi::Block_layout>().as_ref().unwrap() };
+        debug_block_layout(layout, &mut f);
+        f.finish_non_exhaustive()
+    }
+}
+
+impl<A, R, F> Debug for ScopedBlock<A, R, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let mut f = f.debug_struct("ScopedBlock");
+        let ptr: *const Self = self;
+        let layout = unsafe { ptr.cast::<ffi::Block_layout>().as_ref().unwrap() };
+        debug_block_layout(layout, &mut f);
+        f.finish_non_exhaustive()
+    }
+}
+
 impl<A, R> Debug for GlobalBlock<A, R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
         let mut f = f.debug_struct("GlobalBlock");
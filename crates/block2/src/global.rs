@@ -1,4 +1,3 @@
-use core::ffi::c_void;
 use core::marker::PhantomData;
 use core::mem;
 use core::ops::Deref;
@@ -6,16 +5,12 @@ use core::ptr;
 use std::os::raw::c_ulong;
 
 use objc2::encode::__unstable::EncodeReturn;
+use objc2::encode::Encoding;
 
 use super::{ffi, Block};
+use crate::signature::{self, __signature_len};
 use crate::BlockArguments;
 
-// TODO: Should this be a static to help the compiler deduplicating them?
-const GLOBAL_DESCRIPTOR: ffi::Block_descriptor_header = ffi::Block_descriptor_header {
-    reserved: 0,
-    size: mem::size_of::<ffi::Block_layout>() as c_ulong,
-};
-
 /// An Objective-C block that does not capture its environment.
 ///
 /// This is effectively just a glorified function pointer, and can created and
@@ -45,14 +40,18 @@ where
 {
 }
 
-// Note: We can't put correct bounds on A and R because we have a const fn!
-//
-// Fortunately, we don't need them, since they're present on `Sync`, so
-// constructing the static in `global_block!` with an invalid `GlobalBlock`
-// triggers an error.
-impl<A, R> GlobalBlock<A, R> {
-    // TODO: Use new ABI with BLOCK_HAS_SIGNATURE
-    const FLAGS: ffi::block_flags = ffi::BLOCK_IS_GLOBAL | ffi::BLOCK_USE_STRET;
+impl<A, R> GlobalBlock<A, R>
+where
+    A: BlockArguments,
+    R: EncodeReturn,
+{
+    const FLAGS: ffi::block_flags = {
+        let mut flags = ffi::BLOCK_IS_GLOBAL | ffi::BLOCK_HAS_SIGNATURE;
+        if signature::use_stret(&R::ENCODING_RETURN, mem::size_of::<R>()) {
+            flags |= ffi::BLOCK_USE_STRET;
+        }
+        flags
+    };
 
     #[doc(hidden)]
     pub const __DEFAULT_LAYOUT: ffi::Block_layout = ffi::Block_layout {
@@ -62,9 +61,32 @@ impl<A, R> GlobalBlock<A, R> {
         reserved: 0,
         // Populated in `global_block!`
         invoke: None,
-        descriptor: &GLOBAL_DESCRIPTOR as *const ffi::Block_descriptor_header as *mut c_void,
+        // Populated in `global_block!`
+        descriptor: ptr::null(),
+    };
+
+    #[doc(hidden)]
+    pub const __DESCRIPTOR_HEADER: ffi::Block_descriptor_header = ffi::Block_descriptor_header {
+        reserved: 0,
+        size: mem::size_of::<ffi::Block_layout>() as c_ulong,
     };
 
+    #[doc(hidden)]
+    pub const __ENCODING_RETURN: Encoding = R::ENCODING_RETURN;
+
+    #[doc(hidden)]
+    pub const __ENCODING_ARGS: &'static [Encoding] = A::__ENCODINGS;
+
+    #[doc(hidden)]
+    pub const __SIGNATURE_LEN: usize = __signature_len(&R::ENCODING_RETURN, A::__ENCODINGS);
+}
+
+// Note: We can't put correct bounds on A and R because we have a const fn!
+//
+// Fortunately, we don't need them, since they're present on `Sync`, so
+// constructing the static in `global_block!` with an invalid `GlobalBlock`
+// triggers an error.
+impl<A, R> GlobalBlock<A, R> {
     /// Use the [`global_block`] macro instead.
     #[doc(hidden)]
     pub const unsafe fn from_layout(layout: ffi::Block_layout) -> Self {
@@ -170,8 +192,20 @@ macro_rules! global_block {
         $(#[$m])*
         #[allow(unused_unsafe)]
         $vis static $name: $crate::GlobalBlock<($($t,)*) $(, $r)?> = unsafe {
-            let mut layout = $crate::GlobalBlock::<($($t,)*) $(, $r)?>::__DEFAULT_LAYOUT;
+            type __Block = $crate::GlobalBlock<($($t,)*) $(, $r)?>;
+
+            const SIGNATURE: [u8; __Block::__SIGNATURE_LEN] = $crate::__signature_array(
+                &__Block::__ENCODING_RETURN,
+                __Block::__ENCODING_ARGS,
+            );
+            const DESCRIPTOR: $crate::ffi::Block_descriptor_basic = $crate::ffi::Block_descriptor_basic {
+                header: __Block::__DESCRIPTOR_HEADER,
+                encoding: (&SIGNATURE as *const [u8; __Block::__SIGNATURE_LEN]).cast(),
+            };
+
+            let mut layout = __Block::__DEFAULT_LAYOUT;
             layout.isa = &$crate::ffi::_NSConcreteGlobalBlock;
+            layout.descriptor = (&DESCRIPTOR as *const $crate::ffi::Block_descriptor_basic).cast();
             layout.invoke = ::core::option::Option::Some({
                 unsafe extern "C" fn inner(_: *mut $crate::ffi::Block_layout, $($a: $t),*) $(-> $r)? {
                     $body
@@ -219,7 +253,7 @@ mod tests {
 
     #[cfg(feature = "apple")]
     const DEBUG_BLOCKFLAGS: &str = r#"BlockFlags {
-        value: "00110000000000000000000000000000",
+        value: "01010000000000000000000000000000",
         deallocating: false,
         inline_layout_string: false,
         small_descriptor: false,
@@ -229,8 +263,8 @@ mod tests {
         has_ctor: false,
         is_gc: false,
         is_global: true,
-        use_stret: true,
-        has_signature: false,
+        use_stret: false,
+        has_signature: true,
         has_extended_layout: false,
         over_referenced: false,
         reference_count: 0,
@@ -239,12 +273,12 @@ mod tests {
 
     #[cfg(not(feature = "apple"))]
     const DEBUG_BLOCKFLAGS: &str = r#"BlockFlags {
-        value: "00110000000000000000000000000000",
+        value: "01010000000000000000000000000000",
         has_copy_dispose: false,
         has_ctor: false,
         is_global: true,
-        use_stret: true,
-        has_signature: false,
+        use_stret: false,
+        has_signature: true,
         over_referenced: false,
         reference_count: 0,
         ..
@@ -265,6 +299,9 @@ mod tests {
     descriptor: BlockDescriptor {{
         reserved: 0,
         size: {size},
+        encoding: Some(
+            \"v@?\",
+        ),
     }},
     ..
 }}"
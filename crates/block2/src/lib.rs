@@ -91,6 +91,9 @@ extern crate std;
 #[cfg(not(feature = "std"))]
 compile_error!("The `std` feature currently must be enabled.");
 
+#[cfg(all(feature = "objc2-runtime", feature = "compiler-rt"))]
+compile_error!("The `objc2-runtime` feature is not available with `compiler-rt`.");
+
 #[cfg(doctest)]
 #[doc = include_str!("../README.md")]
 extern "C" {}
@@ -98,12 +101,26 @@ extern "C" {}
 pub use block_sys as ffi;
 
 mod block;
+mod byref;
+mod capture;
+pub mod completion;
 mod concrete_block;
 mod debug;
 mod global;
 mod rc_block;
+mod safe_block;
+mod scoped_block;
+mod signature;
+mod verify;
 
 pub use block::{Block, BlockArguments};
+pub use byref::{BlockByRef, ByRef};
+pub use capture::{BlockCapture, BlockCaptures, CapturingBlock, IntoCapturingBlock};
 pub use concrete_block::{ConcreteBlock, IntoConcreteBlock};
 pub use global::GlobalBlock;
-pub use rc_block::RcBlock;
+pub use rc_block::{IntoMutBlock, IntoOnceBlock, RcBlock};
+pub use safe_block::SafeBlock;
+pub use scoped_block::{NoEscapeBlock, ScopedBlock};
+#[doc(hidden)]
+pub use signature::{__signature_array, __signature_len};
+pub use verify::VerificationError;
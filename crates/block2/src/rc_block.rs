@@ -1,8 +1,13 @@
 use core::ops::Deref;
+use std::sync::{Mutex, PoisonError};
 
-use crate::{ffi, Block};
+use objc2::encode::__unstable::EncodeReturn;
+use objc2::encode::Encode;
+
+use crate::{ffi, Block, BlockArguments, ConcreteBlock};
 
 /// A reference-counted Objective-C block.
+#[repr(transparent)]
 pub struct RcBlock<A, R> {
     pub(crate) ptr: *mut Block<A, R>,
 }
@@ -35,6 +40,60 @@ impl<A, R> RcBlock<A, R> {
     }
 }
 
+impl<A: BlockArguments, R: EncodeReturn> RcBlock<A, R> {
+    /// Construct a block on the heap from a closure that may only be called
+    /// once, such as a completion handler.
+    ///
+    /// If the block is called more than once, the process is aborted (since
+    /// the call happens in an `extern "C"` function, where unwinding is not
+    /// allowed).
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block2::RcBlock;
+    ///
+    /// let data = vec![1, 2, 3];
+    /// let block = RcBlock::new_once(move |i: usize| data.into_iter().nth(i));
+    /// assert_eq!(unsafe { block.call((1,)) }, Some(2));
+    /// ```
+    pub fn new_once<F>(closure: F) -> Self
+    where
+        F: IntoOnceBlock<A, Output = R>,
+    {
+        closure.__into_once_block()
+    }
+
+    /// Construct a block on the heap from a closure that may mutate its
+    /// state.
+    ///
+    /// Calls to the block are serialized with a mutex, and the closure must
+    /// be [`Send`], so the block may be called from multiple threads.
+    /// Calling the block recursively from within itself will deadlock.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block2::RcBlock;
+    ///
+    /// let mut counter = 0;
+    /// let block = RcBlock::new_mut(move || {
+    ///     counter += 1;
+    ///     counter
+    /// });
+    /// assert_eq!(unsafe { block.call(()) }, 1);
+    /// assert_eq!(unsafe { block.call(()) }, 2);
+    /// ```
+    pub fn new_mut<F>(closure: F) -> Self
+    where
+        F: IntoMutBlock<A, Output = R>,
+    {
+        closure.__into_mut_block()
+    }
+}
+
 impl<A, R> Clone for RcBlock<A, R> {
     fn clone(&self) -> RcBlock<A, R> {
         // SAFETY: The pointer is valid, since the only way to get an RcBlock
@@ -57,3 +116,116 @@ impl<A, R> Drop for RcBlock<A, R> {
         unsafe { ffi::_Block_release(self.ptr.cast()) };
     }
 }
+
+mod private {
+    pub trait SealedOnce<A> {}
+    pub trait SealedMut<A> {}
+}
+
+/// Types that may be converted into an [`RcBlock`] using
+/// [`RcBlock::new_once`].
+///
+/// This is implemented for [`FnOnce`] closures of up to 12 arguments, where
+/// each argument and the return type implements [`Encode`].
+///
+///
+/// # Safety
+///
+/// This is a sealed trait, and should not need to be implemented. Open an
+/// issue if you know a use-case where this restrition should be lifted!
+pub unsafe trait IntoOnceBlock<A: BlockArguments>: private::SealedOnce<A> + Sized {
+    /// The return type of the resulting block.
+    type Output: EncodeReturn;
+
+    #[doc(hidden)]
+    fn __into_once_block(self) -> RcBlock<A, Self::Output>;
+}
+
+/// Types that may be converted into an [`RcBlock`] using
+/// [`RcBlock::new_mut`].
+///
+/// This is implemented for [`FnMut`] closures of up to 12 arguments, where
+/// each argument and the return type implements [`Encode`], and where the
+/// closure is [`Send`].
+///
+///
+/// # Safety
+///
+/// This is a sealed trait, and should not need to be implemented. Open an
+/// issue if you know a use-case where this restrition should be lifted!
+pub unsafe trait IntoMutBlock<A: BlockArguments>: private::SealedMut<A> + Sized {
+    /// The return type of the resulting block.
+    type Output: EncodeReturn;
+
+    #[doc(hidden)]
+    fn __into_mut_block(self) -> RcBlock<A, Self::Output>;
+}
+
+fn take_once<F>(closure: &Mutex<Option<F>>) -> F {
+    let closure = closure
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    if let Some(closure) = closure {
+        closure
+    } else {
+        std::eprintln!("block created with `RcBlock::new_once` was called more than once");
+        std::process::abort()
+    }
+}
+
+macro_rules! rc_block_impl {
+    ($($a:ident : $t:ident),*) => (
+        impl<$($t: Encode,)* R: EncodeReturn, X> private::SealedOnce<($($t,)*)> for X
+        where
+            X: FnOnce($($t,)*) -> R + 'static,
+        {}
+
+        unsafe impl<$($t: Encode,)* R: EncodeReturn, X> IntoOnceBlock<($($t,)*)> for X
+        where
+            X: FnOnce($($t,)*) -> R + 'static,
+        {
+            type Output = R;
+
+            fn __into_once_block(self) -> RcBlock<($($t,)*), R> {
+                let closure = Mutex::new(Some(self));
+                ConcreteBlock::new(move |$($a: $t),*| take_once(&closure)($($a),*)).copy()
+            }
+        }
+
+        impl<$($t: Encode,)* R: EncodeReturn, X> private::SealedMut<($($t,)*)> for X
+        where
+            X: FnMut($($t,)*) -> R + Send + 'static,
+        {}
+
+        unsafe impl<$($t: Encode,)* R: EncodeReturn, X> IntoMutBlock<($($t,)*)> for X
+        where
+            X: FnMut($($t,)*) -> R + Send + 'static,
+        {
+            type Output = R;
+
+            fn __into_mut_block(self) -> RcBlock<($($t,)*), R> {
+                let closure = Mutex::new(self);
+                ConcreteBlock::new(move |$($a: $t),*| {
+                    let mut closure = closure.lock().unwrap_or_else(PoisonError::into_inner);
+                    (*closure)($($a),*)
+                })
+                .copy()
+            }
+        }
+    );
+}
+
+rc_block_impl!();
+rc_block_impl!(a: A);
+rc_block_impl!(a: A, b: B);
+rc_block_impl!(a: A, b: B, c: C);
+rc_block_impl!(a: A, b: B, c: C, d: D);
+rc_block_impl!(a: A, b: B, c: C, d: D, e: E);
+rc_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F);
+rc_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G);
+rc_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H);
+rc_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I);
+rc_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J);
+rc_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J, k: K);
+rc_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J, k: K, l: L);
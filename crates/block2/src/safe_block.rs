@@ -0,0 +1,143 @@
+use alloc::boxed::Box;
+use core::ops::Deref;
+
+use objc2::encode::__unstable::EncodeReturn;
+use objc2::encode::Encode;
+
+use crate::{Block, BlockArguments, ConcreteBlock, IntoConcreteBlock, RcBlock};
+
+/// A reference-counted block that is safe to call.
+///
+/// Calling an arbitrary [`Block`] is `unsafe`, since nothing ensures that it
+/// actually takes the arguments and returns the type that its Rust type
+/// claims. A `SafeBlock` is either created from a Rust closure, or from a
+/// block that the caller has promised is sound to call (which is checked
+/// against the block's [signature][Block::signature] in debug builds), so
+/// it can be called with [`call`][Self::call] without `unsafe` and without
+/// wrapping the arguments in a tuple.
+///
+/// It can also be converted into a boxed [`Fn`] closure, for storing it
+/// alongside other Rust callbacks.
+///
+///
+/// # Example
+///
+/// ```
+/// use block2::SafeBlock;
+///
+/// let block = SafeBlock::new(|a: i32, b: i32| a + b);
+/// assert_eq!(block.call(5, 8), 13);
+///
+/// let f: Box<dyn Fn(i32, i32) -> i32> = block.into();
+/// assert_eq!(f(1, 2), 3);
+/// ```
+pub struct SafeBlock<A, R> {
+    block: RcBlock<A, R>,
+}
+
+impl<A: BlockArguments, R: EncodeReturn> SafeBlock<A, R> {
+    /// Create a block on the heap from the given closure.
+    pub fn new<F>(closure: F) -> Self
+    where
+        F: IntoConcreteBlock<A, Output = R> + 'static,
+    {
+        Self {
+            block: ConcreteBlock::new(closure).copy(),
+        }
+    }
+
+    /// Wrap a block, and verify that its signature matches the argument and
+    /// return types if `debug_assertions` are enabled.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if `debug_assertions` are enabled and the block doesn't have
+    /// a signature, or if it doesn't match, see [`Block::verify`].
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The block must take arguments of type `A` and return `R`, and calling
+    /// it must not violate any of Rust's safety rules.
+    ///
+    /// Checking the signature is not enough to ensure this, since the
+    /// encodings don't describe e.g. the lifetime of a pointer argument, or
+    /// the class of an object.
+    pub unsafe fn verified(block: RcBlock<A, R>) -> Self {
+        #[cfg(debug_assertions)]
+        if let Err(err) = block.verify() {
+            panic!("invalid block signature: {err}");
+        }
+        Self { block }
+    }
+
+    /// Wrap a block without verifying its signature.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The block must take arguments of type `A` and return `R`, and calling
+    /// it must not violate any of Rust's safety rules.
+    pub unsafe fn new_unchecked(block: RcBlock<A, R>) -> Self {
+        Self { block }
+    }
+}
+
+impl<A, R> SafeBlock<A, R> {
+    /// Get the underlying reference-counted block.
+    pub fn into_rc(self) -> RcBlock<A, R> {
+        self.block
+    }
+}
+
+impl<A, R> Clone for SafeBlock<A, R> {
+    fn clone(&self) -> Self {
+        Self {
+            block: self.block.clone(),
+        }
+    }
+}
+
+impl<A, R> Deref for SafeBlock<A, R> {
+    type Target = Block<A, R>;
+
+    fn deref(&self) -> &Block<A, R> {
+        &self.block
+    }
+}
+
+macro_rules! safe_block_impl {
+    ($($a:ident : $t:ident),*) => (
+        impl<$($t: Encode,)* R: EncodeReturn> SafeBlock<($($t,)*), R> {
+            /// Call the block with the given arguments.
+            #[allow(clippy::too_many_arguments)]
+            pub fn call(&self, $($a: $t),*) -> R {
+                // SAFETY: Ensured when the `SafeBlock` was created.
+                unsafe { self.block.call(($($a,)*)) }
+            }
+        }
+
+        impl<$($t: Encode + 'static,)* R: EncodeReturn + 'static> From<SafeBlock<($($t,)*), R>>
+            for Box<dyn Fn($($t),*) -> R>
+        {
+            fn from(block: SafeBlock<($($t,)*), R>) -> Self {
+                Box::new(move |$($a: $t),*| block.call($($a),*))
+            }
+        }
+    );
+}
+
+safe_block_impl!();
+safe_block_impl!(a: A);
+safe_block_impl!(a: A, b: B);
+safe_block_impl!(a: A, b: B, c: C);
+safe_block_impl!(a: A, b: B, c: C, d: D);
+safe_block_impl!(a: A, b: B, c: C, d: D, e: E);
+safe_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F);
+safe_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G);
+safe_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H);
+safe_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I);
+safe_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J);
+safe_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J, k: K);
+safe_block_impl!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J, k: K, l: L);
@@ -0,0 +1,121 @@
+use core::ops::Deref;
+
+use objc2::encode::__unstable::EncodeReturn;
+use objc2::encode::{Encoding, EncodingBox, RefEncode};
+
+use crate::{Block, BlockArguments, ConcreteBlock, IntoConcreteBlock, VerificationError};
+
+/// A block that the callee promises not to copy or retain after it returns,
+/// like a parameter marked `NS_NOESCAPE` in Objective-C.
+///
+/// Every [`Block`] can be used as a `NoEscapeBlock` (through [`Deref`]), but
+/// not the other way around, so a [`ScopedBlock`] that borrows from its
+/// environment can only be passed to methods that take a `NoEscapeBlock`.
+#[repr(transparent)]
+pub struct NoEscapeBlock<A, R>(Block<A, R>);
+
+unsafe impl<A: BlockArguments, R: EncodeReturn> RefEncode for NoEscapeBlock<A, R> {
+    const ENCODING_REF: Encoding = Encoding::Block;
+}
+
+impl<A, R> NoEscapeBlock<A, R> {
+    /// The Objective-C type-encoding of the block, see
+    /// [`Block::signature`].
+    pub fn signature(&self) -> Option<EncodingBox> {
+        self.0.signature()
+    }
+}
+
+impl<A: BlockArguments, R: EncodeReturn> NoEscapeBlock<A, R> {
+    /// Verify the signature of the block, see [`Block::verify`].
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the block doesn't have a signature, or if it
+    /// doesn't match.
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        self.0.verify()
+    }
+
+    /// Call self with the given arguments.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Block::call`].
+    pub unsafe fn call(&self, args: A) -> R {
+        unsafe { self.0.call(args) }
+    }
+}
+
+impl<A, R> Deref for Block<A, R> {
+    type Target = NoEscapeBlock<A, R>;
+
+    fn deref(&self) -> &NoEscapeBlock<A, R> {
+        let ptr: *const Self = self;
+        // SAFETY: `NoEscapeBlock` is `#[repr(transparent)]` over `Block`.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+/// A block on the stack, whose closure may borrow from its environment.
+///
+/// Unlike [`ConcreteBlock`], this can't be copied to the heap, and only
+/// dereferences to a [`NoEscapeBlock`], so it can only be passed to methods
+/// that promise not to copy it, e.g. `-[NSArray
+/// enumerateObjectsUsingBlock:]`. It has `BLOCK_IS_NOESCAPE` set on Apple
+/// platforms.
+///
+///
+/// # Example
+///
+/// ```
+/// use core::cell::Cell;
+/// use block2::{NoEscapeBlock, ScopedBlock};
+///
+/// fn enumerate(block: &NoEscapeBlock<(i32,), ()>) {
+///     for i in 0..3 {
+///         unsafe { block.call((i,)) };
+///     }
+/// }
+///
+/// let sum = Cell::new(0);
+/// let block = ScopedBlock::new(|i: i32| sum.set(sum.get() + i));
+/// enumerate(&block);
+/// assert_eq!(sum.get(), 3);
+/// ```
+#[repr(transparent)]
+pub struct ScopedBlock<A, R, F> {
+    inner: ConcreteBlock<A, R, F>,
+}
+
+unsafe impl<A: BlockArguments, R: EncodeReturn, F> RefEncode for ScopedBlock<A, R, F> {
+    const ENCODING_REF: Encoding = Encoding::Block;
+}
+
+impl<A, R, F> ScopedBlock<A, R, F>
+where
+    A: BlockArguments,
+    R: EncodeReturn,
+    F: IntoConcreteBlock<A, Output = R>,
+{
+    /// Constructs a `ScopedBlock` with the given closure.
+    ///
+    /// When the block is called, it will return the value that results from
+    /// calling the closure.
+    pub fn new(closure: F) -> Self {
+        let mut inner = ConcreteBlock::new(closure);
+        inner.set_noescape();
+        Self { inner }
+    }
+}
+
+impl<A, R, F> Deref for ScopedBlock<A, R, F> {
+    type Target = NoEscapeBlock<A, R>;
+
+    fn deref(&self) -> &NoEscapeBlock<A, R> {
+        let block: &Block<A, R> = &self.inner;
+        block
+    }
+}
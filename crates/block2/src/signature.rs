@@ -0,0 +1,183 @@
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr;
+use std::os::raw::c_char;
+
+use objc2::encode::__unstable::EncodeReturn;
+use objc2::encode::Encoding;
+
+use crate::{ffi, BlockArguments};
+
+/// The length of [`__signature_array`], including the trailing NUL byte.
+///
+/// The signature is the Objective-C type-encoding of a block, e.g. `v@?i`
+/// for a block that takes an `int` and returns `void`. Like the method
+/// encodings in `objc2`, this does not contain the stack offsets that Clang
+/// emits, since the runtime doesn't need them.
+#[doc(hidden)]
+pub const fn __signature_len(ret: &Encoding, args: &[Encoding]) -> usize {
+    let mut len = ret.__static_str_len() + Encoding::Block.__static_str_len() + 1;
+    let mut i = 0;
+    while i < args.len() {
+        len += args[i].__static_str_len();
+        i += 1;
+    }
+    len
+}
+
+/// The NUL-terminated signature, computed at compile-time.
+///
+/// Used by [`global_block!`](crate::global_block), since generic statics are
+/// not possible, and by [`Signature`].
+#[doc(hidden)]
+pub const fn __signature_array<const LEN: usize>(ret: &Encoding, args: &[Encoding]) -> [u8; LEN] {
+    let mut res: [u8; LEN] = [0; LEN];
+    let mut res_i = 0;
+
+    // We use LEN even though it creates an oversized array
+    let arr = ret.__static_str_array::<LEN>();
+    let mut i = 0;
+    while i < ret.__static_str_len() {
+        res[res_i] = arr[i];
+        res_i += 1;
+        i += 1;
+    }
+
+    let arr = Encoding::Block.__static_str_array::<LEN>();
+    let mut i = 0;
+    while i < Encoding::Block.__static_str_len() {
+        res[res_i] = arr[i];
+        res_i += 1;
+        i += 1;
+    }
+
+    let mut args_i = 0;
+    while args_i < args.len() {
+        let arr = args[args_i].__static_str_array::<LEN>();
+        let mut i = 0;
+        while i < args[args_i].__static_str_len() {
+            res[res_i] = arr[i];
+            res_i += 1;
+            i += 1;
+        }
+        args_i += 1;
+    }
+
+    // The remaining byte is the NUL terminator
+    res
+}
+
+/// Whether the invoke function returns the value through a pointer passed
+/// in by the caller, in which case the block must have `BLOCK_USE_STRET`.
+///
+/// This matches when `objc2` uses `objc_msgSend_stret`.
+pub(crate) const fn use_stret(ret: &Encoding, size: usize) -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        !matches!(ret, Encoding::LongDouble | Encoding::LongDoubleComplex) && size > 16
+    }
+    #[cfg(target_arch = "x86")]
+    {
+        !matches!(
+            ret,
+            Encoding::Float | Encoding::Double | Encoding::LongDouble
+        ) && !matches!(size, 0 | 1 | 2 | 4 | 8)
+    }
+    #[cfg(target_arch = "arm")]
+    {
+        !matches!(
+            ret,
+            Encoding::LongLong | Encoding::ULongLong | Encoding::Double
+        ) && size > 4
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm")))]
+    {
+        let _ = (ret, size);
+        false
+    }
+}
+
+/// The capacity of the signatures of [`ConcreteBlock`] and
+/// [`CapturingBlock`].
+///
+/// The signature is built at compile-time, but unlike in `global_block!`,
+/// where the types are known, the length of an array can't depend on the
+/// generic parameters of these blocks. So the signature is stored in a
+/// NUL-padded buffer instead. 128 bytes fit a dozen arguments with
+/// encodings of up to ten bytes each, e.g. objects, blocks and pointers to
+/// primitives; signatures that don't fit (usually those containing large
+/// structs) are left out, which the blocks ABI allows.
+///
+/// [`ConcreteBlock`]: crate::ConcreteBlock
+/// [`CapturingBlock`]: crate::CapturingBlock
+pub(crate) const SIGNATURE_CAPACITY: usize = 128;
+
+/// The signature of blocks with the given arguments and return type,
+/// computed at compile-time.
+pub(crate) struct Signature<A, R>(PhantomData<(A, R)>);
+
+impl<A: BlockArguments, R: EncodeReturn> Signature<A, R> {
+    const FITS: bool = __signature_len(&R::ENCODING_RETURN, A::__ENCODINGS) <= SIGNATURE_CAPACITY;
+
+    const ARRAY: &'static [u8; SIGNATURE_CAPACITY] = &if Self::FITS {
+        __signature_array(&R::ENCODING_RETURN, A::__ENCODINGS)
+    } else {
+        [0; SIGNATURE_CAPACITY]
+    };
+
+    /// `BLOCK_HAS_SIGNATURE` if the signature is present, along with
+    /// `BLOCK_USE_STRET` if the return value requires it.
+    pub(crate) const FLAGS: ffi::block_flags = {
+        let mut flags = 0;
+        if Self::FITS {
+            flags |= ffi::BLOCK_HAS_SIGNATURE;
+        }
+        if use_stret(&R::ENCODING_RETURN, mem::size_of::<R>()) {
+            flags |= ffi::BLOCK_USE_STRET;
+        }
+        flags
+    };
+
+    /// The NUL-terminated signature, or NULL if it is not present.
+    pub(crate) const ENCODING: *const c_char = if Self::FITS {
+        Self::ARRAY.as_ptr().cast()
+    } else {
+        ptr::null()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        const RET: Encoding = Encoding::Int;
+        const ARGS: &[Encoding] = &[Encoding::Pointer(&Encoding::Int), Encoding::Object];
+        const LEN: usize = __signature_len(&RET, ARGS);
+        const ARRAY: [u8; LEN] = __signature_array(&RET, ARGS);
+        assert_eq!(&ARRAY, b"i@?^i@\0");
+    }
+
+    #[test]
+    fn test_signature_no_args() {
+        const LEN: usize = __signature_len(&Encoding::Void, &[]);
+        const ARRAY: [u8; LEN] = __signature_array(&Encoding::Void, &[]);
+        assert_eq!(&ARRAY, b"v@?\0");
+    }
+
+    #[test]
+    fn test_padded_signature() {
+        use std::ffi::CStr;
+
+        type Sig = Signature<(i32, *const u8), f64>;
+        assert_ne!(Sig::FLAGS & ffi::BLOCK_HAS_SIGNATURE, 0);
+        let encoding = unsafe { CStr::from_ptr(Sig::ENCODING) };
+        assert_eq!(encoding.to_str().unwrap(), "d@?i*");
+
+        // Too long to be stored
+        type Long = Signature<([[i32; 100]; 100],), ()>;
+        assert_eq!(Long::FLAGS & ffi::BLOCK_HAS_SIGNATURE, 0);
+        assert!(Long::ENCODING.is_null());
+    }
+}
@@ -0,0 +1,220 @@
+use alloc::vec::Vec;
+use core::fmt;
+use std::error::Error;
+
+use objc2::encode::{Encoding, EncodingBox, MethodArgument, ParseError, Qualifiers};
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Inner {
+    MissingSignature,
+    ParseError(ParseError),
+    MissingReturn,
+    MissingBlock,
+    InvalidBlock(EncodingBox),
+    MismatchedReturn(EncodingBox, Encoding),
+    MismatchedArgumentsCount(usize, usize),
+    MismatchedArgument(usize, EncodingBox, Encoding),
+}
+
+impl fmt::Display for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSignature => write!(f, "block has no signature"),
+            Self::ParseError(e) => write!(f, "failed parsing block signature: {e}"),
+            Self::MissingReturn => write!(f, "block signature must contain the return type"),
+            Self::MissingBlock => write!(f, "block signature must contain the block itself"),
+            Self::InvalidBlock(enc) => {
+                write!(f, "block encoding must be '@?', but it was '{enc}'")
+            }
+            Self::MismatchedReturn(expected, actual) => {
+                write!(
+                    f,
+                    "expected return to have type code '{expected}', but found '{actual}'",
+                )
+            }
+            Self::MismatchedArgumentsCount(expected, actual) => {
+                write!(f, "expected {expected} arguments, but {actual} were given",)
+            }
+            Self::MismatchedArgument(i, expected, actual) => {
+                write!(
+                    f,
+                    "expected argument at index {i} to have type code '{expected}', but found '{actual}'",
+                )
+            }
+        }
+    }
+}
+
+/// Failed verifying the signature of a block.
+///
+/// This is returned in the error case of [`Block::verify`], see that for
+/// details.
+///
+/// This implements [`Error`], and a description of the error can be retrieved
+/// using [`fmt::Display`].
+///
+/// [`Block::verify`]: crate::Block::verify
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct VerificationError(Inner);
+
+impl From<Inner> for VerificationError {
+    fn from(inner: Inner) -> Self {
+        Self(inner)
+    }
+}
+
+impl From<ParseError> for VerificationError {
+    fn from(e: ParseError) -> Self {
+        Self(Inner::ParseError(e))
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Delegate to inner
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for VerificationError {}
+
+/// Parse an encoding followed by the (optional) stack offset.
+fn next_encoding(s: &mut &str) -> Option<Result<EncodingBox, ParseError>> {
+    if s.is_empty() {
+        return None;
+    }
+    let res = EncodingBox::from_start_of_str(s);
+    *s = s.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '+');
+    Some(res)
+}
+
+/// Parse the qualifiers and encodings in a block signature, ignoring the
+/// stack offsets.
+pub(crate) fn parse_signature(mut s: &str) -> Result<Vec<MethodArgument>, ParseError> {
+    let mut items = Vec::new();
+    loop {
+        let qualifiers = Qualifiers::from_start_of_str(&mut s);
+        match next_encoding(&mut s) {
+            Some(res) => items.push(MethodArgument {
+                qualifiers,
+                encoding: res?,
+                offset: None,
+            }),
+            None => break,
+        }
+    }
+    Ok(items)
+}
+
+pub(crate) fn verify_block_signature(
+    signature: Option<&str>,
+    args: &[Encoding],
+    ret: &Encoding,
+) -> Result<(), VerificationError> {
+    let mut s = signature.ok_or(Inner::MissingSignature)?;
+
+    let expected = next_encoding(&mut s).ok_or(Inner::MissingReturn)??;
+    if !ret.equivalent_to_box(&expected) {
+        return Err(Inner::MismatchedReturn(expected, ret.clone()).into());
+    }
+
+    let block = next_encoding(&mut s).ok_or(Inner::MissingBlock)??;
+    if !Encoding::Block.equivalent_to_box(&block) {
+        return Err(Inner::InvalidBlock(block).into());
+    }
+
+    let actual_count = args.len();
+
+    for (i, actual) in args.iter().enumerate() {
+        if let Some(res) = next_encoding(&mut s) {
+            let expected = res?;
+            if !actual.equivalent_to_box(&expected) {
+                return Err(Inner::MismatchedArgument(i, expected, actual.clone()).into());
+            }
+        } else {
+            return Err(Inner::MismatchedArgumentsCount(i, actual_count).into());
+        }
+    }
+
+    let mut remaining = 0;
+    while let Some(res) = next_encoding(&mut s) {
+        res?;
+        remaining += 1;
+    }
+    if remaining != 0 {
+        return Err(Inner::MismatchedArgumentsCount(actual_count + remaining, actual_count).into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_verify() {
+        let verify = verify_block_signature;
+        assert!(verify(Some("v@?"), &[], &Encoding::Void).is_ok());
+        assert!(verify(Some("i16@?0i8"), &[Encoding::Int], &Encoding::Int).is_ok());
+        assert!(verify(
+            Some("v24@?0@\"NSString\"8@?<v@?>16"),
+            &[Encoding::Object, Encoding::Block],
+            &Encoding::Void,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_parse_signature() {
+        let items = parse_signature("v24@?0@\"NSString\"8r*16").unwrap();
+        assert_eq!(
+            items,
+            [
+                EncodingBox::Void.into(),
+                EncodingBox::Block(None).into(),
+                EncodingBox::Object(Some("NSString".into())).into(),
+                MethodArgument {
+                    qualifiers: Qualifiers::CONST,
+                    encoding: EncodingBox::String,
+                    offset: None,
+                },
+            ]
+        );
+        assert_eq!(parse_signature(""), Ok(Vec::new()));
+        assert!(parse_signature("v@?{").is_err());
+    }
+
+    #[test]
+    fn test_verify_errors() {
+        let verify = verify_block_signature;
+
+        let err = verify(None, &[], &Encoding::Void).unwrap_err();
+        assert_eq!(err.to_string(), "block has no signature");
+
+        let err = verify(Some("v@?"), &[], &Encoding::Int).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected return to have type code 'v', but found 'i'"
+        );
+
+        let err = verify(Some("v@"), &[], &Encoding::Void).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "block encoding must be '@?', but it was '@'"
+        );
+
+        let err = verify(Some("v@?i"), &[], &Encoding::Void).unwrap_err();
+        assert_eq!(err.to_string(), "expected 1 arguments, but 0 were given");
+
+        let err = verify(Some("v@?"), &[Encoding::Int], &Encoding::Void).unwrap_err();
+        assert_eq!(err.to_string(), "expected 0 arguments, but 1 were given");
+
+        let err = verify(Some("v@?i"), &[Encoding::Sel], &Encoding::Void).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected argument at index 0 to have type code 'i', but found ':'"
+        );
+    }
+}
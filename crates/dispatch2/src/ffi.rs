@@ -0,0 +1,173 @@
+//! Raw bindings to `libdispatch`.
+//!
+//! These mirror the declarations in `<dispatch/dispatch.h>`; macros that
+//! refer to exported statics (like `dispatch_get_main_queue`) are provided
+//! as functions.
+#![allow(non_camel_case_types)]
+#![allow(non_upper_case_globals)]
+#![allow(non_snake_case)]
+#![allow(missing_docs)]
+
+use std::os::raw::{c_char, c_long, c_ulong};
+
+use block2::Block;
+
+macro_rules! opaque {
+    ($($name:ident => $ptr:ident;)*) => {$(
+        #[repr(C)]
+        pub struct $name {
+            _priv: [u8; 0],
+        }
+
+        pub type $ptr = *mut $name;
+    )*};
+}
+
+opaque! {
+    dispatch_object_s => dispatch_object_t;
+    dispatch_queue_s => dispatch_queue_t;
+    dispatch_queue_attr_s => dispatch_queue_attr_t;
+    dispatch_group_s => dispatch_group_t;
+    dispatch_semaphore_s => dispatch_semaphore_t;
+    dispatch_source_s => dispatch_source_t;
+}
+
+#[repr(C)]
+pub struct dispatch_source_type_s {
+    _priv: [u8; 0],
+}
+
+pub type dispatch_source_type_t = *const dispatch_source_type_s;
+
+pub type dispatch_block_t = *const Block<(), ()>;
+pub type dispatch_time_t = u64;
+pub type dispatch_once_t = c_long;
+
+pub const DISPATCH_TIME_NOW: dispatch_time_t = 0;
+pub const DISPATCH_TIME_FOREVER: dispatch_time_t = !0;
+
+pub const DISPATCH_QUEUE_PRIORITY_HIGH: c_long = 2;
+pub const DISPATCH_QUEUE_PRIORITY_DEFAULT: c_long = 0;
+pub const DISPATCH_QUEUE_PRIORITY_LOW: c_long = -2;
+pub const DISPATCH_QUEUE_PRIORITY_BACKGROUND: c_long = i16::MIN as c_long;
+
+/// `DISPATCH_QUEUE_SERIAL`.
+pub const DISPATCH_QUEUE_SERIAL: dispatch_queue_attr_t = core::ptr::null_mut();
+
+// libdispatch is part of libSystem on Apple platforms, which is always
+// linked.
+#[cfg_attr(not(target_vendor = "apple"), link(name = "dispatch", kind = "dylib"))]
+extern "C" {
+    static _dispatch_main_q: dispatch_queue_s;
+    static _dispatch_queue_attr_concurrent: dispatch_queue_attr_s;
+
+    static _dispatch_source_type_data_add: dispatch_source_type_s;
+    static _dispatch_source_type_data_or: dispatch_source_type_s;
+    static _dispatch_source_type_read: dispatch_source_type_s;
+    static _dispatch_source_type_signal: dispatch_source_type_s;
+    static _dispatch_source_type_timer: dispatch_source_type_s;
+    static _dispatch_source_type_write: dispatch_source_type_s;
+
+    pub fn dispatch_retain(object: dispatch_object_t);
+    pub fn dispatch_release(object: dispatch_object_t);
+    pub fn dispatch_suspend(object: dispatch_object_t);
+    pub fn dispatch_resume(object: dispatch_object_t);
+
+    pub fn dispatch_time(when: dispatch_time_t, delta: i64) -> dispatch_time_t;
+
+    pub fn dispatch_queue_create(
+        label: *const c_char,
+        attr: dispatch_queue_attr_t,
+    ) -> dispatch_queue_t;
+    pub fn dispatch_get_global_queue(identifier: c_long, flags: c_ulong) -> dispatch_queue_t;
+    pub fn dispatch_queue_get_label(queue: dispatch_queue_t) -> *const c_char;
+    pub fn dispatch_async(queue: dispatch_queue_t, block: dispatch_block_t);
+    pub fn dispatch_sync(queue: dispatch_queue_t, block: dispatch_block_t);
+    pub fn dispatch_after(when: dispatch_time_t, queue: dispatch_queue_t, block: dispatch_block_t);
+    pub fn dispatch_apply(
+        iterations: usize,
+        queue: dispatch_queue_t,
+        block: *const Block<(usize,), ()>,
+    );
+    pub fn dispatch_main() -> !;
+
+    pub fn dispatch_group_create() -> dispatch_group_t;
+    pub fn dispatch_group_async(
+        group: dispatch_group_t,
+        queue: dispatch_queue_t,
+        block: dispatch_block_t,
+    );
+    pub fn dispatch_group_enter(group: dispatch_group_t);
+    pub fn dispatch_group_leave(group: dispatch_group_t);
+    pub fn dispatch_group_wait(group: dispatch_group_t, timeout: dispatch_time_t) -> c_long;
+    pub fn dispatch_group_notify(
+        group: dispatch_group_t,
+        queue: dispatch_queue_t,
+        block: dispatch_block_t,
+    );
+
+    pub fn dispatch_semaphore_create(value: c_long) -> dispatch_semaphore_t;
+    pub fn dispatch_semaphore_wait(dsema: dispatch_semaphore_t, timeout: dispatch_time_t)
+        -> c_long;
+    pub fn dispatch_semaphore_signal(dsema: dispatch_semaphore_t) -> c_long;
+
+    pub fn dispatch_once(predicate: *mut dispatch_once_t, block: dispatch_block_t);
+
+    pub fn dispatch_source_create(
+        type_: dispatch_source_type_t,
+        handle: usize,
+        mask: usize,
+        queue: dispatch_queue_t,
+    ) -> dispatch_source_t;
+    pub fn dispatch_source_set_event_handler(source: dispatch_source_t, handler: dispatch_block_t);
+    pub fn dispatch_source_set_cancel_handler(source: dispatch_source_t, handler: dispatch_block_t);
+    pub fn dispatch_source_cancel(source: dispatch_source_t);
+    pub fn dispatch_source_testcancel(source: dispatch_source_t) -> c_long;
+    pub fn dispatch_source_get_data(source: dispatch_source_t) -> usize;
+    pub fn dispatch_source_merge_data(source: dispatch_source_t, value: usize);
+    pub fn dispatch_source_set_timer(
+        source: dispatch_source_t,
+        start: dispatch_time_t,
+        interval: u64,
+        leeway: u64,
+    );
+}
+
+/// `dispatch_get_main_queue`.
+pub fn dispatch_get_main_queue() -> dispatch_queue_t {
+    // SAFETY: The main queue is a static, and is never deallocated.
+    // Taking the address of an extern static is only safe in newer Rust.
+    #[allow(unused_unsafe)]
+    unsafe {
+        core::ptr::addr_of!(_dispatch_main_q) as *mut _
+    }
+}
+
+/// `DISPATCH_QUEUE_CONCURRENT`.
+pub fn dispatch_queue_attr_concurrent() -> dispatch_queue_attr_t {
+    // SAFETY: Taking the address of an extern static.
+    #[allow(unused_unsafe)]
+    unsafe {
+        core::ptr::addr_of!(_dispatch_queue_attr_concurrent) as *mut _
+    }
+}
+
+macro_rules! source_types {
+    ($($name:ident => $static:ident;)*) => {$(
+        #[doc = concat!("`", stringify!($name), "`.")]
+        pub fn $name() -> dispatch_source_type_t {
+            // SAFETY: Taking the address of an extern static.
+            #[allow(unused_unsafe)]
+            unsafe { core::ptr::addr_of!($static) }
+        }
+    )*};
+}
+
+source_types! {
+    DISPATCH_SOURCE_TYPE_DATA_ADD => _dispatch_source_type_data_add;
+    DISPATCH_SOURCE_TYPE_DATA_OR => _dispatch_source_type_data_or;
+    DISPATCH_SOURCE_TYPE_READ => _dispatch_source_type_read;
+    DISPATCH_SOURCE_TYPE_SIGNAL => _dispatch_source_type_signal;
+    DISPATCH_SOURCE_TYPE_TIMER => _dispatch_source_type_timer;
+    DISPATCH_SOURCE_TYPE_WRITE => _dispatch_source_type_write;
+}
@@ -0,0 +1,141 @@
+use core::fmt;
+use core::time::Duration;
+
+use block2::RcBlock;
+
+use crate::ffi;
+use crate::object::Object;
+use crate::{time, Queue, WaitTimeout};
+
+/// A group of blocks, which can be waited on as a whole.
+///
+/// Cloning the group retains it.
+#[derive(Clone)]
+pub struct Group {
+    inner: Object<ffi::dispatch_group_s>,
+}
+
+impl Group {
+    /// Create a new, empty group.
+    pub fn new() -> Self {
+        // SAFETY: Always safe to call.
+        let ptr = unsafe { ffi::dispatch_group_create() };
+        // SAFETY: The group is returned with +1 retain count.
+        let inner = unsafe { Object::new(ptr) }.expect("failed creating group");
+        Self { inner }
+    }
+
+    /// Submit a closure for asynchronous execution on the queue, as part of
+    /// the group.
+    pub fn exec_async<F>(&self, queue: &Queue, work: F)
+    where
+        F: Send + FnOnce() + 'static,
+    {
+        let block = RcBlock::new_once(work);
+        // SAFETY: The group and queue are valid, and the block is copied.
+        unsafe { ffi::dispatch_group_async(self.as_raw(), queue.as_raw(), &*block) };
+    }
+
+    /// Explicitly mark that work has entered the group.
+    ///
+    /// The work is considered finished when the returned guard is dropped.
+    pub fn enter(&self) -> GroupGuard {
+        // SAFETY: The group is valid, and the guard leaves it again.
+        unsafe { ffi::dispatch_group_enter(self.as_raw()) };
+        GroupGuard(self.clone())
+    }
+
+    /// Wait for all the work in the group to finish, or until the timeout
+    /// expires.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WaitTimeout`] if the timeout expired first.
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<(), WaitTimeout> {
+        // SAFETY: The group is valid.
+        let res = unsafe { ffi::dispatch_group_wait(self.as_raw(), time::timeout(timeout)) };
+        if res == 0 {
+            Ok(())
+        } else {
+            Err(WaitTimeout)
+        }
+    }
+
+    /// Submit a closure to the queue once all the work currently in the
+    /// group has finished.
+    pub fn notify<F>(&self, queue: &Queue, work: F)
+    where
+        F: Send + FnOnce() + 'static,
+    {
+        let block = RcBlock::new_once(work);
+        // SAFETY: The group and queue are valid, and the block is copied.
+        unsafe { ffi::dispatch_group_notify(self.as_raw(), queue.as_raw(), &*block) };
+    }
+
+    /// The underlying `dispatch_group_t`.
+    pub fn as_raw(&self) -> ffi::dispatch_group_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Group").finish_non_exhaustive()
+    }
+}
+
+/// Work that has explicitly entered a [`Group`], see [`Group::enter`].
+///
+/// Leaves the group when dropped.
+#[derive(Debug)]
+#[must_use = "the work is finished when the guard is dropped"]
+pub struct GroupGuard(Group);
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        // SAFETY: The group was entered when creating the guard.
+        unsafe { ffi::dispatch_group_leave(self.0.as_raw()) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_group() {
+        let queue = Queue::concurrent("group");
+        let group = Group::new();
+        let count = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..10 {
+            let count = count.clone();
+            group.exec_async(&queue, move || {
+                count.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        group.wait(None).unwrap();
+        assert_eq!(count.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn test_guard() {
+        let group = Group::new();
+        let guard = group.enter();
+        assert_eq!(
+            group.wait(Some(Duration::from_millis(10))),
+            Err(WaitTimeout)
+        );
+        drop(guard);
+        group.wait(None).unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+//! # Bindings to Grand Central Dispatch
+//!
+//! Grand Central Dispatch (`libdispatch`) manages the execution of work
+//! submitted to queues, which are run either serially or concurrently on a
+//! pool of threads managed by the system. Many Objective-C frameworks take
+//! a queue to call their callbacks on, and the main queue is the usual way
+//! to run code on the main thread.
+//!
+//! Work is submitted as blocks created with [`block2`]; this crate takes
+//! care of creating them from Rust closures.
+//!
+//! `libdispatch` is [open source][libdispatch], and is also available on
+//! Linux, where it uses the `compiler-rt` blocks runtime.
+//!
+//! [libdispatch]: https://github.com/apple/swift-corelibs-libdispatch
+//!
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//! use dispatch2::{Group, Queue};
+//!
+//! let queue = Queue::concurrent("com.example.work");
+//! let group = Group::new();
+//! let sum = Arc::new(AtomicUsize::new(0));
+//!
+//! for i in 0..10 {
+//!     let sum = sum.clone();
+//!     group.exec_async(&queue, move || {
+//!         sum.fetch_add(i, Ordering::Relaxed);
+//!     });
+//! }
+//!
+//! group.wait(None).unwrap();
+//! assert_eq!(sum.load(Ordering::Relaxed), 45);
+//! ```
+#![no_std]
+#![warn(elided_lifetimes_in_paths)]
+#![warn(missing_docs)]
+#![deny(non_ascii_idents)]
+#![warn(unreachable_pub)]
+#![deny(unsafe_op_in_unsafe_fn)]
+#![warn(clippy::cargo)]
+#![warn(clippy::ptr_as_ptr)]
+// Update in Cargo.toml as well.
+#![doc(html_root_url = "https://docs.rs/dispatch2/0.1.0")]
+
+extern crate alloc;
+extern crate std;
+
+#[cfg(not(feature = "std"))]
+compile_error!("The `std` feature currently must be enabled.");
+
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+extern "C" {}
+
+pub mod ffi;
+mod group;
+mod object;
+mod once;
+mod queue;
+mod semaphore;
+mod source;
+mod time;
+
+pub use group::{Group, GroupGuard};
+pub use once::Once;
+pub use queue::{Queue, QueuePriority};
+pub use semaphore::Semaphore;
+pub use source::{Source, SourceType};
+pub use time::WaitTimeout;
+
+/// Run the main queue, i.e. park the main thread and execute the blocks
+/// submitted to the main queue.
+///
+/// This never returns. Applications that run an event loop through
+/// `NSApplicationMain`, `UIApplicationMain` or `CFRunLoop` should not call
+/// this, since those already service the main queue.
+pub fn main() -> ! {
+    // SAFETY: Always safe to call; the process exits by calling `exit` in a
+    // block on some queue.
+    unsafe { ffi::dispatch_main() }
+}
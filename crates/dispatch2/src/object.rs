@@ -0,0 +1,59 @@
+use core::ptr::NonNull;
+
+use block2::{Block, NoEscapeBlock};
+
+use crate::ffi;
+
+/// A retained dispatch object.
+///
+/// All dispatch objects are thread-safe, and are retained and released with
+/// `dispatch_retain` and `dispatch_release`.
+#[derive(Debug)]
+pub(crate) struct Object<T> {
+    ptr: NonNull<T>,
+}
+
+// SAFETY: Dispatch objects may be used from any thread.
+unsafe impl<T> Send for Object<T> {}
+// SAFETY: Same as above.
+unsafe impl<T> Sync for Object<T> {}
+
+impl<T> Object<T> {
+    /// Take ownership of a dispatch object.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be a valid dispatch object with a +1 retain count,
+    /// or a global object like the main queue.
+    pub(crate) unsafe fn new(ptr: *mut T) -> Option<Self> {
+        NonNull::new(ptr).map(|ptr| Self { ptr })
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+}
+
+impl<T> Clone for Object<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: The object is valid.
+        unsafe { ffi::dispatch_retain(self.as_ptr().cast()) };
+        Self { ptr: self.ptr }
+    }
+}
+
+impl<T> Drop for Object<T> {
+    fn drop(&mut self) {
+        // SAFETY: We own a reference to the object. Releasing global objects
+        // does nothing.
+        unsafe { ffi::dispatch_release(self.as_ptr().cast()) };
+    }
+}
+
+/// Get a pointer to a block that is only used during the call it is passed
+/// to.
+pub(crate) fn block_ptr<A, R>(block: &NoEscapeBlock<A, R>) -> *const Block<A, R> {
+    let ptr: *const NoEscapeBlock<A, R> = block;
+    // `NoEscapeBlock` is `#[repr(transparent)]` over `Block`.
+    ptr.cast()
+}
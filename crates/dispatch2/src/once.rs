@@ -0,0 +1,88 @@
+use core::cell::{Cell, UnsafeCell};
+use core::fmt;
+
+use block2::ScopedBlock;
+
+use crate::ffi;
+use crate::object::block_ptr;
+
+/// A value that executes a closure exactly once, using `dispatch_once`.
+///
+/// `libdispatch` requires the predicate to have static or global storage
+/// duration, so [`call_once`][Self::call_once] can only be used on a `Once`
+/// that lives for `'static`, usually one stored in a `static`.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use dispatch2::Once;
+///
+/// static INIT: Once = Once::new();
+///
+/// INIT.call_once(|| println!("only printed once"));
+/// INIT.call_once(|| unreachable!());
+/// ```
+pub struct Once {
+    predicate: UnsafeCell<ffi::dispatch_once_t>,
+}
+
+// SAFETY: The predicate is only accessed by `dispatch_once`, which
+// synchronizes.
+unsafe impl Sync for Once {}
+// SAFETY: Same as above.
+unsafe impl Send for Once {}
+
+impl Once {
+    /// Create a new `Once` that has not yet run.
+    pub const fn new() -> Self {
+        Self {
+            predicate: UnsafeCell::new(0),
+        }
+    }
+
+    /// Execute the closure, unless a closure has already been executed.
+    ///
+    /// If another thread is currently executing a closure, this waits for
+    /// it to finish.
+    ///
+    /// Using a predicate on the stack or in a heap allocation with
+    /// `dispatch_once` is undefined behaviour, hence the `'static` bound.
+    pub fn call_once<F: FnOnce()>(&'static self, work: F) {
+        let work = Cell::new(Some(work));
+        let block = ScopedBlock::new(|| {
+            if let Some(work) = work.take() {
+                work();
+            }
+        });
+        // SAFETY: The predicate is valid and lives for `'static`, and the
+        // block is not copied.
+        unsafe { ffi::dispatch_once(self.predicate.get(), block_ptr(&block)) };
+    }
+}
+
+impl Default for Once {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Once {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Once").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_once() {
+        static ONCE: Once = Once::new();
+        let count = Cell::new(0);
+        ONCE.call_once(|| count.set(count.get() + 1));
+        ONCE.call_once(|| count.set(count.get() + 1));
+        assert_eq!(count.get(), 1);
+    }
+}
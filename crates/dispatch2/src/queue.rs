@@ -0,0 +1,230 @@
+use core::cell::Cell;
+use core::fmt;
+use core::time::Duration;
+use std::ffi::{CStr, CString};
+
+use block2::{RcBlock, ScopedBlock};
+
+use crate::ffi;
+use crate::object::{block_ptr, Object};
+use crate::time;
+
+/// The priority of a global concurrent queue, see [`Queue::global`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum QueuePriority {
+    /// `DISPATCH_QUEUE_PRIORITY_HIGH`.
+    High,
+    /// `DISPATCH_QUEUE_PRIORITY_DEFAULT`.
+    Default,
+    /// `DISPATCH_QUEUE_PRIORITY_LOW`.
+    Low,
+    /// `DISPATCH_QUEUE_PRIORITY_BACKGROUND`.
+    Background,
+}
+
+impl Default for QueuePriority {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+impl QueuePriority {
+    fn to_raw(self) -> std::os::raw::c_long {
+        match self {
+            Self::High => ffi::DISPATCH_QUEUE_PRIORITY_HIGH,
+            Self::Default => ffi::DISPATCH_QUEUE_PRIORITY_DEFAULT,
+            Self::Low => ffi::DISPATCH_QUEUE_PRIORITY_LOW,
+            Self::Background => ffi::DISPATCH_QUEUE_PRIORITY_BACKGROUND,
+        }
+    }
+}
+
+/// A dispatch queue, to which work can be submitted.
+///
+/// Serial queues execute one block at a time in the order they were
+/// submitted, while concurrent queues may execute several at once.
+///
+/// Cloning the queue retains it.
+#[derive(Clone)]
+pub struct Queue {
+    inner: Object<ffi::dispatch_queue_s>,
+}
+
+impl Queue {
+    fn create(label: &str, attr: ffi::dispatch_queue_attr_t) -> Self {
+        let label = CString::new(label).expect("queue label must not contain NUL bytes");
+        // SAFETY: The label is copied, and the attribute is valid.
+        let ptr = unsafe { ffi::dispatch_queue_create(label.as_ptr(), attr) };
+        // SAFETY: The queue is returned with +1 retain count.
+        let inner = unsafe { Object::new(ptr) }.expect("failed creating queue");
+        Self { inner }
+    }
+
+    /// Create a new serial queue with the given label.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the label contains a NUL byte.
+    pub fn serial(label: &str) -> Self {
+        Self::create(label, ffi::DISPATCH_QUEUE_SERIAL)
+    }
+
+    /// Create a new concurrent queue with the given label.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the label contains a NUL byte.
+    pub fn concurrent(label: &str) -> Self {
+        Self::create(label, ffi::dispatch_queue_attr_concurrent())
+    }
+
+    /// The serial queue that executes blocks on the main thread.
+    ///
+    /// Blocks submitted to the main queue are only executed while the main
+    /// thread runs an event loop, or [`dispatch2::main`][crate::main].
+    pub fn main() -> Self {
+        // SAFETY: The main queue is a global object.
+        let inner = unsafe { Object::new(ffi::dispatch_get_main_queue()) }.unwrap();
+        Self { inner }
+    }
+
+    /// One of the system-defined global concurrent queues.
+    pub fn global(priority: QueuePriority) -> Self {
+        // SAFETY: Always safe to call.
+        let ptr = unsafe { ffi::dispatch_get_global_queue(priority.to_raw(), 0) };
+        // SAFETY: The global queues are global objects.
+        let inner = unsafe { Object::new(ptr) }.expect("failed getting global queue");
+        Self { inner }
+    }
+
+    /// The label that the queue was created with.
+    pub fn label(&self) -> &str {
+        // SAFETY: The queue is valid, and the label lives as long as it.
+        let label = unsafe { ffi::dispatch_queue_get_label(self.as_raw()) };
+        if label.is_null() {
+            return "";
+        }
+        // SAFETY: Checked for NULL above, and the label is a C string.
+        unsafe { CStr::from_ptr(label) }.to_str().unwrap_or("")
+    }
+
+    /// Submit a closure for asynchronous execution on the queue.
+    pub fn exec_async<F>(&self, work: F)
+    where
+        F: Send + FnOnce() + 'static,
+    {
+        let block = RcBlock::new_once(work);
+        // SAFETY: The queue is valid, and the block is copied by
+        // `dispatch_async`.
+        unsafe { ffi::dispatch_async(self.as_raw(), &*block) };
+    }
+
+    /// Submit a closure for execution on the queue, and wait for it to
+    /// finish.
+    ///
+    /// This deadlocks if called on the queue itself when it is serial, e.g.
+    /// when calling it on the main queue from the main thread.
+    pub fn exec_sync<F, R>(&self, work: F) -> R
+    where
+        F: Send + FnOnce() -> R,
+        R: Send,
+    {
+        // The closure and result are only accessed from the thread that
+        // executes the block, while the current thread is blocked in
+        // `dispatch_sync`; the bounds on `F` and `R` allow moving them
+        // there and back.
+        let work = Cell::new(Some(work));
+        let result = Cell::new(None);
+        {
+            let block = ScopedBlock::new(|| {
+                if let Some(work) = work.take() {
+                    result.set(Some(work()));
+                }
+            });
+            // SAFETY: The queue is valid, and `dispatch_sync` doesn't copy
+            // the block.
+            unsafe { ffi::dispatch_sync(self.as_raw(), block_ptr(&block)) };
+        }
+        result.into_inner().expect("block was not executed")
+    }
+
+    /// Submit a closure for asynchronous execution on the queue after the
+    /// given delay.
+    pub fn exec_after<F>(&self, delay: Duration, work: F)
+    where
+        F: Send + FnOnce() + 'static,
+    {
+        let block = RcBlock::new_once(work);
+        // SAFETY: The queue is valid, and the block is copied by
+        // `dispatch_after`.
+        unsafe { ffi::dispatch_after(time::after(delay), self.as_raw(), &*block) };
+    }
+
+    /// Execute the closure `iterations` times on the queue, and wait for all
+    /// of them to finish.
+    ///
+    /// The closure is passed the index of the iteration. If the queue is
+    /// concurrent, the iterations may execute in parallel.
+    pub fn apply<F>(&self, iterations: usize, work: F)
+    where
+        F: Sync + Fn(usize),
+    {
+        let block = ScopedBlock::new(|i: usize| work(i));
+        // SAFETY: The queue is valid, and `dispatch_apply` doesn't copy the
+        // block.
+        unsafe { ffi::dispatch_apply(iterations, self.as_raw(), block_ptr(&block)) };
+    }
+
+    /// The underlying `dispatch_queue_t`.
+    pub fn as_raw(&self) -> ffi::dispatch_queue_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl fmt::Debug for Queue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Queue")
+            .field("label", &self.label())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_label() {
+        let queue = Queue::serial("com.example.test");
+        assert_eq!(queue.label(), "com.example.test");
+        assert_eq!(queue.clone().label(), "com.example.test");
+    }
+
+    #[test]
+    fn test_serial() {
+        let queue = Queue::serial("serial");
+        let items = Arc::new(Mutex::new(Vec::new()));
+        for i in 0..10 {
+            let items = items.clone();
+            queue.exec_async(move || items.lock().unwrap().push(i));
+        }
+        let items = queue.exec_sync(move || items.lock().unwrap().clone());
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_apply() {
+        let queue = Queue::global(QueuePriority::Default);
+        let sum = AtomicUsize::new(0);
+        queue.apply(5, |i| {
+            sum.fetch_add(i, Ordering::Relaxed);
+        });
+        assert_eq!(sum.into_inner(), 10);
+    }
+}
@@ -0,0 +1,86 @@
+use core::fmt;
+use core::time::Duration;
+
+use crate::ffi;
+use crate::object::Object;
+use crate::{time, WaitTimeout};
+
+/// A counting semaphore.
+///
+/// Cloning the semaphore retains it.
+#[derive(Clone)]
+pub struct Semaphore {
+    inner: Object<ffi::dispatch_semaphore_s>,
+}
+
+impl Semaphore {
+    /// Create a new semaphore with the given initial value.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is negative.
+    pub fn new(value: isize) -> Self {
+        assert!(value >= 0, "semaphore value must not be negative");
+        // SAFETY: The value is non-negative.
+        let ptr = unsafe { ffi::dispatch_semaphore_create(value as _) };
+        // SAFETY: The semaphore is returned with +1 retain count.
+        let inner = unsafe { Object::new(ptr) }.expect("failed creating semaphore");
+        Self { inner }
+    }
+
+    /// Decrement the semaphore, waiting until that is possible or the
+    /// timeout expires.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WaitTimeout`] if the timeout expired first.
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<(), WaitTimeout> {
+        // SAFETY: The semaphore is valid.
+        let res = unsafe { ffi::dispatch_semaphore_wait(self.as_raw(), time::timeout(timeout)) };
+        if res == 0 {
+            Ok(())
+        } else {
+            Err(WaitTimeout)
+        }
+    }
+
+    /// Increment the semaphore.
+    ///
+    /// Returns `true` if a waiting thread was woken.
+    pub fn signal(&self) -> bool {
+        // SAFETY: The semaphore is valid.
+        unsafe { ffi::dispatch_semaphore_signal(self.as_raw()) != 0 }
+    }
+
+    /// The underlying `dispatch_semaphore_t`.
+    pub fn as_raw(&self) -> ffi::dispatch_semaphore_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Semaphore").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Queue;
+
+    #[test]
+    fn test_semaphore() {
+        let semaphore = Semaphore::new(1);
+        semaphore.wait(None).unwrap();
+        assert_eq!(semaphore.wait(Some(Duration::ZERO)), Err(WaitTimeout));
+
+        let signaller = semaphore.clone();
+        Queue::serial("semaphore").exec_async(move || {
+            signaller.signal();
+        });
+        semaphore.wait(None).unwrap();
+    }
+}
@@ -0,0 +1,278 @@
+use core::fmt;
+use core::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use block2::RcBlock;
+
+use crate::ffi;
+use crate::object::Object;
+use crate::{time, Queue};
+
+/// The kind of event that a [`Source`] monitors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SourceType {
+    /// `DISPATCH_SOURCE_TYPE_DATA_ADD`, data merged with
+    /// [`Source::merge_data`] is added together.
+    DataAdd,
+    /// `DISPATCH_SOURCE_TYPE_DATA_OR`, data merged with
+    /// [`Source::merge_data`] is combined with bitwise OR.
+    DataOr,
+    /// `DISPATCH_SOURCE_TYPE_TIMER`, configured with [`Source::set_timer`].
+    Timer,
+    /// `DISPATCH_SOURCE_TYPE_READ`, the handle is a file descriptor.
+    Read,
+    /// `DISPATCH_SOURCE_TYPE_WRITE`, the handle is a file descriptor.
+    Write,
+    /// `DISPATCH_SOURCE_TYPE_SIGNAL`, the handle is a signal number.
+    Signal,
+}
+
+impl SourceType {
+    fn to_raw(self) -> ffi::dispatch_source_type_t {
+        match self {
+            Self::DataAdd => ffi::DISPATCH_SOURCE_TYPE_DATA_ADD(),
+            Self::DataOr => ffi::DISPATCH_SOURCE_TYPE_DATA_OR(),
+            Self::Timer => ffi::DISPATCH_SOURCE_TYPE_TIMER(),
+            Self::Read => ffi::DISPATCH_SOURCE_TYPE_READ(),
+            Self::Write => ffi::DISPATCH_SOURCE_TYPE_WRITE(),
+            Self::Signal => ffi::DISPATCH_SOURCE_TYPE_SIGNAL(),
+        }
+    }
+}
+
+/// A dispatch source, which submits its event handler to a queue whenever
+/// an event occurs.
+///
+/// Sources are created suspended, so that their handlers can be set before
+/// any events are delivered; call [`resume`][Self::resume] to start it.
+///
+/// The source is cancelled when dropped.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use dispatch2::{Queue, Source};
+///
+/// let source = Source::timer(&Queue::main());
+/// source.set_timer(Duration::ZERO, Some(Duration::from_secs(1)), Duration::ZERO);
+/// source.set_event_handler(|| println!("tick"));
+/// source.resume();
+/// ```
+pub struct Source {
+    inner: Object<ffi::dispatch_source_s>,
+    /// `libdispatch` crashes if a suspended source is released, so we keep
+    /// track of the suspension count ourselves.
+    suspend_count: AtomicUsize,
+}
+
+impl Source {
+    /// Create a new source monitoring the given handle.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The handle and mask must be valid for the source type; e.g. the file
+    /// descriptor of a [`SourceType::Read`] source must stay open until the
+    /// source has been cancelled.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if `libdispatch` fails to create the source, e.g. because the
+    /// handle is invalid.
+    pub unsafe fn new(type_: SourceType, handle: usize, mask: usize, queue: &Queue) -> Self {
+        // SAFETY: Upheld by the caller.
+        let ptr =
+            unsafe { ffi::dispatch_source_create(type_.to_raw(), handle, mask, queue.as_raw()) };
+        // SAFETY: The source is returned with +1 retain count.
+        let inner = unsafe { Object::new(ptr) }.expect("failed creating source");
+        Self {
+            inner,
+            suspend_count: AtomicUsize::new(1),
+        }
+    }
+
+    /// Create a new timer source, configured with
+    /// [`set_timer`][Self::set_timer].
+    pub fn timer(queue: &Queue) -> Self {
+        // SAFETY: Timer sources don't use the handle or mask.
+        unsafe { Self::new(SourceType::Timer, 0, 0, queue) }
+    }
+
+    /// Create a new source that coalesces the data merged with
+    /// [`merge_data`][Self::merge_data] by adding it.
+    pub fn data_add(queue: &Queue) -> Self {
+        // SAFETY: Data sources don't use the handle or mask.
+        unsafe { Self::new(SourceType::DataAdd, 0, 0, queue) }
+    }
+
+    /// Create a new source that coalesces the data merged with
+    /// [`merge_data`][Self::merge_data] using bitwise OR.
+    pub fn data_or(queue: &Queue) -> Self {
+        // SAFETY: Data sources don't use the handle or mask.
+        unsafe { Self::new(SourceType::DataOr, 0, 0, queue) }
+    }
+
+    /// Set the closure that is submitted to the target queue for each
+    /// event.
+    ///
+    /// The handler is never executed concurrently with itself.
+    pub fn set_event_handler<F>(&self, handler: F)
+    where
+        F: Send + FnMut() + 'static,
+    {
+        let block = RcBlock::new_mut(handler);
+        // SAFETY: The source is valid, and the block is copied.
+        unsafe { ffi::dispatch_source_set_event_handler(self.as_raw(), &*block) };
+    }
+
+    /// Set the closure that is submitted to the target queue once the
+    /// source has been cancelled.
+    pub fn set_cancel_handler<F>(&self, handler: F)
+    where
+        F: Send + FnOnce() + 'static,
+    {
+        let block = RcBlock::new_once(handler);
+        // SAFETY: The source is valid, and the block is copied.
+        unsafe { ffi::dispatch_source_set_cancel_handler(self.as_raw(), &*block) };
+    }
+
+    /// Configure a timer source to first fire `start` from now, and then
+    /// repeatedly every `interval` (or only once if `None`).
+    ///
+    /// The system may defer the timer by up to `leeway` to improve power
+    /// consumption.
+    pub fn set_timer(&self, start: Duration, interval: Option<Duration>, leeway: Duration) {
+        let interval = interval
+            .map(time::nanos)
+            .unwrap_or(ffi::DISPATCH_TIME_FOREVER);
+        // SAFETY: The source is valid. Does nothing on non-timer sources.
+        unsafe {
+            ffi::dispatch_source_set_timer(
+                self.as_raw(),
+                time::after(start),
+                interval,
+                time::nanos(leeway),
+            )
+        };
+    }
+
+    /// The data of the event currently being handled, e.g. the coalesced
+    /// value for data sources, or the number of times a timer fired.
+    ///
+    /// Should be called from within the event handler.
+    pub fn data(&self) -> usize {
+        // SAFETY: The source is valid.
+        unsafe { ffi::dispatch_source_get_data(self.as_raw()) }
+    }
+
+    /// Merge data into a [`SourceType::DataAdd`] or [`SourceType::DataOr`]
+    /// source, and submit its event handler.
+    pub fn merge_data(&self, value: usize) {
+        // SAFETY: The source is valid. Does nothing on other sources.
+        unsafe { ffi::dispatch_source_merge_data(self.as_raw(), value) };
+    }
+
+    /// Resume the source, allowing events to be delivered.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source is not suspended.
+    pub fn resume(&self) {
+        self.suspend_count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                count.checked_sub(1)
+            })
+            .expect("source is not suspended");
+        // SAFETY: The source is valid and suspended.
+        unsafe { ffi::dispatch_resume(self.as_raw().cast()) };
+    }
+
+    /// Suspend the source, deferring the delivery of events until it is
+    /// resumed.
+    pub fn suspend(&self) {
+        self.suspend_count.fetch_add(1, Ordering::AcqRel);
+        // SAFETY: The source is valid.
+        unsafe { ffi::dispatch_suspend(self.as_raw().cast()) };
+    }
+
+    /// Cancel the source, preventing further events from being delivered.
+    ///
+    /// The cancel handler is submitted once any currently executing event
+    /// handler has finished.
+    pub fn cancel(&self) {
+        // SAFETY: The source is valid.
+        unsafe { ffi::dispatch_source_cancel(self.as_raw()) };
+    }
+
+    /// Whether the source has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        // SAFETY: The source is valid.
+        unsafe { ffi::dispatch_source_testcancel(self.as_raw()) != 0 }
+    }
+
+    /// The underlying `dispatch_source_t`.
+    pub fn as_raw(&self) -> ffi::dispatch_source_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Drop for Source {
+    fn drop(&mut self) {
+        self.cancel();
+        // Resume the source, so that it can be released (and the cancel
+        // handler can run).
+        for _ in 0..*self.suspend_count.get_mut() {
+            // SAFETY: The source is valid and suspended.
+            unsafe { ffi::dispatch_resume(self.as_raw().cast()) };
+        }
+    }
+}
+
+impl fmt::Debug for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Source")
+            .field("suspend_count", &self.suspend_count)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Semaphore;
+
+    #[test]
+    fn test_data_source() {
+        let semaphore = Semaphore::new(0);
+        let source = Source::data_add(&Queue::serial("source"));
+        let signaller = semaphore.clone();
+        source.set_event_handler(move || {
+            signaller.signal();
+        });
+        source.resume();
+
+        source.merge_data(1);
+        semaphore.wait(None).unwrap();
+        assert!(!source.is_cancelled());
+    }
+
+    #[test]
+    fn test_drop_suspended() {
+        let source = Source::timer(&Queue::serial("timer"));
+        source.suspend();
+        source.set_cancel_handler(|| {});
+    }
+
+    #[test]
+    #[should_panic = "source is not suspended"]
+    fn test_over_resume() {
+        let source = Source::data_or(&Queue::serial("source"));
+        source.resume();
+        source.resume();
+    }
+}
@@ -0,0 +1,37 @@
+use core::fmt;
+use core::time::Duration;
+
+use crate::ffi;
+
+/// The error returned when waiting on a [`Group`] or [`Semaphore`] timed
+/// out.
+///
+/// [`Group`]: crate::Group
+/// [`Semaphore`]: crate::Semaphore
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct WaitTimeout;
+
+impl fmt::Display for WaitTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("timed out while waiting")
+    }
+}
+
+impl std::error::Error for WaitTimeout {}
+
+/// The time `delay` from now.
+pub(crate) fn after(delay: Duration) -> ffi::dispatch_time_t {
+    let nanos = i64::try_from(delay.as_nanos()).unwrap_or(i64::MAX);
+    // SAFETY: Always safe to call.
+    unsafe { ffi::dispatch_time(ffi::DISPATCH_TIME_NOW, nanos) }
+}
+
+/// The time `timeout` from now, or forever if `None`.
+pub(crate) fn timeout(timeout: Option<Duration>) -> ffi::dispatch_time_t {
+    timeout.map(after).unwrap_or(ffi::DISPATCH_TIME_FOREVER)
+}
+
+/// A duration in nanoseconds, as used for intervals.
+pub(crate) fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
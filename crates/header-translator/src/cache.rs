@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::iter;
 use std::mem;
 
 use crate::availability::Availability;
@@ -8,7 +9,7 @@ use crate::id::ItemIdentifier;
 use crate::method::Method;
 use crate::output::Output;
 use crate::rust_type::Ownership;
-use crate::stmt::Stmt;
+use crate::stmt::{ClassGeneric, Stmt};
 
 #[derive(Debug, PartialEq, Clone)]
 struct MethodCache {
@@ -21,6 +22,11 @@ struct MethodCache {
 struct ClassCache {
     /// Methods that should be duplicated onto any subclass.
     to_emit: Vec<MethodCache>,
+    /// Selectors of methods marked `NS_REQUIRES_SUPER`, including those
+    /// declared in categories.
+    requires_super: Vec<String>,
+    /// The generic parameters of the class.
+    generics: Vec<ClassGeneric>,
     // We don't need availability here, since a superclass' availability
     // should always be greater than the subclass'.
 }
@@ -33,6 +39,14 @@ impl ClassCache {
     }
 }
 
+fn extend_unique(vec: &mut Vec<String>, items: impl IntoIterator<Item = String>) {
+    for item in items {
+        if !vec.contains(&item) {
+            vec.push(item);
+        }
+    }
+}
+
 /// A helper struct for doing global analysis on the output.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Cache<'a> {
@@ -55,10 +69,35 @@ impl<'a> Cache<'a> {
                         let cache = classes.entry(cls.clone()).or_default();
                         cache.to_emit.push(method_cache);
                     }
-                    if let Stmt::ClassDecl { id, ownership, .. } = stmt {
-                        if *ownership != Ownership::default() {
-                            ownership_map.insert(id.name.clone(), ownership.clone());
+                    match stmt {
+                        Stmt::ClassDecl {
+                            id,
+                            generics,
+                            ownership,
+                            requires_super,
+                            ..
+                        } => {
+                            if *ownership != Ownership::default() {
+                                ownership_map.insert(id.name.clone(), ownership.clone());
+                            }
+                            let cache = classes.entry(id.clone()).or_default();
+                            cache.generics = generics.clone();
+                            extend_unique(
+                                &mut cache.requires_super,
+                                requires_super.iter().cloned(),
+                            );
                         }
+                        Stmt::Methods { cls, methods, .. } => {
+                            let cache = classes.entry(cls.clone()).or_default();
+                            extend_unique(
+                                &mut cache.requires_super,
+                                methods
+                                    .iter()
+                                    .filter(|method| method.requires_super)
+                                    .map(|method| method.selector.clone()),
+                            );
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -164,11 +203,22 @@ impl<'a> Cache<'a> {
                     id,
                     generics,
                     superclasses,
+                    requires_super,
                     ..
                 } => {
                     let _span = debug_span!("Stmt::ClassDecl", ?id).entered();
                     let data = self.config.class_data.get(&id.name);
 
+                    // `RequiresSuper` is implemented directly for each class
+                    // (it can't defer to the superclass' impl, since that
+                    // may not exist), so include the selectors from
+                    // categories and from all superclasses.
+                    for cls in iter::once(&*id).chain(superclasses.iter().map(|(cls, _)| cls)) {
+                        if let Some(cache) = self.classes.get(cls) {
+                            extend_unique(requires_super, cache.requires_super.iter().cloned());
+                        }
+                    }
+
                     // Used for duplicate checking (sometimes the subclass
                     // defines the same method that the superclass did).
                     let mut seen_methods: Vec<_> = self
@@ -217,9 +267,18 @@ impl<'a> Cache<'a> {
                         }
                     }
                 }
-                Stmt::Methods { cls, methods, .. } => {
+                Stmt::Methods {
+                    cls,
+                    generics,
+                    methods,
+                    ..
+                } => {
+                    self.update_generics(generics, cls);
                     self.update_methods(methods, &cls.name);
                 }
+                Stmt::ProtocolImpl { cls, generics, .. } => {
+                    self.update_generics(generics, cls);
+                }
                 Stmt::ProtocolDecl { id, methods, .. } => {
                     self.update_methods(methods, &id.name);
                 }
@@ -258,6 +317,18 @@ impl<'a> Cache<'a> {
         }
     }
 
+    /// Categories may omit the bounds of the class' generic parameters, but
+    /// we need them in every impl.
+    fn update_generics(&self, generics: &mut [ClassGeneric], cls: &ItemIdentifier) {
+        if let Some(cache) = self.classes.get(cls) {
+            for (generic, class_generic) in generics.iter_mut().zip(&cache.generics) {
+                if generic.bound.is_none() {
+                    generic.bound = class_generic.bound.clone();
+                }
+            }
+        }
+    }
+
     fn update_methods(&self, methods: &mut [Method], self_means: &str) {
         for method in methods {
             // Beware! We make instance methods return `Owned` as well, though
@@ -32,6 +32,17 @@ impl<'a> Context<'a> {
         }
     }
 
+    #[cfg(test)]
+    pub fn new_test(config: &'a Config) -> Self {
+        Self {
+            config,
+            macro_invocations: Default::default(),
+            framework_dir: PathBuf::from("/System/Library/Frameworks"),
+            include_dir: PathBuf::from("/usr/include"),
+            system_headers: HashSet::new(),
+        }
+    }
+
     pub fn get_library_and_file_name(
         &self,
         entity: &Entity<'_>,
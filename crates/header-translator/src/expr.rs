@@ -1,11 +1,15 @@
+use std::cell::RefCell;
 use std::fmt;
 use std::fmt::Write;
 
 use clang::token::TokenKind;
-use clang::{Entity, EntityKind, EntityVisitResult};
+use clang::{Entity, EntityKind, EntityVisitResult, Type, TypeKind};
 
 use crate::context::Context;
+use crate::id::ItemIdentifier;
 use crate::immediate_children;
+use crate::method::handle_reserved;
+use crate::rust_type::Ty;
 use crate::unexposed_attr::UnexposedAttr;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -68,7 +72,12 @@ impl Expr {
         res
     }
 
-    pub fn parse_var(entity: &Entity<'_>) -> Option<Self> {
+    pub fn parse_var(entity: &Entity<'_>, context: &Context<'_>) -> Option<Self> {
+        // Struct initializers can't be translated token by token
+        if unwrap_expr(*entity).get_kind() == EntityKind::InitListExpr {
+            let translator = Translator::new(context, true);
+            return translator.value(entity).map(Self::String);
+        }
         Self::parse(entity, &[])
     }
 
@@ -147,3 +156,847 @@ impl fmt::Display for Expr {
         }
     }
 }
+
+/// The translated body of a `static inline` function.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FnBody {
+    rust: String,
+    /// The types used in the body, which may require extra features.
+    types: Vec<Ty>,
+}
+
+impl FnBody {
+    /// Translate the body of a `static inline` function.
+    ///
+    /// Returns `None` if the body contains anything that we don't know how
+    /// to translate.
+    pub fn parse(entity: &Entity<'_>, context: &Context<'_>) -> Option<Self> {
+        let mut translator = Translator::new(context, false);
+        let rust = translator.body(entity)?;
+        Some(Self {
+            rust,
+            types: translator.types.into_inner(),
+        })
+    }
+
+    pub fn visit_required_types(&self, f: &mut impl FnMut(&ItemIdentifier)) {
+        for ty in &self.types {
+            ty.visit_required_types(f);
+        }
+    }
+}
+
+impl fmt::Display for FnBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rust)
+    }
+}
+
+/// A translated expression, along with whether it is a Rust `bool`.
+///
+/// C doesn't have a separate boolean type for the result of comparisons and
+/// logical operators, so we need to keep track of this ourselves.
+struct Translated {
+    rust: String,
+    is_bool: bool,
+}
+
+impl Translated {
+    fn value(rust: String) -> Self {
+        Self {
+            rust,
+            is_bool: false,
+        }
+    }
+
+    fn bool(rust: String) -> Self {
+        Self {
+            rust,
+            is_bool: true,
+        }
+    }
+}
+
+enum Line {
+    Let {
+        name: String,
+        ty: String,
+        value: String,
+    },
+    Other(String),
+}
+
+/// A struct variable that has been declared without an initializer, and
+/// whose fields are being assigned one by one.
+struct PendingStruct {
+    name: String,
+    ty: String,
+    fields: Vec<(String, Option<String>)>,
+}
+
+/// Translates simple C expressions and statements to Rust, by walking the
+/// AST.
+///
+/// Only the subset needed for `static inline` functions doing arithmetic on
+/// structs, and for initializers of constant structs, is supported.
+struct Translator<'a, 'c> {
+    context: &'a Context<'c>,
+    /// Parameters and local variables, which are renamed the same way as
+    /// the function's arguments.
+    locals: Vec<String>,
+    params: Vec<String>,
+    /// Local variables that are assigned to after their declaration.
+    assigned: Vec<String>,
+    /// Whether we're translating the initializer of a static, where
+    /// function calls and reading other statics are not allowed.
+    in_static: bool,
+    /// The types that have been used.
+    types: RefCell<Vec<Ty>>,
+}
+
+fn is_arithmetic(kind: TypeKind) -> bool {
+    use TypeKind::*;
+    matches!(
+        kind,
+        CharS
+            | CharU
+            | SChar
+            | UChar
+            | Short
+            | UShort
+            | Int
+            | UInt
+            | Long
+            | ULong
+            | LongLong
+            | ULongLong
+            | Float
+            | Double
+    )
+}
+
+/// Unsigned integers wrap around on overflow in C.
+fn is_unsigned(kind: TypeKind) -> bool {
+    use TypeKind::*;
+    matches!(kind, CharU | UChar | UShort | UInt | ULong | ULongLong)
+}
+
+fn tokens(entity: &Entity<'_>) -> Vec<String> {
+    entity
+        .get_range()
+        .map(|range| {
+            range
+                .tokenize()
+                .into_iter()
+                .map(|token| token.get_spelling())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Skip over implicit casts and parentheses.
+fn unwrap_expr<'tu>(entity: Entity<'tu>) -> Entity<'tu> {
+    match (entity.get_kind(), &*entity.get_children()) {
+        (EntityKind::UnexposedExpr | EntityKind::ParenExpr, [child]) => unwrap_expr(*child),
+        _ => entity,
+    }
+}
+
+/// The names of the fields in the struct that the type refers to.
+fn struct_fields(ty: Type<'_>) -> Option<Vec<String>> {
+    let ty = ty.get_canonical_type();
+    if ty.get_kind() != TypeKind::Record {
+        return None;
+    }
+    let declaration = ty.get_declaration()?;
+    let fields = declaration
+        .get_children()
+        .into_iter()
+        .filter(|entity| entity.get_kind() == EntityKind::FieldDecl)
+        .map(|entity| entity.get_name().map(|name| handle_reserved(&name)))
+        .collect::<Option<Vec<_>>>()?;
+    if fields.is_empty() {
+        None
+    } else {
+        Some(fields)
+    }
+}
+
+/// Whether the expression is a single group of parentheses, like `(a + b)`.
+fn is_parenthesized(rust: &str) -> bool {
+    if !rust.starts_with('(') {
+        return false;
+    }
+    let mut depth = 0;
+    for (i, c) in rust.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return i == rust.len() - 1;
+        }
+    }
+    false
+}
+
+/// Convert a C floating-point literal to Rust, e.g. `.5f` to `0.5`.
+///
+/// Rust doesn't allow the `f`/`l` suffixes, nor a missing integer or
+/// fractional part.
+fn float_literal(lit: &str) -> String {
+    let lit = lit.trim_end_matches(['f', 'F', 'l', 'L']);
+    let mut res = String::with_capacity(lit.len() + 2);
+    if lit.starts_with('.') {
+        res.push('0');
+    }
+    let mut chars = lit.chars().peekable();
+    while let Some(c) = chars.next() {
+        res.push(c);
+        if c == '.' && !matches!(chars.peek(), Some(c) if c.is_ascii_digit()) {
+            res.push('0');
+        }
+    }
+    res
+}
+
+impl<'a, 'c> Translator<'a, 'c> {
+    fn new(context: &'a Context<'c>, in_static: bool) -> Self {
+        Self {
+            context,
+            locals: Vec::new(),
+            params: Vec::new(),
+            assigned: Vec::new(),
+            in_static,
+            types: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn local_name(name: &str) -> String {
+        handle_reserved(&crate::to_snake_case(name))
+    }
+
+    fn ty(&self, ty: Type<'_>) -> String {
+        let ty = Ty::parse_static(ty, self.context);
+        let res = ty.to_string();
+        self.types.borrow_mut().push(ty);
+        res
+    }
+
+    /// Prepare an expression for having a method called on it.
+    fn receiver(&self, rust: String, ty: Type<'_>) -> String {
+        if rust.starts_with(|c: char| c.is_ascii_digit()) {
+            // The type of integer literals must be known
+            format!("({rust} as {})", self.ty(ty))
+        } else if rust
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+            || is_parenthesized(&rust)
+        {
+            rust
+        } else {
+            format!("({rust})")
+        }
+    }
+
+    /// The method to use instead of an arithmetic operator on an unsigned
+    /// integer, to get the same wrapping behaviour as in C.
+    fn wrapping_method(op: &str, ty: Type<'_>) -> Option<&'static str> {
+        if !is_unsigned(ty.get_canonical_type().get_kind()) {
+            return None;
+        }
+        match op {
+            "+" => Some("wrapping_add"),
+            "-" => Some("wrapping_sub"),
+            "*" => Some("wrapping_mul"),
+            _ => None,
+        }
+    }
+
+    /// A binary arithmetic operation.
+    fn arithmetic(
+        &self,
+        lhs: &Entity<'_>,
+        op: &str,
+        rhs: &Entity<'_>,
+        ty: Type<'_>,
+    ) -> Option<String> {
+        let lhs_rust = self.value(lhs)?;
+        let rhs_rust = self.value(rhs)?;
+        if let Some(method) = Self::wrapping_method(op, ty) {
+            let lhs_rust = self.receiver(lhs_rust, ty);
+            Some(format!("{lhs_rust}.{method}({rhs_rust})"))
+        } else {
+            Some(format!("{lhs_rust} {op} {rhs_rust}"))
+        }
+    }
+
+    /// An expression that is used as a value.
+    fn value(&self, entity: &Entity<'_>) -> Option<String> {
+        let res = self.expr(entity)?;
+        if res.is_bool {
+            debug!(?entity, "boolean used as a value");
+            return None;
+        }
+        Some(res.rust)
+    }
+
+    /// An expression that is used as a condition.
+    fn condition(&self, entity: &Entity<'_>) -> Option<String> {
+        let res = self.expr(entity)?;
+        if !res.is_bool {
+            debug!(?entity, "value used as a condition");
+            return None;
+        }
+        Some(res.rust)
+    }
+
+    fn expr(&self, entity: &Entity<'_>) -> Option<Translated> {
+        let children = entity.get_children();
+        match entity.get_kind() {
+            // Implicit casts
+            EntityKind::UnexposedExpr => {
+                let child = match &*children {
+                    [child] => child,
+                    _ => return None,
+                };
+                let to = entity.get_type()?;
+                let from = child.get_type()?;
+                if to.get_display_name() == "BOOL" && from.get_display_name() != "BOOL" {
+                    let inner = self.expr(child)?;
+                    return if inner.is_bool {
+                        Some(Translated::value(format!("Bool::new({})", inner.rust)))
+                    } else {
+                        None
+                    };
+                }
+                let to_kind = to.get_canonical_type().get_kind();
+                let from_kind = from.get_canonical_type().get_kind();
+                if to_kind != from_kind && is_arithmetic(to_kind) && is_arithmetic(from_kind) {
+                    let inner = self.value(child)?;
+                    Some(Translated::value(format!("({inner} as {})", self.ty(to))))
+                } else {
+                    self.expr(child)
+                }
+            }
+            EntityKind::ParenExpr => {
+                let child = match &*children {
+                    [child] => child,
+                    _ => return None,
+                };
+                let inner = self.expr(child)?;
+                Some(Translated {
+                    rust: format!("({})", inner.rust),
+                    is_bool: inner.is_bool,
+                })
+            }
+            EntityKind::IntegerLiteral => {
+                let lit = match &*tokens(entity) {
+                    [lit] => lit.clone(),
+                    _ => return None,
+                };
+                let lit = lit.trim_end_matches(['u', 'U', 'l', 'L']);
+                Some(Translated::value(lit.replace("0X", "0x")))
+            }
+            EntityKind::FloatingLiteral => {
+                let lit = match &*tokens(entity) {
+                    [lit] => lit.clone(),
+                    _ => return None,
+                };
+                Some(Translated::value(float_literal(&lit)))
+            }
+            EntityKind::ObjCBoolLiteralExpr => match &*tokens(entity) {
+                [lit] if lit == "YES" || lit == "__objc_yes" => {
+                    Some(Translated::value("Bool::YES".into()))
+                }
+                [lit] if lit == "NO" || lit == "__objc_no" => {
+                    Some(Translated::value("Bool::NO".into()))
+                }
+                _ => None,
+            },
+            EntityKind::DeclRefExpr => {
+                let name = entity.get_name()?;
+                if self.locals.contains(&name) {
+                    return Some(Translated::value(Self::local_name(&name)));
+                }
+                let declaration = entity.get_reference()?;
+                match declaration.get_kind() {
+                    EntityKind::EnumConstantDecl => Some(Translated::value(name)),
+                    EntityKind::VarDecl => {
+                        let skipped = self
+                            .context
+                            .statics
+                            .get(&name)
+                            .map(|data| data.skipped)
+                            .unwrap_or_default();
+                        if skipped {
+                            return None;
+                        }
+                        let has_value = declaration
+                            .get_children()
+                            .iter()
+                            .any(|child| child.is_expression());
+                        if has_value {
+                            // Emitted as a Rust `static` with a value
+                            Some(Translated::value(name))
+                        } else if self.in_static {
+                            None
+                        } else {
+                            // Reading an `extern` static is unsafe
+                            Some(Translated::value(format!("unsafe {{ {name} }}")))
+                        }
+                    }
+                    _ => None,
+                }
+            }
+            EntityKind::MemberRefExpr => {
+                let base = match &*children {
+                    [base] => base,
+                    _ => return None,
+                };
+                // `->` would require dereferencing a pointer
+                if tokens(entity).get(tokens(base).len()).map(|s| &**s) != Some(".") {
+                    return None;
+                }
+                let base = self.value(base)?;
+                let field = handle_reserved(&entity.get_name()?);
+                Some(Translated::value(format!("{base}.{field}")))
+            }
+            EntityKind::BinaryOperator => {
+                let (lhs, rhs) = match &*children {
+                    [lhs, rhs] => (lhs, rhs),
+                    _ => return None,
+                };
+                let op = tokens(entity).get(tokens(lhs).len())?.clone();
+                match &*op {
+                    "+" | "-" | "*" | "/" | "%" | "&" | "|" | "^" | "<<" | ">>" => {
+                        let rust = self.arithmetic(lhs, &op, rhs, entity.get_type()?)?;
+                        Some(Translated::value(rust))
+                    }
+                    "<" | ">" | "<=" | ">=" | "==" | "!=" => {
+                        let lhs = self.value(lhs)?;
+                        let rhs = self.value(rhs)?;
+                        Some(Translated::bool(format!("{lhs} {op} {rhs}")))
+                    }
+                    "&&" | "||" => {
+                        let lhs = self.condition(lhs)?;
+                        let rhs = self.condition(rhs)?;
+                        Some(Translated::bool(format!("{lhs} {op} {rhs}")))
+                    }
+                    _ => None,
+                }
+            }
+            EntityKind::UnaryOperator => {
+                let operand = match &*children {
+                    [operand] => operand,
+                    _ => return None,
+                };
+                let all_tokens = tokens(entity);
+                // Postfix operators (`x++`) are not supported
+                if all_tokens.len() <= tokens(operand).len() {
+                    return None;
+                }
+                match &*all_tokens[0] {
+                    "-" => {
+                        let ty = entity.get_type()?;
+                        let value = self.value(operand)?;
+                        if is_unsigned(ty.get_canonical_type().get_kind()) {
+                            let value = self.receiver(value, ty);
+                            Some(Translated::value(format!("{value}.wrapping_neg()")))
+                        } else {
+                            Some(Translated::value(format!("-{value}")))
+                        }
+                    }
+                    "~" => Some(Translated::value(format!("!{}", self.value(operand)?))),
+                    "!" => Some(Translated::bool(format!("!{}", self.condition(operand)?))),
+                    _ => None,
+                }
+            }
+            EntityKind::ConditionalOperator => {
+                let (condition, then, else_) = match &*children {
+                    [condition, then, else_] => (condition, then, else_),
+                    _ => return None,
+                };
+                let condition = self.condition(condition)?;
+                let then = self.value(then)?;
+                let else_ = self.value(else_)?;
+                Some(Translated::value(format!(
+                    "if {condition} {{ {then} }} else {{ {else_} }}"
+                )))
+            }
+            EntityKind::CStyleCastExpr => {
+                let inner = children.iter().rfind(|child| child.is_expression())?;
+                let ty = entity.get_type()?;
+                if !is_arithmetic(ty.get_canonical_type().get_kind()) {
+                    return None;
+                }
+                let inner = self.value(inner)?;
+                Some(Translated::value(format!("({inner} as {})", self.ty(ty))))
+            }
+            EntityKind::CallExpr => {
+                if self.in_static {
+                    return None;
+                }
+                let (callee, arguments) = children.split_first()?;
+                let callee = unwrap_expr(*callee);
+                if callee.get_kind() != EntityKind::DeclRefExpr {
+                    return None;
+                }
+                let function = callee.get_reference()?;
+                if function.get_kind() != EntityKind::FunctionDecl || function.is_variadic() {
+                    return None;
+                }
+                let name = callee.get_name()?;
+                let data = self.context.fns.get(&name).cloned().unwrap_or_default();
+                if data.skipped {
+                    return None;
+                }
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.value(argument))
+                    .collect::<Option<Vec<_>>>()?;
+                let call = format!("{name}({})", arguments.join(", "));
+                if data.unsafe_ {
+                    Some(Translated::value(format!("unsafe {{ {call} }}")))
+                } else {
+                    Some(Translated::value(call))
+                }
+            }
+            EntityKind::InitListExpr => {
+                let ty = entity.get_type()?;
+                let fields = struct_fields(ty)?;
+                if fields.len() != children.len() {
+                    // Partially initialized structs
+                    return None;
+                }
+                let values = fields
+                    .iter()
+                    .zip(&children)
+                    .map(|(field, value)| Some(format!("{field}: {}", self.value(value)?)))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Translated::value(format!(
+                    "{} {{ {} }}",
+                    self.ty(ty),
+                    values.join(", ")
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the local variable and field in `var.field = value`.
+    fn field_assignment(&self, lhs: &Entity<'_>) -> Option<(String, String)> {
+        if lhs.get_kind() != EntityKind::MemberRefExpr {
+            return None;
+        }
+        let base = match &*lhs.get_children() {
+            [base] => unwrap_expr(*base),
+            _ => return None,
+        };
+        if base.get_kind() != EntityKind::DeclRefExpr {
+            return None;
+        }
+        Some((base.get_name()?, handle_reserved(&lhs.get_name()?)))
+    }
+
+    fn flush(&self, pending: &mut Option<PendingStruct>, lines: &mut Vec<Line>) -> Option<()> {
+        if let Some(PendingStruct { name, ty, fields }) = pending.take() {
+            let values = fields
+                .into_iter()
+                .map(|(field, value)| Some(format!("{field}: {}", value?)))
+                .collect::<Option<Vec<_>>>();
+            let values = match values {
+                Some(values) => values,
+                None => {
+                    debug!(name, "struct was not fully initialized");
+                    return None;
+                }
+            };
+            lines.push(Line::Let {
+                name: Self::local_name(&name),
+                value: format!("{ty} {{ {} }}", values.join(", ")),
+                ty,
+            });
+        }
+        Some(())
+    }
+
+    fn body(&mut self, entity: &Entity<'_>) -> Option<String> {
+        let mut body = None;
+        for child in entity.get_children() {
+            match child.get_kind() {
+                EntityKind::ParmDecl => {
+                    let name = child.get_name()?;
+                    self.locals.push(name.clone());
+                    self.params.push(name);
+                }
+                EntityKind::CompoundStmt => body = Some(child),
+                _ => {}
+            }
+        }
+        let statements = body?.get_children();
+
+        let mut lines = Vec::new();
+        let mut pending: Option<PendingStruct> = None;
+
+        for (i, statement) in statements.iter().enumerate() {
+            let is_last = i == statements.len() - 1;
+
+            // Returning a struct that was initialized field by field
+            if let (Some(PendingStruct { name, .. }), EntityKind::ReturnStmt, [value], true) = (
+                &pending,
+                statement.get_kind(),
+                &*statement.get_children(),
+                is_last,
+            ) {
+                let value = unwrap_expr(*value);
+                if value.get_kind() == EntityKind::DeclRefExpr
+                    && value.get_name().as_ref() == Some(name)
+                {
+                    let mut struct_lines = Vec::new();
+                    self.flush(&mut pending, &mut struct_lines)?;
+                    if let [Line::Let { value, .. }] = &*struct_lines {
+                        lines.push(Line::Other(value.clone()));
+                        continue;
+                    }
+                    return None;
+                }
+            }
+
+            // Assigning fields of a struct that is being initialized
+            if let (Some(pending), EntityKind::BinaryOperator, [lhs, rhs]) = (
+                &mut pending,
+                statement.get_kind(),
+                &*statement.get_children(),
+            ) {
+                if tokens(statement).get(tokens(lhs).len()).map(|s| &**s) == Some("=") {
+                    if let Some((name, field)) = self.field_assignment(lhs) {
+                        if name == pending.name {
+                            let value = self.value(rhs)?;
+                            let (_, slot) = pending.fields.iter_mut().find(|(f, _)| *f == field)?;
+                            *slot = Some(value);
+                            continue;
+                        }
+                    }
+                }
+            }
+            self.flush(&mut pending, &mut lines)?;
+
+            match statement.get_kind() {
+                EntityKind::DeclStmt => {
+                    for var in statement.get_children() {
+                        if var.get_kind() != EntityKind::VarDecl {
+                            return None;
+                        }
+                        let name = var.get_name()?;
+                        let ty = var.get_type()?;
+                        self.locals.push(name.clone());
+
+                        let init = var
+                            .get_children()
+                            .into_iter()
+                            .rfind(|child| child.is_expression());
+                        if let Some(init) = init {
+                            lines.push(Line::Let {
+                                name: Self::local_name(&name),
+                                ty: self.ty(ty),
+                                value: self.value(&init)?,
+                            });
+                        } else {
+                            // Must be assigned field by field afterwards
+                            self.flush(&mut pending, &mut lines)?;
+                            pending = Some(PendingStruct {
+                                name,
+                                ty: self.ty(ty),
+                                fields: struct_fields(ty)?
+                                    .into_iter()
+                                    .map(|field| (field, None))
+                                    .collect(),
+                            });
+                        }
+                    }
+                }
+                EntityKind::ReturnStmt => match &*statement.get_children() {
+                    [] if is_last => {}
+                    [] => lines.push(Line::Other("return;".into())),
+                    [value] if is_last => lines.push(Line::Other(self.value(value)?)),
+                    [value] => lines.push(Line::Other(format!("return {};", self.value(value)?))),
+                    _ => return None,
+                },
+                EntityKind::BinaryOperator | EntityKind::CompoundAssignOperator => {
+                    let (lhs, rhs) = match &*statement.get_children() {
+                        [lhs, rhs] => (*lhs, *rhs),
+                        _ => return None,
+                    };
+                    let op = tokens(statement).get(tokens(&lhs).len())?.clone();
+                    if !op.ends_with('=') || matches!(&*op, "==" | "!=" | "<=" | ">=") {
+                        return None;
+                    }
+                    let lhs_inner = unwrap_expr(lhs);
+                    let target = match lhs_inner.get_kind() {
+                        EntityKind::DeclRefExpr => lhs_inner.get_name(),
+                        EntityKind::MemberRefExpr => {
+                            self.field_assignment(&lhs_inner).map(|(name, _)| name)
+                        }
+                        _ => None,
+                    }?;
+                    if self.params.contains(&target) || !self.locals.contains(&target) {
+                        // Parameters are not mutable, and we don't want to
+                        // write to globals.
+                        return None;
+                    }
+                    self.assigned.push(Self::local_name(&target));
+                    let arithmetic_op = op.trim_end_matches('=');
+                    let ty = lhs.get_type()?;
+                    if Self::wrapping_method(arithmetic_op, ty).is_some() {
+                        let value = self.arithmetic(&lhs, arithmetic_op, &rhs, ty)?;
+                        let lhs = self.value(&lhs)?;
+                        lines.push(Line::Other(format!("{lhs} = {value};")));
+                    } else {
+                        let lhs = self.value(&lhs)?;
+                        let rhs = self.value(&rhs)?;
+                        lines.push(Line::Other(format!("{lhs} {op} {rhs};")));
+                    }
+                }
+                EntityKind::CallExpr => {
+                    lines.push(Line::Other(format!("{};", self.value(statement)?)));
+                }
+                _ => {
+                    debug!(?statement, "unsupported statement");
+                    return None;
+                }
+            }
+        }
+        self.flush(&mut pending, &mut lines)?;
+
+        let lines: Vec<_> = lines
+            .into_iter()
+            .map(|line| match line {
+                Line::Let { name, ty, value } => {
+                    let mut_ = if self.assigned.contains(&name) {
+                        "mut "
+                    } else {
+                        ""
+                    };
+                    format!("let {mut_}{name}: {ty} = {value};")
+                }
+                Line::Other(line) => line,
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use clang::{Clang, Index, Unsaved};
+
+    use super::*;
+    use crate::config::Config;
+
+    const HEADER: &str = r#"
+        typedef long MyInt;
+
+        extern long ExternValue;
+        static const long ConstValue = 3;
+
+        long UnsafeFn(long x);
+        long SafeFn(long x);
+        long SkippedFn(long x);
+
+        static inline unsigned int UnsignedAdd(unsigned int a, unsigned int b) { return a + b; }
+        static inline unsigned int UnsignedSubMul(unsigned int a, unsigned int b) { return (a - b) * 2; }
+        static inline unsigned int UnsignedNeg(unsigned int a) { return -a; }
+        static inline unsigned int Increment(unsigned int a) {
+            unsigned int res = a;
+            res += 1;
+            return res;
+        }
+        static inline long SignedAdd(long a, long b) { return a + b; }
+        static inline long SignedNeg(long a) { return -a; }
+        static inline long CallUnsafe(long x) { return UnsafeFn(x); }
+        static inline long CallSafe(long x) { return SafeFn(x); }
+        static inline long CallSkipped(long x) { return SkippedFn(x); }
+        static inline long ReadExtern(void) { return ExternValue; }
+        static inline long ReadConst(void) { return ConstValue; }
+        static inline MyInt Cast(long x) { return (MyInt)x; }
+    "#;
+
+    const CONFIG: &str = r#"
+        [fn.SafeFn]
+        unsafe = false
+
+        [fn.SkippedFn]
+        skipped = true
+    "#;
+
+    #[test]
+    fn test_float_literal() {
+        assert_eq!(float_literal("1.5"), "1.5");
+        assert_eq!(float_literal("1.5f"), "1.5");
+        assert_eq!(float_literal(".5f"), "0.5");
+        assert_eq!(float_literal("1.f"), "1.0");
+        assert_eq!(float_literal("1.L"), "1.0");
+        assert_eq!(float_literal("1.e5"), "1.0e5");
+        assert_eq!(float_literal("1e5F"), "1e5");
+    }
+
+    #[test]
+    fn test_fn_body() {
+        if clang_sys::load().is_err() {
+            eprintln!("skipping, libclang is not available");
+            return;
+        }
+        let clang = Clang::new().unwrap();
+        let index = Index::new(&clang, false, false);
+        let tu = index
+            .parser("test.h")
+            .unsaved(&[Unsaved::new("test.h", HEADER)])
+            .parse()
+            .unwrap();
+
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let context = Context::new_test(&config);
+
+        let bodies: HashMap<_, _> = tu
+            .get_entity()
+            .get_children()
+            .into_iter()
+            .filter(|entity| entity.is_inline_function())
+            .map(|entity| {
+                let body = FnBody::parse(&entity, &context);
+                (entity.get_name().unwrap(), body)
+            })
+            .collect();
+        let rust = |name: &str| bodies[name].as_ref().map(|body| body.to_string());
+
+        // Unsigned arithmetic wraps
+        assert_eq!(rust("UnsignedAdd").unwrap(), "a.wrapping_add(b)");
+        assert_eq!(
+            rust("UnsignedSubMul").unwrap(),
+            "(a.wrapping_sub(b)).wrapping_mul((2 as c_uint))"
+        );
+        assert_eq!(rust("UnsignedNeg").unwrap(), "a.wrapping_neg()");
+        assert_eq!(
+            rust("Increment").unwrap(),
+            "let mut res: c_uint = a;\nres = res.wrapping_add((1 as c_uint));\nres"
+        );
+        assert_eq!(rust("SignedAdd").unwrap(), "a + b");
+        assert_eq!(rust("SignedNeg").unwrap(), "-a");
+
+        // Calling functions and reading statics
+        assert_eq!(rust("CallUnsafe").unwrap(), "unsafe { UnsafeFn(x) }");
+        assert_eq!(rust("CallSafe").unwrap(), "SafeFn(x)");
+        assert_eq!(rust("CallSkipped"), None);
+        assert_eq!(rust("ReadExtern").unwrap(), "unsafe { ExternValue }");
+        assert_eq!(rust("ReadConst").unwrap(), "ConstValue");
+
+        // The types used in the body are tracked
+        let cast = bodies["Cast"].as_ref().unwrap();
+        assert_eq!(cast.to_string(), "(x as MyInt)");
+        assert_eq!(cast.types.len(), 1);
+    }
+}
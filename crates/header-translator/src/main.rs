@@ -297,7 +297,8 @@ fn get_translation_unit<'i: 'tu, 'tu>(
         .parser(Path::new(env!("CARGO_MANIFEST_DIR")).join("framework-includes.h"))
         .detailed_preprocessing_record(true)
         .incomplete(true)
-        .skip_function_bodies(true)
+        // The bodies of `static inline` functions are translated
+        .skip_function_bodies(false)
         .keep_going(true)
         // .single_file_parse(true)
         .include_attributed_types(true)
@@ -51,6 +51,8 @@ struct MethodModifiers {
     returns_retained: bool,
     returns_not_retained: bool,
     designated_initializer: bool,
+    must_use: bool,
+    requires_super: bool,
 }
 
 impl MethodModifiers {
@@ -78,7 +80,9 @@ impl MethodModifiers {
                 this.consumes_self = true;
             }
             EntityKind::NSReturnsAutoreleased => {
-                error!("found NSReturnsAutoreleased, which requires manual handling");
+                // Autoreleased return values have the same semantics as
+                // not retained return values when receiving them.
+                this.returns_not_retained = true;
             }
             EntityKind::NSReturnsRetained => {
                 this.returns_retained = true;
@@ -90,11 +94,11 @@ impl MethodModifiers {
                 this.designated_initializer = true;
             }
             EntityKind::ObjCRequiresSuper => {
-                // TODO: Can we use this for something?
                 // <https://clang.llvm.org/docs/AttributeReference.html#objc-requires-super>
+                this.requires_super = true;
             }
             EntityKind::WarnUnusedResultAttr => {
-                // TODO: Emit `#[must_use]` on this
+                this.must_use = true;
             }
             EntityKind::ObjCClassRef
             | EntityKind::ObjCProtocolRef
@@ -127,9 +131,12 @@ pub enum MemoryManagement {
     IdNew,
     IdInit,
     IdOther,
-    // TODO:
-    // IdReturnsRetained,
-    // IdReturnsNotRetained,
+    /// Marked `NS_RETURNS_RETAINED`, but not in a family that already
+    /// returns retained objects.
+    IdReturnsRetained,
+    /// Marked `NS_RETURNS_NOT_RETAINED` or `NS_RETURNS_AUTORELEASED`, while
+    /// being in a family that would otherwise return retained objects.
+    IdReturnsNotRetained,
     Normal,
 }
 
@@ -184,6 +191,16 @@ impl MemoryManagement {
             ) {
                 (false, false, true, false, false, Self::IdCopyOrMutCopy) => Self::IdCopyOrMutCopy,
                 (false, false, true, false, false, Self::IdNew) => Self::IdNew,
+                // Methods that break the naming conventions, and have
+                // explicitly overridden their retain semantics.
+                //
+                // Note that `clang` implicitly adds `NS_RETURNS_RETAINED` to
+                // methods in the `new` and `copy` families, so we can't know
+                // if that was also written out explicitly.
+                (false, false, false, true, false, Self::IdCopyOrMutCopy | Self::IdNew) => {
+                    Self::IdReturnsNotRetained
+                }
+                (false, false, true, false, false, Self::IdOther) => Self::IdReturnsRetained,
                 // For the `init` family there's another restriction:
                 // > must be instance methods
                 //
@@ -200,6 +217,8 @@ impl MemoryManagement {
                     }
                 }
                 (false, false, false, false, false, Self::IdOther) => Self::IdOther,
+                // Redundant, methods in no family already return +0
+                (false, false, false, true, false, Self::IdOther) => Self::IdOther,
                 data => {
                     error!(?data, "invalid MemoryManagement id attributes");
                     Self::IdOther
@@ -213,6 +232,8 @@ impl MemoryManagement {
             consumes_self: false,
             returns_retained: false,
             returns_not_retained: false,
+            must_use: _,
+            requires_super: _,
         } = modifiers
         {
             Self::Normal
@@ -237,6 +258,8 @@ pub struct Method {
     safe: bool,
     mutating: bool,
     is_protocol: bool,
+    must_use: bool,
+    pub requires_super: bool,
 }
 
 impl Method {
@@ -360,6 +383,7 @@ impl<'tu> PartialMethod<'tu> {
                 let qualifier = entity
                     .get_objc_qualifiers()
                     .map(MethodArgumentQualifier::parse);
+                let mut is_no_escape = false;
 
                 immediate_children(&entity, |entity, _span| match entity.get_kind() {
                     EntityKind::ObjCClassRef
@@ -371,18 +395,18 @@ impl<'tu> PartialMethod<'tu> {
                     EntityKind::NSConsumed => {
                         error!("found NSConsumed, which requires manual handling");
                     }
-                    EntityKind::UnexposedAttr => {
-                        if let Some(attr) = UnexposedAttr::parse(&entity, context) {
-                            error!(?attr, "unknown attribute");
-                        }
-                    }
+                    EntityKind::UnexposedAttr => match UnexposedAttr::parse(&entity, context) {
+                        Some(UnexposedAttr::NoEscape) => is_no_escape = true,
+                        Some(attr) => error!(?attr, "unknown attribute"),
+                        None => {}
+                    },
                     // For some reason we recurse into array types
                     EntityKind::IntegerLiteral => {}
                     _ => error!("unknown"),
                 });
 
                 let ty = entity.get_type().expect("argument type");
-                let ty = Ty::parse_method_argument(ty, qualifier, context);
+                let ty = Ty::parse_method_argument(ty, qualifier, is_no_escape, context);
 
                 (name, ty)
             })
@@ -442,6 +466,8 @@ impl<'tu> PartialMethod<'tu> {
                 safe: !data.unsafe_,
                 mutating: data.mutating,
                 is_protocol,
+                must_use: modifiers.must_use,
+                requires_super: modifiers.requires_super,
             },
         ))
     }
@@ -514,6 +540,8 @@ impl PartialProperty<'_> {
                 safe: !getter_data.unsafe_,
                 mutating: getter_data.mutating,
                 is_protocol,
+                must_use: modifiers.must_use,
+                requires_super: modifiers.requires_super,
             })
         } else {
             None
@@ -541,6 +569,8 @@ impl PartialProperty<'_> {
                     safe: !setter_data.unsafe_,
                     mutating: setter_data.mutating,
                     is_protocol,
+                    must_use: false,
+                    requires_super: modifiers.requires_super,
                 })
             } else {
                 None
@@ -581,11 +611,17 @@ impl fmt::Display for Method {
             writeln!(f, "        #[optional]")?;
         }
 
+        if self.must_use {
+            writeln!(f, "        #[must_use]")?;
+        }
+
         let id_mm_name = match &self.memory_management {
             MemoryManagement::IdCopyOrMutCopy => Some("CopyOrMutCopy"),
             MemoryManagement::IdNew => Some("New"),
             MemoryManagement::IdInit => Some("Init"),
             MemoryManagement::IdOther => Some("Other"),
+            MemoryManagement::IdReturnsRetained => Some("ReturnsRetained"),
+            MemoryManagement::IdReturnsNotRetained => Some("ReturnsNotRetained"),
             MemoryManagement::Normal => None,
         };
         if let Some(id_mm_name) = id_mm_name {
@@ -1,9 +1,10 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
 use std::str::FromStr;
 
 use crate::config::Config;
 use crate::library::Library;
-use crate::stmt::Stmt;
+use crate::stmt::{Stmt, SuperclassArgument};
 
 #[derive(Debug, PartialEq)]
 pub struct Output {
@@ -114,17 +115,41 @@ impl Output {
                     #[allow(clippy::single_match)] // There will be others
                     match stmt {
                         Stmt::ClassDecl {
-                            id, superclasses, ..
+                            id,
+                            generics,
+                            superclasses,
+                            ..
                         } => {
                             if let Some(feature) = id.feature() {
                                 // Only require the first superclass as feature,
                                 // since the rest will be enabled transitively.
-                                if let Some((superclass, _)) = superclasses.first() {
-                                    let superclass_features: Vec<_> = superclass
-                                        .feature()
-                                        .map(|f| f.to_string())
-                                        .into_iter()
-                                        .collect();
+                                if let Some((superclass, arguments)) = superclasses.first() {
+                                    // The class declaration also refers to the
+                                    // bounds of its generic parameters, and to
+                                    // the classes given to its superclass.
+                                    let arguments =
+                                        arguments.iter().filter_map(|argument| match argument {
+                                            SuperclassArgument::Class(class) => Some(class),
+                                            SuperclassArgument::Generic(_) => None,
+                                        });
+                                    let bounds = generics
+                                        .iter()
+                                        .filter_map(|generic| generic.bound.as_ref());
+
+                                    let mut superclass_features: Vec<_> = vec![];
+                                    for item in
+                                        iter::once(superclass).chain(arguments).chain(bounds)
+                                    {
+                                        if item == id {
+                                            continue;
+                                        }
+                                        if let Some(required) = item.feature() {
+                                            let required = required.to_string();
+                                            if !superclass_features.contains(&required) {
+                                                superclass_features.push(required);
+                                            }
+                                        }
+                                    }
                                     if let Some(existing) =
                                         features.insert(feature.to_string(), superclass_features)
                                     {
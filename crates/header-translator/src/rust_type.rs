@@ -337,7 +337,14 @@ impl IdType {
                         assert_eq!(id.name, name);
 
                         if !generics.is_empty() && !protocols.is_empty() {
-                            panic!("got object with both protocols and generics: {name:?}, {protocols:?}, {generics:?}");
+                            // We can't express both in the type-system, and
+                            // the generics are the most useful to keep.
+                            warn!(
+                                ?name,
+                                ?protocols,
+                                ?generics,
+                                "ignoring protocols on object with generics"
+                            );
                         }
 
                         if generics.is_empty() && protocols.is_empty() {
@@ -352,10 +359,10 @@ impl IdType {
 
                         Self::Class {
                             id,
-                            params: if protocols.is_empty() {
-                                TypeParams::Generics(generics)
-                            } else {
+                            params: if generics.is_empty() {
                                 TypeParams::Protocols(protocols)
+                            } else {
+                                TypeParams::Generics(generics)
                             },
                             ownership: None,
                         }
@@ -549,6 +556,8 @@ enum Inner {
     Block {
         arguments: Vec<Inner>,
         result_type: Box<Inner>,
+        /// The block is marked `NS_NOESCAPE`.
+        no_escape: bool,
     },
 
     TypeDef {
@@ -708,6 +717,7 @@ impl Inner {
                         pointee: Box::new(Self::Block {
                             arguments,
                             result_type,
+                            no_escape: false,
                         }),
                     },
                     pointee => panic!("unexpected pointee in block: {pointee:?}"),
@@ -1031,6 +1041,7 @@ impl Inner {
             | Self::Block {
                 arguments,
                 result_type,
+                ..
             } => {
                 // TODO if block
                 // f("block2");
@@ -1179,8 +1190,13 @@ impl fmt::Display for Inner {
             Self::Block {
                 arguments,
                 result_type,
+                no_escape,
             } => {
-                write!(f, "Block<(")?;
+                if *no_escape {
+                    write!(f, "NoEscapeBlock<(")?;
+                } else {
+                    write!(f, "Block<(")?;
+                }
                 for arg in arguments {
                     write!(f, "{arg}, ")?;
                 }
@@ -1229,9 +1245,18 @@ impl Ty {
     pub fn parse_method_argument(
         ty: Type<'_>,
         _qualifier: Option<MethodArgumentQualifier>,
+        is_no_escape: bool,
         context: &Context<'_>,
     ) -> Self {
-        let ty = Inner::parse(ty, Lifetime::Unspecified, context);
+        let mut ty = Inner::parse(ty, Lifetime::Unspecified, context);
+
+        if is_no_escape {
+            if let Inner::Pointer { pointee, .. } = &mut ty {
+                if let Inner::Block { no_escape, .. } = &mut **pointee {
+                    *no_escape = true;
+                }
+            }
+        }
 
         match &ty {
             Inner::Pointer { pointee, .. } => pointee.visit_lifetime(|lifetime| {
@@ -1274,8 +1299,12 @@ impl Ty {
         }
     }
 
-    pub fn parse_function_argument(ty: Type<'_>, context: &Context<'_>) -> Self {
-        let mut this = Self::parse_method_argument(ty, None, context);
+    pub fn parse_function_argument(
+        ty: Type<'_>,
+        is_no_escape: bool,
+        context: &Context<'_>,
+    ) -> Self {
+        let mut this = Self::parse_method_argument(ty, None, is_no_escape, context);
         this.kind = TyKind::FnArgument;
         this
     }
@@ -1790,8 +1819,10 @@ fn parse_unexposed_tokens(s: &str) -> String {
                 None
             }
         }) {
-            if let Some(attr) = attr {
-                error!(?attr, "unknown attribute");
+            match attr {
+                // Handled on the parameter declaration
+                Some(UnexposedAttr::NoEscape) | None => {}
+                Some(attr) => error!(?attr, "unknown attribute"),
             }
             iter.next();
         }
@@ -1815,6 +1846,7 @@ mod tests {
             "NSString * const __strong",
         );
         check("NS_REFINED_FOR_SWIFT NSNumber *", "NSNumber *");
+        check("NS_NOESCAPE NSComparator", "NSComparator");
         check(
             "API_AVAILABLE(macos(10.9)) const NSProgressUserInfoKey __strong",
             "const NSProgressUserInfoKey __strong",
@@ -5,13 +5,13 @@ use std::fmt;
 use std::iter;
 use std::mem;
 
-use clang::{Entity, EntityKind, EntityVisitResult};
+use clang::{Entity, EntityKind, EntityVisitResult, TypeKind};
 
 use crate::availability::Availability;
 use crate::config::{ClassData, MethodData};
 use crate::context::Context;
-use crate::expr::Expr;
-use crate::id::ItemIdentifier;
+use crate::expr::{Expr, FnBody};
+use crate::id::{ItemIdentifier, ToOptionString};
 use crate::immediate_children;
 use crate::method::{handle_reserved, Method};
 use crate::rust_type::{Ownership, Ty};
@@ -67,9 +67,9 @@ fn parse_protocols(
 fn parse_superclass<'ty>(
     entity: &Entity<'ty>,
     context: &Context<'_>,
-) -> Option<(Entity<'ty>, ItemIdentifier, Vec<String>)> {
+) -> Option<(Entity<'ty>, ItemIdentifier, Vec<SuperclassArgument>)> {
     let mut superclass = None;
-    let mut generics = Vec::new();
+    let mut arguments = Vec::new();
 
     immediate_children(entity, |entity, _span| match entity.get_kind() {
         EntityKind::ObjCSuperClassRef => {
@@ -81,12 +81,37 @@ fn parse_superclass<'ty>(
         }
         EntityKind::TypeRef => {
             let name = entity.get_name().expect("typeref name");
-            generics.push(name);
+            arguments.push(SuperclassArgument::Generic(name));
+        }
+        EntityKind::ObjCClassRef if superclass.is_some() => {
+            let reference = entity
+                .get_reference()
+                .expect("ObjCClassRef to reference entity");
+            arguments.push(SuperclassArgument::Class(ItemIdentifier::new(
+                &reference, context,
+            )));
         }
         _ => {}
     });
 
-    superclass.map(|entity| (entity, ItemIdentifier::new(&entity, context), generics))
+    superclass.map(|entity| (entity, ItemIdentifier::new(&entity, context), arguments))
+}
+
+/// Find the class that a generic parameter is bounded by, if any.
+///
+/// Unbounded parameters, and parameters bounded by a protocol (like
+/// `ResultType: id<NSFetchRequestResult>`), return `None`.
+fn parse_generic_bound(entity: &Entity<'_>, context: &Context<'_>) -> Option<ItemIdentifier> {
+    let ty = entity.get_type()?.get_canonical_type();
+    if ty.get_kind() != TypeKind::ObjCObjectPointer {
+        return None;
+    }
+    let pointee = ty.get_pointee_type()?;
+    if pointee.get_kind() != TypeKind::ObjCInterface {
+        return None;
+    }
+    let declaration = pointee.get_declaration()?;
+    Some(ItemIdentifier::new(&declaration, context))
 }
 
 /// Takes one of:
@@ -96,7 +121,8 @@ fn parse_superclass<'ty>(
 fn parse_objc_decl(
     entity: &Entity<'_>,
     superclass: bool,
-    mut generics: Option<&mut Vec<String>>,
+    is_protocol: bool,
+    mut generics: Option<&mut Vec<ClassGeneric>>,
     get_data: impl Fn(&str) -> MethodData,
     context: &Context<'_>,
 ) -> (BTreeSet<ItemIdentifier>, Vec<Method>, Vec<String>) {
@@ -109,7 +135,7 @@ fn parse_objc_decl(
     let mut properties = HashSet::new();
 
     immediate_children(entity, |entity, span| match entity.get_kind() {
-        EntityKind::ObjCExplicitProtocolImpl if generics.is_none() && !superclass => {
+        EntityKind::ObjCExplicitProtocolImpl if is_protocol => {
             // TODO NS_PROTOCOL_REQUIRES_EXPLICIT_IMPLEMENTATION
         }
         EntityKind::ObjCIvarDecl | EntityKind::StructDecl | EntityKind::UnionDecl if superclass => {
@@ -124,15 +150,14 @@ fn parse_objc_decl(
         EntityKind::ObjCRootClass => {
             debug!("parsing root class");
         }
-        EntityKind::ObjCClassRef if generics.is_some() => {
+        EntityKind::ObjCClassRef if !is_protocol => {
             // debug!("ObjCClassRef: {:?}", entity.get_display_name());
         }
         EntityKind::TemplateTypeParameter => {
             if let Some(generics) = &mut generics {
-                // TODO: Generics with bounds (like NSMeasurement<UnitType: NSUnit *>)
-                // let ty = entity.get_type().expect("template type");
                 let name = entity.get_name().expect("template name");
-                generics.push(name);
+                let bound = parse_generic_bound(&entity, context);
+                generics.push(ClassGeneric { name, bound });
             } else {
                 error!("unsupported generics");
             }
@@ -141,10 +166,15 @@ fn parse_objc_decl(
             let entity = entity
                 .get_reference()
                 .expect("ObjCProtocolRef to reference entity");
-            protocols.insert(
+            let is_new = protocols.insert(
                 ItemIdentifier::new(&entity, context)
                     .map_name(|name| context.replace_protocol_name(name)),
             );
+            // Classes (and categories) conforming to a protocol also conform
+            // to the protocols that it inherits.
+            if is_new && !is_protocol {
+                parse_protocols(&entity, &mut protocols, context);
+            }
         }
         EntityKind::ObjCInstanceMethodDecl | EntityKind::ObjCClassMethodDecl => {
             drop(span);
@@ -153,7 +183,7 @@ fn parse_objc_decl(
             if !properties.remove(&(partial.is_class, partial.fn_name.clone())) {
                 let data = get_data(&partial.fn_name);
                 if let Some((designated_initializer, method)) =
-                    partial.parse(data, generics.is_none(), context)
+                    partial.parse(data, is_protocol, context)
                 {
                     if designated_initializer {
                         designated_initializers.push(method.fn_name.clone());
@@ -172,8 +202,7 @@ fn parse_objc_decl(
                 .as_ref()
                 .map(|setter_name| get_data(setter_name));
 
-            let (getter, setter) =
-                partial.parse(getter_data, setter_data, generics.is_none(), context);
+            let (getter, setter) = partial.parse(getter_data, setter_data, is_protocol, context);
             if let Some(getter) = getter {
                 if !properties.insert((getter.is_class, getter.fn_name.clone())) {
                     error!(?setter, "already exisiting property");
@@ -212,6 +241,26 @@ fn parse_objc_decl(
     (protocols, methods, designated_initializers)
 }
 
+/// A generic parameter declared on a class, like `ObjectType` in
+/// `@interface NSArray<ObjectType>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassGeneric {
+    pub name: String,
+    /// The class that the parameter is bounded by, like `NSUnit` in
+    /// `NSMeasurement<UnitType: NSUnit *>`.
+    pub bound: Option<ItemIdentifier>,
+}
+
+/// A type argument given to a generic superclass.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuperclassArgument {
+    /// One of the subclass' own generic parameters.
+    Generic(String),
+    /// A concrete class, like `NSLayoutXAxisAnchor` in
+    /// `NSLayoutXAxisAnchor : NSLayoutAnchor<NSLayoutXAxisAnchor *>`.
+    Class(ItemIdentifier),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
     /// @interface name: superclass <protocols*>
@@ -219,10 +268,12 @@ pub enum Stmt {
     /// extern_class!
     ClassDecl {
         id: ItemIdentifier,
-        generics: Vec<String>,
+        generics: Vec<ClassGeneric>,
         availability: Availability,
-        superclasses: Vec<(ItemIdentifier, Vec<String>)>,
+        superclasses: Vec<(ItemIdentifier, Vec<SuperclassArgument>)>,
         designated_initializers: Vec<String>,
+        /// Selectors of methods marked `NS_REQUIRES_SUPER`.
+        requires_super: Vec<String>,
         derives: Derives,
         ownership: Ownership,
     },
@@ -231,11 +282,11 @@ pub enum Stmt {
     /// extern_methods!
     Methods {
         cls: ItemIdentifier,
-        generics: Vec<String>,
+        generics: Vec<ClassGeneric>,
         /// For the categories that have a name (though some don't, see NSClipView)
         category: ItemIdentifier<Option<String>>,
         availability: Availability,
-        superclasses: Vec<(ItemIdentifier, Vec<String>)>,
+        superclasses: Vec<(ItemIdentifier, Vec<SuperclassArgument>)>,
         methods: Vec<Method>,
         description: Option<String>,
     },
@@ -253,7 +304,7 @@ pub enum Stmt {
     ProtocolImpl {
         cls: ItemIdentifier,
         protocol: ItemIdentifier,
-        generics: Vec<String>,
+        generics: Vec<ClassGeneric>,
         availability: Availability,
     },
     /// struct name {
@@ -317,7 +368,7 @@ pub enum Stmt {
         arguments: Vec<(String, Ty)>,
         result_type: Ty,
         // Some -> inline function.
-        body: Option<()>,
+        body: Option<FnBody>,
         safe: bool,
     },
     /// typedef Type TypedefName;
@@ -363,19 +414,22 @@ fn parse_struct(entity: &Entity<'_>, context: &Context<'_>) -> (bool, Vec<(Strin
     (boxable, fields)
 }
 
-fn parse_fn_param_children(entity: &Entity<'_>, context: &Context<'_>) {
+/// Returns whether the parameter is marked `NS_NOESCAPE`.
+fn parse_fn_param_children(entity: &Entity<'_>, context: &Context<'_>) -> bool {
+    let mut is_no_escape = false;
     immediate_children(entity, |entity, _span| match entity.get_kind() {
-        EntityKind::UnexposedAttr => {
-            if let Some(attr) = UnexposedAttr::parse(&entity, context) {
-                error!(?attr, "unknown attribute");
-            }
-        }
+        EntityKind::UnexposedAttr => match UnexposedAttr::parse(&entity, context) {
+            Some(UnexposedAttr::NoEscape) => is_no_escape = true,
+            Some(attr) => error!(?attr, "unknown attribute"),
+            None => {}
+        },
         EntityKind::ObjCClassRef | EntityKind::TypeRef | EntityKind::ObjCProtocolRef => {}
         EntityKind::NSConsumed => {
             error!("found NSConsumed, which requires manual handling");
         }
         kind => error!(?kind, "unknown"),
     });
+    is_no_escape
 }
 
 impl Stmt {
@@ -405,6 +459,7 @@ impl Stmt {
                 let (_, methods, designated_initializers) = parse_objc_decl(
                     entity,
                     true,
+                    false,
                     Some(&mut generics),
                     |name| ClassData::get_method_data(data, name),
                     context,
@@ -421,13 +476,19 @@ impl Stmt {
                 let mut superclass_entity = *entity;
                 let mut superclasses = vec![];
 
-                while let Some((next_entity, superclass, generics)) =
+                while let Some((next_entity, superclass, arguments)) =
                     parse_superclass(&superclass_entity, context)
                 {
                     superclass_entity = next_entity;
-                    superclasses.push((superclass, generics));
+                    superclasses.push((superclass, arguments));
                 }
 
+                let requires_super = methods
+                    .iter()
+                    .filter(|method| method.requires_super)
+                    .map(|method| method.selector.clone())
+                    .collect();
+
                 let methods = Self::Methods {
                     cls: id.clone(),
                     generics: generics.clone(),
@@ -445,6 +506,7 @@ impl Stmt {
                         availability: availability.clone(),
                         superclasses,
                         designated_initializers,
+                        requires_super,
                         derives: data.map(|data| data.derives.clone()).unwrap_or_default(),
                         ownership: data.map(|data| data.ownership.clone()).unwrap_or_default(),
                     })
@@ -502,6 +564,7 @@ impl Stmt {
                 let (protocols, methods, designated_initializers) = parse_objc_decl(
                     entity,
                     false,
+                    false,
                     Some(&mut generics),
                     |name| ClassData::get_method_data(data, name),
                     context,
@@ -517,11 +580,11 @@ impl Stmt {
                 let mut superclass_entity = *entity;
                 let mut superclasses = vec![];
 
-                while let Some((next_entity, superclass, generics)) =
+                while let Some((next_entity, superclass, arguments)) =
                     parse_superclass(&superclass_entity, context)
                 {
                     superclass_entity = next_entity;
-                    superclasses.push((superclass, generics));
+                    superclasses.push((superclass, arguments));
                 }
 
                 iter::once(Self::Methods {
@@ -555,6 +618,7 @@ impl Stmt {
                 let (protocols, methods, designated_initializers) = parse_objc_decl(
                     entity,
                     false,
+                    true,
                     None,
                     |name| {
                         data.and_then(|data| data.methods.get(name))
@@ -818,7 +882,7 @@ impl Stmt {
                     EntityKind::TypeRef => {}
                     _ if entity.is_expression() => {
                         if value.is_none() {
-                            value = Some(Expr::parse_var(&entity));
+                            value = Some(Expr::parse_var(&entity, context));
                         } else {
                             panic!("got variable value twice")
                         }
@@ -875,11 +939,11 @@ impl Stmt {
                     | EntityKind::TypeRef
                     | EntityKind::ObjCProtocolRef => {}
                     EntityKind::ParmDecl => {
-                        parse_fn_param_children(&entity, context);
+                        let is_no_escape = parse_fn_param_children(&entity, context);
                         // Could also be retrieved via. `get_arguments`
                         let name = entity.get_name().unwrap_or_else(|| "_".into());
                         let ty = entity.get_type().expect("function argument type");
-                        let ty = Ty::parse_function_argument(ty, context);
+                        let ty = Ty::parse_function_argument(ty, is_no_escape, context);
                         arguments.push((name, ty))
                     }
                     EntityKind::VisibilityAttr => {
@@ -889,7 +953,12 @@ impl Stmt {
                 });
 
                 let body = if entity.is_inline_function() {
-                    Some(())
+                    if let Some(body) = FnBody::parse(entity, context) {
+                        Some(body)
+                    } else {
+                        warn!("could not translate inline function body");
+                        return vec![];
+                    }
                 } else {
                     None
                 };
@@ -960,6 +1029,7 @@ impl Stmt {
             Stmt::FnDecl {
                 arguments,
                 result_type,
+                body,
                 ..
             } => {
                 for (_, arg) in arguments {
@@ -967,6 +1037,10 @@ impl Stmt {
                 }
 
                 result_type.visit_required_types(&mut f);
+
+                if let Some(body) = body {
+                    body.visit_required_types(&mut f);
+                }
             }
             _ => {}
         }
@@ -981,9 +1055,7 @@ impl Stmt {
             Stmt::StructDecl { id, .. } => Some(&*id.name),
             Stmt::EnumDecl { id, .. } => id.name.as_deref(),
             Stmt::VarDecl { id, .. } => Some(&*id.name),
-            Stmt::FnDecl { id, body, .. } if body.is_none() => Some(&*id.name),
-            // TODO
-            Stmt::FnDecl { .. } => None,
+            Stmt::FnDecl { id, .. } => Some(&*id.name),
             Stmt::AliasDecl { id, .. } => Some(&*id.name),
         }
         .into_iter()
@@ -997,45 +1069,122 @@ impl Stmt {
     }
 }
 
-impl fmt::Display for Stmt {
+struct GenericTyHelper<'a>(&'a [ClassGeneric]);
+
+impl fmt::Display for GenericTyHelper<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let _span = debug_span!("stmt", discriminant = ?mem::discriminant(self)).entered();
+        if !self.0.is_empty() {
+            write!(f, "<")?;
+            for ClassGeneric { name, .. } in self.0 {
+                write!(f, "{name}, ")?;
+            }
+            for ClassGeneric { name, .. } in self.0 {
+                write!(f, "{name}Ownership, ")?;
+            }
+            write!(f, ">")?;
+        }
+        Ok(())
+    }
+}
 
-        struct GenericTyHelper<'a>(&'a [String]);
+/// The bounds of a generic parameter.
+///
+/// Parameters bounded by a class must be that class or one of its
+/// subclasses, which we approximate with `AsRef`.
+struct GenericBoundHelper<'a, N>(&'a ClassGeneric, &'a ItemIdentifier<N>);
 
-        impl fmt::Display for GenericTyHelper<'_> {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                if !self.0.is_empty() {
-                    write!(f, "<")?;
-                    for generic in self.0 {
-                        write!(f, "{generic}, ")?;
-                    }
-                    for generic in self.0 {
-                        write!(f, "{generic}Ownership, ")?;
-                    }
-                    write!(f, ">")?;
+impl<N: ToOptionString> fmt::Display for GenericBoundHelper<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Message")?;
+        if let Some(bound) = &self.0.bound {
+            write!(f, " + AsRef<{}>", bound.path_in_relation_to(self.1))?;
+        }
+        Ok(())
+    }
+}
+
+struct GenericParamsHelper<'a, N>(&'a [ClassGeneric], &'a ItemIdentifier<N>);
+
+impl<N: ToOptionString> fmt::Display for GenericParamsHelper<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.0.is_empty() {
+            write!(f, "<")?;
+            for generic in self.0 {
+                write!(
+                    f,
+                    "{}: {}, ",
+                    generic.name,
+                    GenericBoundHelper(generic, self.1)
+                )?;
+            }
+            for ClassGeneric { name, .. } in self.0 {
+                write!(f, "{name}Ownership: Ownership, ")?;
+            }
+            write!(f, ">")?;
+        }
+        Ok(())
+    }
+}
+
+/// Like [`GenericParamsHelper`], but with defaults, for use in the struct
+/// declaration.
+struct GenericStructParamsHelper<'a, N>(&'a [ClassGeneric], &'a ItemIdentifier<N>);
+
+impl<N: ToOptionString> fmt::Display for GenericStructParamsHelper<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.0.is_empty() {
+            write!(f, "<")?;
+            for generic in self.0 {
+                write!(
+                    f,
+                    "{}: {} = ",
+                    generic.name,
+                    GenericBoundHelper(generic, self.1)
+                )?;
+                if let Some(bound) = &generic.bound {
+                    write!(f, "{}, ", bound.path_in_relation_to(self.1))?;
+                } else {
+                    write!(f, "Object, ")?;
                 }
-                Ok(())
             }
+            for ClassGeneric { name, .. } in self.0 {
+                write!(f, "{name}Ownership: Ownership = Shared, ")?;
+            }
+            write!(f, ">")?;
         }
+        Ok(())
+    }
+}
 
-        struct GenericParamsHelper<'a>(&'a [String]);
+struct SuperclassArgumentsHelper<'a>(&'a [SuperclassArgument], &'a ItemIdentifier);
 
-        impl fmt::Display for GenericParamsHelper<'_> {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                if !self.0.is_empty() {
-                    write!(f, "<")?;
-                    for generic in self.0 {
-                        write!(f, "{generic}: Message, ")?;
-                    }
-                    for generic in self.0 {
-                        write!(f, "{generic}Ownership: Ownership, ")?;
+impl fmt::Display for SuperclassArgumentsHelper<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.0.is_empty() {
+            write!(f, "<")?;
+            for argument in self.0 {
+                match argument {
+                    SuperclassArgument::Generic(name) => write!(f, "{name}, ")?,
+                    SuperclassArgument::Class(id) => {
+                        write!(f, "{}, ", id.path_in_relation_to(self.1))?
                     }
-                    write!(f, ">")?;
                 }
-                Ok(())
             }
+            for argument in self.0 {
+                match argument {
+                    SuperclassArgument::Generic(name) => write!(f, "{name}Ownership, ")?,
+                    SuperclassArgument::Class(_) => write!(f, "Shared, ")?,
+                }
+            }
+            write!(f, ">")?;
         }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let _span = debug_span!("stmt", discriminant = ?mem::discriminant(self)).entered();
 
         match self {
             Self::ClassDecl {
@@ -1044,6 +1193,7 @@ impl fmt::Display for Stmt {
                 availability,
                 superclasses,
                 designated_initializers: _,
+                requires_super,
                 derives,
                 ownership: _,
             } => {
@@ -1059,27 +1209,19 @@ impl fmt::Display for Stmt {
                     writeln!(f, "    #[cfg(feature = \"{feature}\")]")?;
                 }
                 write!(f, "{availability}")?;
-                write!(f, "    pub struct {}", id.name)?;
-                if !generics.is_empty() {
-                    write!(f, "<")?;
-                    for generic in generics {
-                        write!(f, "{generic}: Message = Object, ")?;
-                    }
-                    for generic in generics {
-                        write!(f, "{generic}Ownership: Ownership = Shared, ")?;
-                    }
-                    write!(f, ">")?;
-                };
+                write!(
+                    f,
+                    "    pub struct {}{}",
+                    id.name,
+                    GenericStructParamsHelper(generics, id)
+                )?;
                 if generics.is_empty() {
                     writeln!(f, ";")?;
                 } else {
                     writeln!(f, " {{")?;
-                    for (i, generic) in generics.iter().enumerate() {
+                    for (i, ClassGeneric { name, .. }) in generics.iter().enumerate() {
                         // Invariant over the generic (for now)
-                        writeln!(
-                            f,
-                            "_inner{i}: PhantomData<*mut ({generic}, {generic}Ownership)>,"
-                        )?;
+                        writeln!(f, "_inner{i}: PhantomData<*mut ({name}, {name}Ownership)>,")?;
                     }
                     writeln!(f, "notunwindsafe: PhantomData<&'static mut ()>,")?;
                     writeln!(f, "}}")?;
@@ -1093,32 +1235,32 @@ impl fmt::Display for Stmt {
                 writeln!(
                     f,
                     "    unsafe impl{} ClassType for {}{} {{",
-                    GenericParamsHelper(generics),
+                    GenericParamsHelper(generics, id),
                     id.name,
                     GenericTyHelper(generics),
                 )?;
                 let (superclass, rest) = superclasses.split_at(1);
-                let (superclass, generics) =
+                let (superclass, superclass_arguments) =
                     superclass.get(0).expect("must have a least one superclass");
                 if !rest.is_empty() {
                     write!(f, "    #[inherits(")?;
                     let mut iter = rest.iter();
                     // Using generics in here is not technically correct, but
                     // should work for our use-cases.
-                    if let Some((superclass, generics)) = iter.next() {
+                    if let Some((superclass, arguments)) = iter.next() {
                         write!(
                             f,
                             "{}{}",
                             superclass.path_in_relation_to(id),
-                            GenericTyHelper(generics)
+                            SuperclassArgumentsHelper(arguments, id)
                         )?;
                     }
-                    for (superclass, generics) in iter {
+                    for (superclass, arguments) in iter {
                         write!(
                             f,
                             ", {}{}",
                             superclass.path_in_relation_to(id),
-                            GenericTyHelper(generics)
+                            SuperclassArgumentsHelper(arguments, id)
                         )?;
                     }
                     writeln!(f, ")]")?;
@@ -1127,10 +1269,35 @@ impl fmt::Display for Stmt {
                     f,
                     "        type Super = {}{};",
                     superclass.path_in_relation_to(id),
-                    GenericTyHelper(generics)
+                    SuperclassArgumentsHelper(superclass_arguments, id)
                 )?;
                 writeln!(f, "    }}")?;
                 writeln!(f, ");")?;
+
+                if !requires_super.is_empty() {
+                    writeln!(f)?;
+                    if let Some(feature) = id.feature() {
+                        writeln!(f, "#[cfg(feature = \"{feature}\")]")?;
+                    }
+                    writeln!(
+                        f,
+                        "impl{} RequiresSuper for {}{} {{",
+                        GenericParamsHelper(generics, id),
+                        id.name,
+                        GenericTyHelper(generics),
+                    )?;
+                    writeln!(f, "    fn requires_super(selector: &str) -> bool {{")?;
+                    write!(f, "        matches!(selector, ")?;
+                    for (i, selector) in requires_super.iter().enumerate() {
+                        if i != 0 {
+                            write!(f, " | ")?;
+                        }
+                        write!(f, "{selector:?}")?;
+                    }
+                    writeln!(f, ")")?;
+                    writeln!(f, "    }}")?;
+                    writeln!(f, "}}")?;
+                }
             }
             Self::Methods {
                 cls,
@@ -1158,7 +1325,7 @@ impl fmt::Display for Stmt {
                 writeln!(
                     f,
                     "    unsafe impl{} {}{} {{",
-                    GenericParamsHelper(generics),
+                    GenericParamsHelper(generics, category),
                     cls.path_in_relation_to(category),
                     GenericTyHelper(generics),
                 )?;
@@ -1225,7 +1392,7 @@ impl fmt::Display for Stmt {
                 writeln!(
                     f,
                     "unsafe impl{} {} for {}{} {{}}",
-                    GenericParamsHelper(generics),
+                    GenericParamsHelper(generics, cls),
                     protocol.path_in_relation_to(cls),
                     cls.path(),
                     GenericTyHelper(generics),
@@ -1413,9 +1580,11 @@ impl fmt::Display for Stmt {
                 }
                 write!(f, "){result_type}")?;
 
-                if body.is_some() {
+                if let Some(body) = body {
                     writeln!(f, "{{")?;
-                    writeln!(f, "        todo!()")?;
+                    for line in body.to_string().lines() {
+                        writeln!(f, "        {line}")?;
+                    }
                     writeln!(f, "    }}")?;
                 } else {
                     writeln!(f, ";")?;
@@ -1448,3 +1617,63 @@ impl fmt::Display for Stmt {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(name: &str, file_name: &str) -> ItemIdentifier {
+        ItemIdentifier {
+            name: name.to_string(),
+            library: "Foundation".to_string(),
+            file_name: Some(file_name.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_generics() {
+        let cls = id("NSMeasurement", "NSMeasurement");
+        let generics = [
+            ClassGeneric {
+                name: "UnitType".to_string(),
+                bound: Some(id("NSUnit", "NSUnit")),
+            },
+            ClassGeneric {
+                name: "ObjectType".to_string(),
+                bound: None,
+            },
+        ];
+
+        assert_eq!(
+            GenericStructParamsHelper(&generics, &cls).to_string(),
+            "<UnitType: Message + AsRef<NSUnit> = NSUnit, ObjectType: Message = Object, \
+            UnitTypeOwnership: Ownership = Shared, ObjectTypeOwnership: Ownership = Shared, >",
+        );
+        assert_eq!(
+            GenericParamsHelper(&generics, &cls).to_string(),
+            "<UnitType: Message + AsRef<NSUnit>, ObjectType: Message, \
+            UnitTypeOwnership: Ownership, ObjectTypeOwnership: Ownership, >",
+        );
+        assert_eq!(
+            GenericTyHelper(&generics).to_string(),
+            "<UnitType, ObjectType, UnitTypeOwnership, ObjectTypeOwnership, >",
+        );
+        assert_eq!(GenericParamsHelper(&[], &cls).to_string(), "");
+        assert_eq!(GenericTyHelper(&[]).to_string(), "");
+    }
+
+    #[test]
+    fn test_superclass_arguments() {
+        let cls = id("NSLayoutXAxisAnchor", "NSLayoutAnchor");
+        let arguments = [
+            SuperclassArgument::Class(cls.clone()),
+            SuperclassArgument::Generic("ObjectType".to_string()),
+        ];
+
+        assert_eq!(
+            SuperclassArgumentsHelper(&arguments, &cls).to_string(),
+            "<NSLayoutXAxisAnchor, ObjectType, Shared, ObjectTypeOwnership, >",
+        );
+        assert_eq!(SuperclassArgumentsHelper(&[], &cls).to_string(), "");
+    }
+}
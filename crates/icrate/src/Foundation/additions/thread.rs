@@ -151,7 +151,7 @@ impl MainThreadMarker {
         if let Some(mtm) = MainThreadMarker::new() {
             f(mtm)
         } else {
-            dispatch::Queue::main().exec_sync(|| {
+            dispatch2::Queue::main().exec_sync(|| {
                 // SAFETY: The outer closure is submitted to run on the main
                 // thread, so now, when the closure actually runs, it's
                 // guaranteed to be on the main thread.
@@ -159,6 +159,35 @@ impl MainThreadMarker {
             })
         }
     }
+
+    /// Submit the given closure to the main queue, without waiting for it
+    /// to run.
+    ///
+    /// The closure is passed a [`MainThreadMarker`], like in
+    /// [`run_on_main`][Self::run_on_main]. Unlike that, the closure is
+    /// always submitted to the queue, even if the current thread is the main
+    /// thread.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use icrate::Foundation::MainThreadMarker;
+    /// MainThreadMarker::run_on_main_async(|mtm| {
+    ///     // Do something on the main thread with the given marker
+    /// });
+    /// ```
+    #[cfg(feature = "dispatch")]
+    pub fn run_on_main_async<F>(f: F)
+    where
+        F: Send + FnOnce(MainThreadMarker) + 'static,
+    {
+        dispatch2::Queue::main().exec_async(move || {
+            // SAFETY: Blocks on the main queue are executed on the main
+            // thread.
+            f(unsafe { MainThreadMarker::new_unchecked() })
+        })
+    }
 }
 
 impl fmt::Debug for MainThreadMarker {
@@ -10,6 +10,8 @@ pub(crate) use std::os::raw::{
     c_ulong, c_ulonglong, c_ushort,
 };
 
+#[cfg(feature = "objective-c")]
+pub(crate) use objc2::declare::RequiresSuper;
 #[cfg(feature = "objective-c")]
 pub(crate) use objc2::ffi::{NSInteger, NSIntegerMax, NSUInteger, NSUIntegerMax, IMP};
 #[cfg(feature = "objective-c")]
@@ -25,7 +27,7 @@ pub(crate) use objc2::{
 };
 
 #[cfg(feature = "block")]
-pub(crate) use block2::Block;
+pub(crate) use block2::{Block, NoEscapeBlock};
 
 // TODO
 #[cfg(feature = "objective-c")]
@@ -338,6 +338,16 @@ macro_rules! inline_fn {
         $(#[$m:meta])*
         $v:vis unsafe fn $name:ident($($args:tt)*) $(-> $res:ty)? $body:block
     ) => {
-        // TODO
+        #[inline]
+        $(#[$m])*
+        $v unsafe fn $name($($args)*) $(-> $res)? $body
+    };
+    (
+        $(#[$m:meta])*
+        $v:vis fn $name:ident($($args:tt)*) $(-> $res:ty)? $body:block
+    ) => {
+        #[inline]
+        $(#[$m])*
+        $v fn $name($($args)*) $(-> $res)? $body
     };
 }
@@ -0,0 +1,327 @@
+//! Generating arbitrary encodings for fuzzing and property testing.
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::helper::NestingLevel;
+use crate::{EncodingBox, MethodArgument, Qualifiers};
+
+/// The maximum nesting depth, to avoid overflowing the stack.
+const MAX_DEPTH: usize = 6;
+
+/// Encodings without any contents.
+const SIMPLE: &[EncodingBox] = &[
+    EncodingBox::Char,
+    EncodingBox::Short,
+    EncodingBox::Int,
+    EncodingBox::Long,
+    EncodingBox::LongLong,
+    EncodingBox::UChar,
+    EncodingBox::UShort,
+    EncodingBox::UInt,
+    EncodingBox::ULong,
+    EncodingBox::ULongLong,
+    EncodingBox::Int128,
+    EncodingBox::UInt128,
+    EncodingBox::Float,
+    EncodingBox::Double,
+    EncodingBox::LongDouble,
+    EncodingBox::FloatComplex,
+    EncodingBox::DoubleComplex,
+    EncodingBox::LongDoubleComplex,
+    EncodingBox::Bool,
+    EncodingBox::Void,
+    EncodingBox::String,
+    EncodingBox::Object(None),
+    EncodingBox::Block(None),
+    EncodingBox::Class,
+    EncodingBox::Sel,
+    EncodingBox::Unknown,
+];
+
+/// The integral types that the GNU runtimes allow in bitfields.
+const BITFIELD_TYPES: &[EncodingBox] = &[
+    EncodingBox::Char,
+    EncodingBox::Short,
+    EncodingBox::Int,
+    EncodingBox::Long,
+    EncodingBox::LongLong,
+    EncodingBox::UChar,
+    EncodingBox::UShort,
+    EncodingBox::UInt,
+    EncodingBox::ULong,
+    EncodingBox::ULongLong,
+    EncodingBox::Int128,
+    EncodingBox::UInt128,
+    EncodingBox::Bool,
+];
+
+/// Only generates valid encodings, that is, encodings that can be converted
+/// to a string and parsed back again into an equal encoding.
+///
+/// Notably:
+/// - Struct and union names are always valid identifiers.
+/// - Structs and unions never have fields when they are nested so deeply
+///   that the fields wouldn't be included in the string (e.g. behind two
+///   pointers).
+/// - [`EncodingBox::Unknown`] is never generated directly inside structs,
+///   unions and block signatures, since `@?` would be parsed as a block.
+/// - The items in block signatures never have a stack offset, since that
+///   could be parsed as part of a preceding bitfield.
+impl<'a> Arbitrary<'a> for EncodingBox {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        encoding(u, MAX_DEPTH, NestingLevel::new())
+    }
+}
+
+fn encoding(u: &mut Unstructured<'_>, depth: usize, level: NestingLevel) -> Result<EncodingBox> {
+    let boxed = |u: &mut Unstructured<'_>, level| encoding(u, depth - 1, level).map(Box::new);
+    let container_items = |u: &mut Unstructured<'_>| {
+        if level.include_container_fields() {
+            items(u, depth - 1, level.container()).map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+
+    // Only generate encodings with contents if we have the depth for it
+    let max = if depth == 0 { 2 } else { 11 };
+    Ok(match u.int_in_range(0..=max)? {
+        0 => u.choose(SIMPLE)?.clone(),
+        1 => EncodingBox::Object(Some(object_name(u)?)),
+        2 => {
+            let size = u.arbitrary()?;
+            if u.arbitrary()? {
+                let ty = u.choose(BITFIELD_TYPES)?.clone();
+                EncodingBox::BitField(size, Some(Box::new((u.arbitrary()?, ty))))
+            } else {
+                EncodingBox::BitField(size, None)
+            }
+        }
+        3 => EncodingBox::Block(Some(signature(u, depth - 1)?)),
+        4 => EncodingBox::Pointer(boxed(u, level.pointer())?),
+        5 => EncodingBox::Atomic(boxed(u, level.atomic())?),
+        6 => EncodingBox::Array(u.arbitrary()?, boxed(u, level.array())?),
+        7 => EncodingBox::Vector(u.arbitrary()?, u.arbitrary()?, boxed(u, level.vector())?),
+        8 => EncodingBox::Struct(container_name(u)?, None),
+        9 => EncodingBox::Struct(container_name(u)?, container_items(u)?),
+        10 => EncodingBox::Union(container_name(u)?, None),
+        _ => EncodingBox::Union(container_name(u)?, container_items(u)?),
+    })
+}
+
+/// The items in a struct, union or block signature.
+fn items(u: &mut Unstructured<'_>, depth: usize, level: NestingLevel) -> Result<Vec<EncodingBox>> {
+    let mut items = Vec::new();
+    while u.arbitrary()? {
+        items.push(match encoding(u, depth, level)? {
+            // Use a function pointer instead, see above
+            EncodingBox::Unknown => EncodingBox::Pointer(Box::new(EncodingBox::Unknown)),
+            item => item,
+        });
+    }
+    Ok(items)
+}
+
+/// The items in a block signature, with qualifiers.
+fn signature(u: &mut Unstructured<'_>, depth: usize) -> Result<Vec<MethodArgument>> {
+    items(u, depth, NestingLevel::new())?
+        .into_iter()
+        .map(|encoding| {
+            Ok(MethodArgument {
+                qualifiers: qualifiers(u)?,
+                encoding,
+                offset: None,
+            })
+        })
+        .collect()
+}
+
+const QUALIFIERS: &[Qualifiers] = &[
+    Qualifiers::CONST,
+    Qualifiers::IN,
+    Qualifiers::INOUT,
+    Qualifiers::OUT,
+    Qualifiers::BYCOPY,
+    Qualifiers::BYREF,
+    Qualifiers::ONEWAY,
+];
+
+fn qualifiers(u: &mut Unstructured<'_>) -> Result<Qualifiers> {
+    let mut qualifiers = Qualifiers::empty();
+    for qualifier in QUALIFIERS {
+        if u.ratio(1, 8)? {
+            qualifiers |= *qualifier;
+        }
+    }
+    Ok(qualifiers)
+}
+
+const IDENTIFIER_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_";
+
+fn identifier(u: &mut Unstructured<'_>) -> Result<String> {
+    let mut name = String::from(*u.choose(IDENTIFIER_CHARS)? as char);
+    while u.arbitrary()? {
+        name.push(*u.choose(IDENTIFIER_CHARS)? as char);
+    }
+    Ok(name)
+}
+
+fn container_name(u: &mut Unstructured<'_>) -> Result<String> {
+    if u.ratio(1, 8)? {
+        Ok(String::from("?"))
+    } else {
+        identifier(u)
+    }
+}
+
+/// A class name, a protocol name or a class with a protocol.
+fn object_name(u: &mut Unstructured<'_>) -> Result<String> {
+    Ok(match u.int_in_range(0..=2)? {
+        0 => identifier(u)?,
+        1 => alloc::format!("<{}>", identifier(u)?),
+        _ => alloc::format!("{}<{}>", identifier(u)?, identifier(u)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use core::str::FromStr;
+
+    use crate::Encoding;
+
+    /// Run a property with many pseudo-random encodings.
+    fn check(mut f: impl FnMut(&mut Unstructured<'_>) -> Result<()>) {
+        // xorshift64*, to avoid a dependency on `rand`
+        let mut state: u64 = 0x853c_49e6_748f_ea9b;
+        let mut data = [0; 512];
+        for _ in 0..2000 {
+            for chunk in data.chunks_mut(8) {
+                state ^= state >> 12;
+                state ^= state << 25;
+                state ^= state >> 27;
+                let bytes = state.wrapping_mul(0x2545_f491_4f6c_dd1d).to_le_bytes();
+                chunk.copy_from_slice(&bytes);
+            }
+            f(&mut Unstructured::new(&data)).unwrap();
+        }
+    }
+
+    /// Convert to an `Encoding` by leaking the contents, if possible.
+    fn to_encoding(enc: &EncodingBox) -> Option<Encoding> {
+        fn leak<T>(value: T) -> &'static T {
+            Box::leak(Box::new(value))
+        }
+        let items = |items: &[EncodingBox]| -> Option<&'static [Encoding]> {
+            let items: Option<Vec<_>> = items.iter().map(to_encoding).collect();
+            Some(Box::leak(items?.into_boxed_slice()))
+        };
+        let name = |name: &str| -> &'static str { Box::leak(name.into()) };
+        Some(match enc {
+            EncodingBox::Char => Encoding::Char,
+            EncodingBox::Short => Encoding::Short,
+            EncodingBox::Int => Encoding::Int,
+            EncodingBox::Long => Encoding::Long,
+            EncodingBox::LongLong => Encoding::LongLong,
+            EncodingBox::UChar => Encoding::UChar,
+            EncodingBox::UShort => Encoding::UShort,
+            EncodingBox::UInt => Encoding::UInt,
+            EncodingBox::ULong => Encoding::ULong,
+            EncodingBox::ULongLong => Encoding::ULongLong,
+            EncodingBox::Int128 => Encoding::Int128,
+            EncodingBox::UInt128 => Encoding::UInt128,
+            EncodingBox::Float => Encoding::Float,
+            EncodingBox::Double => Encoding::Double,
+            EncodingBox::LongDouble => Encoding::LongDouble,
+            EncodingBox::FloatComplex => Encoding::FloatComplex,
+            EncodingBox::DoubleComplex => Encoding::DoubleComplex,
+            EncodingBox::LongDoubleComplex => Encoding::LongDoubleComplex,
+            EncodingBox::Bool => Encoding::Bool,
+            EncodingBox::Void => Encoding::Void,
+            EncodingBox::String => Encoding::String,
+            EncodingBox::Object(_) => Encoding::Object,
+            EncodingBox::Block(_) => Encoding::Block,
+            EncodingBox::Class => Encoding::Class,
+            EncodingBox::Sel => Encoding::Sel,
+            EncodingBox::Unknown => Encoding::Unknown,
+            EncodingBox::BitField(size, None) => Encoding::BitField(*size, None),
+            EncodingBox::BitField(size, Some(b)) => {
+                Encoding::BitField(*size, Some(leak((b.0, to_encoding(&b.1)?))))
+            }
+            EncodingBox::Pointer(t) => Encoding::Pointer(leak(to_encoding(t)?)),
+            EncodingBox::Atomic(t) => Encoding::Atomic(leak(to_encoding(t)?)),
+            EncodingBox::Array(len, item) => Encoding::Array(*len, leak(to_encoding(item)?)),
+            EncodingBox::Vector(size, alignment, item) => {
+                Encoding::Vector(*size, *alignment, leak(to_encoding(item)?))
+            }
+            EncodingBox::Struct(n, Some(fields)) => Encoding::Struct(name(n), items(fields)?),
+            EncodingBox::Union(n, Some(members)) => Encoding::Union(name(n), items(members)?),
+            // Containers without items can't be represented by `Encoding`
+            EncodingBox::Struct(_, None) | EncodingBox::Union(_, None) => return None,
+        })
+    }
+
+    #[test]
+    fn roundtrip() {
+        check(|u| {
+            let enc = EncodingBox::arbitrary(u)?;
+            let s = enc.to_string();
+            assert_eq!(EncodingBox::from_str(&s), Ok(enc.clone()), "{s}");
+
+            if let Some(static_enc) = to_encoding(&enc) {
+                assert!(static_enc.equivalent_to_box(&enc), "{s}");
+                assert!(static_enc.equivalent_to_str(&s), "{s}");
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn from_start_of_str() {
+        check(|u| {
+            let s1 = EncodingBox::arbitrary(u)?.to_string();
+            let s2 = EncodingBox::arbitrary(u)?.to_string();
+            // `@` followed by `?` is parsed as a block
+            if s1.ends_with('@') && s2.starts_with('?') {
+                return Ok(());
+            }
+            let concatenated = alloc::format!("{s1}{s2}");
+
+            let mut remaining = &*concatenated;
+            let enc = EncodingBox::from_start_of_str(&mut remaining).unwrap();
+            assert_eq!(enc.to_string(), s1);
+            assert_eq!(remaining, s2);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn equivalence_symmetric() {
+        check(|u| {
+            let enc1 = EncodingBox::arbitrary(u)?;
+            // Make it likely that the encodings are similar
+            let enc2 = if u.arbitrary()? {
+                enc1.clone()
+            } else {
+                EncodingBox::arbitrary(u)?
+            };
+            if let (Some(static1), Some(static2)) = (to_encoding(&enc1), to_encoding(&enc2)) {
+                assert_eq!(
+                    static1.equivalent_to_box(&enc2),
+                    static2.equivalent_to_box(&enc1),
+                    "{enc1} and {enc2}",
+                );
+                assert_eq!(
+                    static1.equivalent_to(&static2),
+                    static2.equivalent_to(&static1),
+                    "{enc1} and {enc2}",
+                );
+            }
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,368 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::helper::{
+    compare_encodings, ContainerKind, EncodingType, Helper, IndirectionKind, NestingLevel,
+};
+use crate::EncodingProfile;
+
+/// A step into the structure of an encoding.
+///
+/// See [`EncodingDiff::path`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PathSegment {
+    /// The pointee of a pointer.
+    Pointer,
+    /// The inner type of an atomic type.
+    Atomic,
+    /// The item type of an array.
+    Array,
+    /// The element type of a vector.
+    Vector,
+    /// The type of a bitfield.
+    BitField,
+    /// A struct with the given name.
+    Struct(String),
+    /// A union with the given name.
+    Union(String),
+    /// The field or member at the given index in a struct or union.
+    Field(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pointer => write!(f, "pointer"),
+            Self::Atomic => write!(f, "atomic"),
+            Self::Array => write!(f, "array"),
+            Self::Vector => write!(f, "vector"),
+            Self::BitField => write!(f, "bitfield"),
+            Self::Struct(name) => write!(f, "struct {name}"),
+            Self::Union(name) => write!(f, "union {name}"),
+            Self::Field(i) => write!(f, "field {i}"),
+        }
+    }
+}
+
+/// The innermost point at which two encodings differ.
+///
+/// This is useful for pointing out exactly which part of a large struct
+/// differs, and is created with [`Encoding::diff_box`] or
+/// [`Encoding::diff_box_in`].
+///
+/// [`Encoding::diff_box`]: crate::Encoding::diff_box
+/// [`Encoding::diff_box_in`]: crate::Encoding::diff_box_in
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_encode::{Encoding, EncodingBox};
+///
+/// const POINT: Encoding = Encoding::Struct("CGPoint", &[Encoding::Double, Encoding::Double]);
+/// const SIZE: Encoding = Encoding::Struct("CGSize", &[Encoding::Float, Encoding::Double]);
+/// const RECT: Encoding = Encoding::Struct("CGRect", &[POINT, SIZE]);
+///
+/// let expected: EncodingBox = "{CGRect={CGPoint=dd}{CGSize=dd}}".parse()?;
+/// let diff = RECT.diff_box(&expected).unwrap();
+/// assert_eq!(
+///     diff.to_string(),
+///     "struct CGRect -> field 1 -> struct CGSize -> field 0: expected 'd', but found 'f'",
+/// );
+/// assert_eq!(diff.expected(), "d");
+/// assert_eq!(diff.actual(), "f");
+///
+/// assert_eq!(POINT.diff_box(&"{CGPoint=dd}".parse()?), None);
+/// # Ok::<(), objc2_encode::ParseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EncodingDiff {
+    path: Vec<PathSegment>,
+    expected: String,
+    actual: String,
+}
+
+impl EncodingDiff {
+    /// The path from the top-level encoding to the point where the
+    /// encodings differ.
+    ///
+    /// This is empty if the top-level encodings themselves differ.
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The expected encoding at the point where the encodings differ.
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// The actual encoding at the point where the encodings differ.
+    pub fn actual(&self) -> &str {
+        &self.actual
+    }
+}
+
+/// Formats the path (separated by `->`), followed by both sides of the
+/// difference.
+impl fmt::Display for EncodingDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.path.iter().enumerate() {
+            if i == 0 {
+                write!(f, "{segment}")?;
+            } else {
+                write!(f, " -> {segment}")?;
+            }
+        }
+        if !self.path.is_empty() {
+            write!(f, ": ")?;
+        }
+        write!(
+            f,
+            "expected '{}', but found '{}'",
+            self.expected, self.actual
+        )
+    }
+}
+
+pub(crate) fn diff_encodings<E1: EncodingType, E2: EncodingType>(
+    actual: &E1,
+    expected: &E2,
+    profile: Option<EncodingProfile>,
+) -> Option<EncodingDiff> {
+    let mut path = Vec::new();
+    let (actual, expected) = find_difference(
+        actual,
+        NestingLevel::new(),
+        expected,
+        NestingLevel::new(),
+        profile,
+        &mut path,
+    )?;
+    Some(EncodingDiff {
+        path,
+        expected,
+        actual,
+    })
+}
+
+/// Find the innermost difference, and push the path to it.
+fn find_difference<E1: EncodingType, E2: EncodingType>(
+    actual: &E1,
+    level1: NestingLevel,
+    expected: &E2,
+    level2: NestingLevel,
+    profile: Option<EncodingProfile>,
+    path: &mut Vec<PathSegment>,
+) -> Option<(String, String)> {
+    use Helper::*;
+
+    if compare_encodings(actual, level1, expected, level2, false, profile) {
+        return None;
+    }
+
+    let res = match (actual.helper(level1), expected.helper(level2)) {
+        (Indirection(kind1, t1, l1), Indirection(kind2, t2, l2)) if kind1 == kind2 => {
+            let segment = match kind1 {
+                IndirectionKind::Pointer => PathSegment::Pointer,
+                IndirectionKind::Atomic => PathSegment::Atomic,
+            };
+            find_within(segment, t1, l1, t2, l2, profile, path)
+        }
+        (Array(len1, item1, l1), Array(len2, item2, l2)) if len1 == len2 => {
+            find_within(PathSegment::Array, item1, l1, item2, l2, profile, path)
+        }
+        (Vector(size1, align1, item1, l1), Vector(size2, align2, item2, l2))
+            if size1 == size2 && align1 == align2 =>
+        {
+            find_within(PathSegment::Vector, item1, l1, item2, l2, profile, path)
+        }
+        (BitField(size1, Some((offset1, t1)), l1), BitField(size2, Some((offset2, t2)), l2))
+            if size1 == size2 && offset1 == offset2 =>
+        {
+            find_within(PathSegment::BitField, t1, l1, t2, l2, profile, path)
+        }
+        (Container(kind1, name1, Some(items1), l1), Container(kind2, name2, Some(items2), l2))
+            if kind1 == kind2 && name1 == name2 && items1.len() == items2.len() =>
+        {
+            path.push(match kind1 {
+                ContainerKind::Struct => PathSegment::Struct(name1.to_string()),
+                ContainerKind::Union => PathSegment::Union(name1.to_string()),
+            });
+            let res = items1
+                .iter()
+                .zip(items2)
+                .enumerate()
+                .find_map(|(i, (item1, item2))| {
+                    find_within(PathSegment::Field(i), item1, l1, item2, l2, profile, path)
+                });
+            if res.is_none() {
+                path.pop();
+            }
+            res
+        }
+        _ => None,
+    };
+
+    // If we couldn't find a difference further down, the difference is here
+    res.or_else(|| {
+        Some((
+            actual.helper(level1).to_string(),
+            expected.helper(level2).to_string(),
+        ))
+    })
+}
+
+/// Find the difference within a part of the encodings.
+fn find_within<E1: EncodingType, E2: EncodingType>(
+    segment: PathSegment,
+    actual: &E1,
+    level1: NestingLevel,
+    expected: &E2,
+    level2: NestingLevel,
+    profile: Option<EncodingProfile>,
+    path: &mut Vec<PathSegment>,
+) -> Option<(String, String)> {
+    path.push(segment);
+    let res = find_difference(actual, level1, expected, level2, profile, path);
+    if res.is_none() {
+        path.pop();
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Encoding, EncodingBox};
+    use alloc::vec;
+    use core::str::FromStr;
+
+    #[track_caller]
+    fn assert_diff(actual: &Encoding, expected: &str, path: &[PathSegment], parts: (&str, &str)) {
+        let expected = EncodingBox::from_str(expected).unwrap();
+        let diff = actual.diff_box(&expected).expect("differ");
+        assert_eq!(diff.path(), path);
+        assert_eq!((diff.expected(), diff.actual()), parts);
+    }
+
+    #[test]
+    fn equivalent() {
+        let enc = Encoding::Struct("S", &[Encoding::Int, Encoding::Pointer(&Encoding::Char)]);
+        assert_eq!(
+            enc.diff_box(&EncodingBox::from_str("{S=i^c}").unwrap()),
+            None
+        );
+        assert_eq!(
+            enc.diff_box(&EncodingBox::from_str("r{S=i^c}").unwrap()),
+            None
+        );
+        assert_eq!(
+            Encoding::Object.diff_box(&EncodingBox::from_str("@\"NSString\"").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn top_level() {
+        assert_diff(&Encoding::Int, "I", &[], ("I", "i"));
+        assert_diff(
+            &Encoding::Struct("A", &[Encoding::Int]),
+            "{B=i}",
+            &[],
+            ("{B=i}", "{A=i}"),
+        );
+        assert_diff(
+            &Encoding::Array(2, &Encoding::Int),
+            "[3i]",
+            &[],
+            ("[3i]", "[2i]"),
+        );
+        // Different number of fields
+        assert_diff(
+            &Encoding::Struct("A", &[Encoding::Int]),
+            "{A=ii}",
+            &[],
+            ("{A=ii}", "{A=i}"),
+        );
+    }
+
+    #[test]
+    fn nested() {
+        const TRANSFORM: Encoding = Encoding::Struct(
+            "CGAffineTransform",
+            &[
+                Encoding::Double,
+                Encoding::Double,
+                Encoding::Double,
+                Encoding::Float,
+                Encoding::Double,
+                Encoding::Double,
+            ],
+        );
+        assert_diff(
+            &TRANSFORM,
+            "{CGAffineTransform=dddddd}",
+            &[
+                PathSegment::Struct("CGAffineTransform".into()),
+                PathSegment::Field(3),
+            ],
+            ("d", "f"),
+        );
+
+        assert_diff(
+            &Encoding::Pointer(&Encoding::Array(
+                4,
+                &Encoding::Union("U", &[Encoding::Atomic(&Encoding::Int)]),
+            )),
+            "^[4(U=AI)]",
+            &[
+                PathSegment::Pointer,
+                PathSegment::Array,
+                PathSegment::Union("U".into()),
+                PathSegment::Field(0),
+                PathSegment::Atomic,
+            ],
+            ("I", "i"),
+        );
+
+        assert_diff(
+            &Encoding::BitField(2, Some(&(0, Encoding::UInt))),
+            "b0i2",
+            &[PathSegment::BitField],
+            ("i", "I"),
+        );
+    }
+
+    #[test]
+    fn profile() {
+        let expected = EncodingBox::from_str("{S=C}").unwrap();
+        let enc = Encoding::Struct("S", &[Encoding::Bool]);
+        assert!(enc.diff_box(&expected).is_some());
+        assert_eq!(enc.diff_box_in(&expected, EncodingProfile::GNUstep), None);
+        assert_eq!(
+            enc.diff_box_in(&expected, EncodingProfile::Apple)
+                .unwrap()
+                .path(),
+            vec![PathSegment::Struct("S".into()), PathSegment::Field(0)]
+        );
+    }
+
+    #[test]
+    fn display() {
+        let expected = EncodingBox::from_str("{A={B=i}}").unwrap();
+        let enc = Encoding::Struct("A", &[Encoding::Struct("B", &[Encoding::Long])]);
+        assert_eq!(
+            enc.diff_box(&expected).unwrap().to_string(),
+            "struct A -> field 0 -> struct B -> field 0: expected 'i', but found 'l'"
+        );
+        assert_eq!(
+            Encoding::Int
+                .diff_box(&EncodingBox::Sel)
+                .unwrap()
+                .to_string(),
+            "expected ':', but found 'i'"
+        );
+    }
+}
@@ -1,8 +1,12 @@
 use core::fmt;
+use core::str::FromStr;
 
+use crate::diff::diff_encodings;
 use crate::helper::{compare_encodings, Helper, NestingLevel};
 use crate::parse::Parser;
-use crate::EncodingBox;
+use crate::profile::ProfileDisplay;
+use crate::static_str::{static_encoding_str_array, static_encoding_str_len};
+use crate::{EncodingBox, EncodingDiff, EncodingProfile};
 
 /// An Objective-C type-encoding.
 ///
@@ -64,6 +68,10 @@ pub enum Encoding {
     ULong,
     /// A C `unsigned long long`. Corresponds to the `"Q"` code.
     ULongLong,
+    /// A C `__int128`. Corresponds to the `"t"` code.
+    Int128,
+    /// A C `unsigned __int128`. Corresponds to the `"T"` code.
+    UInt128,
     /// A C `float`. Corresponds to the `"f"` code.
     Float,
     /// A C `double`. Corresponds to the `"d"` code.
@@ -135,10 +143,16 @@ pub enum Encoding {
     ///
     /// Corresponds to the `"(" name "=" fields... ")"` code.
     Union(&'static str, &'static [Encoding]),
-    // TODO: "Vector" types have the '!' encoding, but are not implemented in
-    // clang
-
-    // TODO: `t` and `T` codes for i128 and u128?
+    /// A SIMD vector with the given size and alignment in bytes, and the
+    /// given element type, e.g. `simd_float4` or a type declared with
+    /// `__attribute__((vector_size(16)))`.
+    ///
+    /// Corresponds to the `"!" "[" size "," alignment type "]"` code, e.g.
+    /// `![16,16f]`.
+    ///
+    /// Note that `clang` does not currently emit an encoding for vector
+    /// types, while GCC does.
+    Vector(u64, u64, &'static Encoding),
 }
 
 impl Encoding {
@@ -196,7 +210,14 @@ impl Encoding {
     /// For example, you should not rely on two equivalent encodings to have
     /// the same size or ABI - that is provided on a best-effort basis.
     pub fn equivalent_to(&self, other: &Self) -> bool {
-        compare_encodings(self, NestingLevel::new(), other, NestingLevel::new(), false)
+        compare_encodings(
+            self,
+            NestingLevel::new(),
+            other,
+            NestingLevel::new(),
+            false,
+            None,
+        )
     }
 
     /// Check if an encoding is equivalent to the given string representation.
@@ -222,7 +243,105 @@ impl Encoding {
     /// See [`Encoding::equivalent_to`] for details about the meaning of
     /// "equivalence".
     pub fn equivalent_to_box(&self, other: &EncodingBox) -> bool {
-        compare_encodings(self, NestingLevel::new(), other, NestingLevel::new(), false)
+        compare_encodings(
+            self,
+            NestingLevel::new(),
+            other,
+            NestingLevel::new(),
+            false,
+            None,
+        )
+    }
+
+    /// Check if one encoding is equivalent to another, taking the quirks of
+    /// the given runtime into account.
+    ///
+    /// See [`EncodingProfile`] for details about which quirks are handled.
+    pub fn equivalent_to_in(&self, other: &Self, profile: EncodingProfile) -> bool {
+        compare_encodings(
+            self,
+            NestingLevel::new(),
+            other,
+            NestingLevel::new(),
+            false,
+            Some(profile),
+        )
+    }
+
+    /// Check if an encoding is equivalent to the given string
+    /// representation, taking the quirks of the given runtime into account.
+    ///
+    /// Returns `false` if the string could not be parsed.
+    ///
+    /// See [`EncodingProfile`] for details about which quirks are handled.
+    pub fn equivalent_to_str_in(&self, s: &str, profile: EncodingProfile) -> bool {
+        match EncodingBox::from_str(s) {
+            Ok(other) => self.equivalent_to_box_in(&other, profile),
+            Err(_) => false,
+        }
+    }
+
+    /// Check if an encoding is equivalent to a boxed encoding, taking the
+    /// quirks of the given runtime into account.
+    ///
+    /// See [`EncodingProfile`] for details about which quirks are handled.
+    pub fn equivalent_to_box_in(&self, other: &EncodingBox, profile: EncodingProfile) -> bool {
+        compare_encodings(
+            self,
+            NestingLevel::new(),
+            other,
+            NestingLevel::new(),
+            false,
+            Some(profile),
+        )
+    }
+
+    /// Find the innermost point at which this encoding differs from the
+    /// expected boxed encoding.
+    ///
+    /// Returns `None` if the encodings are equivalent, see
+    /// [`Encoding::equivalent_to_box`].
+    pub fn diff_box(&self, expected: &EncodingBox) -> Option<EncodingDiff> {
+        diff_encodings(self, expected, None)
+    }
+
+    /// Find the innermost point at which this encoding differs from the
+    /// expected boxed encoding, taking the quirks of the given runtime into
+    /// account.
+    ///
+    /// Returns `None` if the encodings are equivalent, see
+    /// [`Encoding::equivalent_to_box_in`].
+    pub fn diff_box_in(
+        &self,
+        expected: &EncodingBox,
+        profile: EncodingProfile,
+    ) -> Option<EncodingDiff> {
+        diff_encodings(self, expected, Some(profile))
+    }
+
+    /// Format the encoding in the way that the compiler used with the given
+    /// runtime would.
+    ///
+    /// The [`Display`][`fmt::Display`] implementation of [`Encoding`]
+    /// formats the encoding as it was given, which may differ from this.
+    pub fn display_in(&self, profile: EncodingProfile) -> impl fmt::Display + '_ {
+        ProfileDisplay::new(self, profile)
+    }
+
+    /// The length of the string representation of this encoding.
+    ///
+    /// Used together with [`Encoding::__static_str_array`] to build encoding
+    /// strings at compile-time, e.g. for block signatures in `block2`.
+    #[doc(hidden)]
+    pub const fn __static_str_len(&self) -> usize {
+        static_encoding_str_len(self, NestingLevel::new())
+    }
+
+    /// The string representation of this encoding, in an array of length
+    /// [`Encoding::__static_str_len`].
+    #[doc(hidden)]
+    pub const fn __static_str_array<const LEN: usize>(&self) -> [u8; LEN] {
+        static_encoding_str_array(self, NestingLevel::new())
     }
 }
 
@@ -238,11 +357,19 @@ impl fmt::Display for Encoding {
     }
 }
 
+/// Serializes the encoding as its canonical string, the same as
+/// [`EncodingBox`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for Encoding {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::helper::NestingLevel;
-    use crate::static_str::{static_encoding_str_array, static_encoding_str_len};
     use alloc::string::ToString;
     use alloc::vec;
     use core::str::FromStr;
@@ -362,13 +489,22 @@ mod tests {
         fn block() {
             Encoding::Block;
             "@?";
+            // Extended
+            ~"@?<v@?>";
+            ~"@?<@\"NSString\"@?r^vi>";
+            !"@?<v@?";
         }
 
         fn object() {
             Encoding::Object;
             !Encoding::Block;
             "@";
+            // Extended
+            ~"@\"NSString\"";
+            ~"@\"<NSCopying>\"";
+            ~"@\"NSObject<NSCopying><NSCoding>\"";
             !"@?";
+            !"@\"NSString";
         }
 
         fn unknown() {
@@ -444,6 +580,31 @@ mod tests {
             !"[12i";
         }
 
+        fn int128() {
+            Encoding::Int128;
+            !Encoding::UInt128;
+            !Encoding::LongLong;
+            "t";
+            !"T";
+        }
+
+        fn vector() {
+            Encoding::Vector(16, 16, &Encoding::Float);
+            !Encoding::Vector(16, 4, &Encoding::Float);
+            !Encoding::Vector(8, 16, &Encoding::Float);
+            !Encoding::Vector(16, 16, &Encoding::Int);
+            !Encoding::Array(4, &Encoding::Float);
+            "![16,16f]";
+            !"![16,16f";
+            !"![16f]";
+            !"[16,16f]";
+        }
+
+        fn struct_vector() {
+            Encoding::Struct("simd_float4x2", &[Encoding::Array(2, &Encoding::Vector(16, 16, &Encoding::Float))]);
+            "{simd_float4x2=[2![16,16f]]}";
+        }
+
         fn struct_() {
             Encoding::Struct("SomeStruct", &[Encoding::Char, Encoding::Int]);
             !Encoding::Union("SomeStruct", &[Encoding::Char, Encoding::Int]);
@@ -591,7 +752,7 @@ mod tests {
         let parsed = EncodingBox::from_str(s).unwrap();
         let expected = EncodingBox::Struct(
             "S".to_string(),
-            Some(vec![EncodingBox::Block, EncodingBox::Block]),
+            Some(vec![EncodingBox::Block(None), EncodingBox::Block(None)]),
         );
         assert_eq!(parsed, expected);
 
@@ -1,18 +1,20 @@
 use alloc::boxed::Box;
-use alloc::string::String;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 use core::str::FromStr;
 
 use crate::helper::{compare_encodings, Helper, NestingLevel};
 use crate::parse::{ParseError, Parser};
-use crate::Encoding;
+use crate::profile::ProfileDisplay;
+use crate::{Encoding, EncodingProfile, MethodArgument};
 
 /// The boxed version of [`Encoding`].
 ///
-/// This has exactly the same items as `Encoding`, the only difference is in
-/// where the contents of the more complex encodings like [`Struct`] are
-/// stored.
+/// This has the same items as `Encoding`, the main difference is in where
+/// the contents of the more complex encodings like [`Struct`] are stored.
+/// Additionally, [`Object`] and [`Block`] can contain the extra information
+/// from extended type encodings, since those are only ever parsed.
 ///
 /// In `Encoding`, the data is stored in static memory, while in `EncodingBox`
 /// it is stored on the heap. The former allows storing in constants (which is
@@ -25,6 +27,8 @@ use crate::Encoding;
 /// is possible.
 ///
 /// [`Struct`]: Self::Struct
+/// [`Object`]: Self::Object
+/// [`Block`]: Self::Block
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive] // Maybe we're missing some encodings?
 pub enum EncodingBox {
@@ -48,6 +52,10 @@ pub enum EncodingBox {
     ULong,
     /// Same as [`Encoding::ULongLong`].
     ULongLong,
+    /// Same as [`Encoding::Int128`].
+    Int128,
+    /// Same as [`Encoding::UInt128`].
+    UInt128,
     /// Same as [`Encoding::Float`].
     Float,
     /// Same as [`Encoding::Double`].
@@ -66,10 +74,26 @@ pub enum EncodingBox {
     Void,
     /// Same as [`Encoding::String`].
     String,
-    /// Same as [`Encoding::Object`].
-    Object,
-    /// Same as [`Encoding::Block`].
-    Block,
+    /// Same as [`Encoding::Object`], along with the class or protocol name
+    /// from an extended type encoding, if present.
+    ///
+    /// The name is stored as it appears between the quotes, e.g.
+    /// `"NSString"` for `@"NSString"`, `"<NSCopying>"` for
+    /// `@"<NSCopying>"` and `"NSObject<NSCopying>"` for
+    /// `@"NSObject<NSCopying>"`.
+    ///
+    /// The name is ignored when checking equivalence with an [`Encoding`].
+    Object(Option<String>),
+    /// Same as [`Encoding::Block`], along with the block's signature from an
+    /// extended type encoding, if present.
+    ///
+    /// The signature contains the return type, the block itself and then the
+    /// arguments, along with their qualifiers, e.g. `[Void, Block(None),
+    /// Object(Some("NSData"))]` for `@?<v@?@"NSData">`.
+    ///
+    /// The signature is ignored when checking equivalence with an
+    /// [`Encoding`].
+    Block(Option<Vec<MethodArgument>>),
     /// Same as [`Encoding::Class`].
     Class,
     /// Same as [`Encoding::Sel`].
@@ -88,6 +112,8 @@ pub enum EncodingBox {
     Struct(String, Option<Vec<Self>>),
     /// Same as [`Encoding::Union`].
     Union(String, Option<Vec<Self>>),
+    /// Same as [`Encoding::Vector`].
+    Vector(u64, u64, Box<Self>),
 }
 
 impl EncodingBox {
@@ -125,6 +151,14 @@ impl EncodingBox {
             }
         }
     }
+
+    /// Format the encoding in the way that the compiler used with the given
+    /// runtime would.
+    ///
+    /// See [`Encoding::display_in`] for details.
+    pub fn display_in(&self, profile: EncodingProfile) -> impl fmt::Display + '_ {
+        ProfileDisplay::from_box(self, profile)
+    }
 }
 
 /// Same formatting as [`Encoding`]'s `Display` implementation.
@@ -134,9 +168,61 @@ impl fmt::Display for EncodingBox {
     }
 }
 
+impl From<&Encoding> for EncodingBox {
+    fn from(encoding: &Encoding) -> Self {
+        let items = |items: &[Encoding]| Some(items.iter().map(Self::from).collect());
+        match encoding {
+            Encoding::Char => Self::Char,
+            Encoding::Short => Self::Short,
+            Encoding::Int => Self::Int,
+            Encoding::Long => Self::Long,
+            Encoding::LongLong => Self::LongLong,
+            Encoding::UChar => Self::UChar,
+            Encoding::UShort => Self::UShort,
+            Encoding::UInt => Self::UInt,
+            Encoding::ULong => Self::ULong,
+            Encoding::ULongLong => Self::ULongLong,
+            Encoding::Int128 => Self::Int128,
+            Encoding::UInt128 => Self::UInt128,
+            Encoding::Float => Self::Float,
+            Encoding::Double => Self::Double,
+            Encoding::LongDouble => Self::LongDouble,
+            Encoding::FloatComplex => Self::FloatComplex,
+            Encoding::DoubleComplex => Self::DoubleComplex,
+            Encoding::LongDoubleComplex => Self::LongDoubleComplex,
+            Encoding::Bool => Self::Bool,
+            Encoding::Void => Self::Void,
+            Encoding::String => Self::String,
+            Encoding::Object => Self::Object(None),
+            Encoding::Block => Self::Block(None),
+            Encoding::Class => Self::Class,
+            Encoding::Sel => Self::Sel,
+            Encoding::Unknown => Self::Unknown,
+            Encoding::BitField(size, t) => {
+                Self::BitField(*size, t.map(|(offset, t)| Box::new((*offset, t.into()))))
+            }
+            Encoding::Pointer(t) => Self::Pointer(Box::new((*t).into())),
+            Encoding::Atomic(t) => Self::Atomic(Box::new((*t).into())),
+            Encoding::Array(len, item) => Self::Array(*len, Box::new((*item).into())),
+            Encoding::Struct(name, fields) => Self::Struct(name.to_string(), items(fields)),
+            Encoding::Union(name, members) => Self::Union(name.to_string(), items(members)),
+            Encoding::Vector(size, alignment, item) => {
+                Self::Vector(*size, *alignment, Box::new((*item).into()))
+            }
+        }
+    }
+}
+
 impl PartialEq<Encoding> for EncodingBox {
     fn eq(&self, other: &Encoding) -> bool {
-        compare_encodings(self, NestingLevel::new(), other, NestingLevel::new(), true)
+        compare_encodings(
+            self,
+            NestingLevel::new(),
+            other,
+            NestingLevel::new(),
+            true,
+            None,
+        )
     }
 }
 
@@ -160,12 +246,66 @@ impl FromStr for EncodingBox {
     }
 }
 
+/// Serializes the encoding as its canonical string, e.g. `"{CGPoint=dd}"`.
+///
+/// See the [`structured`] module for a structured alternative.
+///
+/// [`structured`]: crate::structured
+#[cfg(feature = "serde")]
+impl serde::Serialize for EncodingBox {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Parses the encoding from a string, using the [`FromStr`] implementation.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EncodingBox {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EncodingVisitor;
+
+        impl serde::de::Visitor<'_> for EncodingVisitor {
+            type Value = EncodingBox;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an Objective-C type-encoding")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                EncodingBox::from_str(s).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(EncodingVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Qualifiers;
     use alloc::string::ToString;
     use alloc::vec;
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let enc = EncodingBox::from_str("^{CGPoint=dd}").unwrap();
+        let json = serde_json::to_string(&enc).unwrap();
+        assert_eq!(json, r#""^{CGPoint=dd}""#);
+        assert_eq!(serde_json::from_str::<EncodingBox>(&json).unwrap(), enc);
+
+        const ENC: Encoding = Encoding::Pointer(&Encoding::Struct(
+            "CGPoint",
+            &[Encoding::Double, Encoding::Double],
+        ));
+        assert_eq!(serde_json::to_string(&ENC).unwrap(), json);
+
+        let err = serde_json::from_str::<EncodingBox>(r#""{A=i""#).unwrap_err();
+        assert!(err.to_string().contains("expected struct to be closed"));
+        assert!(serde_json::from_str::<EncodingBox>("1").is_err());
+    }
+
     #[test]
     fn eq_encodings() {
         let enc1 = Encoding::Char;
@@ -234,6 +374,73 @@ mod tests {
         assert_eq!(expected.to_string(), "AA{a}");
     }
 
+    #[test]
+    fn parse_extended() {
+        #[track_caller]
+        fn assert_roundtrip(s: &str, expected: EncodingBox) {
+            let actual = EncodingBox::from_str(s).unwrap();
+            assert_eq!(expected, actual);
+            assert_eq!(expected.to_string(), s);
+        }
+
+        assert_roundtrip("@", EncodingBox::Object(None));
+        assert_roundtrip(
+            "@\"NSString\"",
+            EncodingBox::Object(Some("NSString".into())),
+        );
+        assert_roundtrip(
+            "@\"NSObject<NSCopying>\"",
+            EncodingBox::Object(Some("NSObject<NSCopying>".into())),
+        );
+        assert_roundtrip("@?", EncodingBox::Block(None));
+        assert_roundtrip("@?<>", EncodingBox::Block(Some(vec![])));
+        assert_roundtrip(
+            "^@?<v@?@\"NSData\"@?<v@?>>",
+            EncodingBox::Pointer(Box::new(EncodingBox::Block(Some(vec![
+                EncodingBox::Void.into(),
+                EncodingBox::Block(None).into(),
+                EncodingBox::Object(Some("NSData".into())).into(),
+                EncodingBox::Block(Some(vec![
+                    EncodingBox::Void.into(),
+                    EncodingBox::Block(None).into(),
+                ]))
+                .into(),
+            ])))),
+        );
+        assert_roundtrip(
+            "@?<v@?r*>",
+            EncodingBox::Block(Some(vec![
+                EncodingBox::Void.into(),
+                EncodingBox::Block(None).into(),
+                MethodArgument {
+                    qualifiers: Qualifiers::CONST,
+                    encoding: EncodingBox::String,
+                    offset: None,
+                },
+            ])),
+        );
+
+        // Extended information is ignored when comparing with `Encoding`
+        assert_eq!(
+            EncodingBox::Object(Some("NSString".into())),
+            Encoding::Object
+        );
+        assert_eq!(EncodingBox::Block(Some(vec![])), Encoding::Block);
+        assert_ne!(
+            EncodingBox::Object(Some("NSString".into())),
+            Encoding::Block
+        );
+        assert_ne!(
+            EncodingBox::Object(Some("NSString".into())),
+            EncodingBox::Object(None),
+        );
+
+        assert!(EncodingBox::from_str("@\"NSString").is_err());
+        assert!(EncodingBox::from_str("@?<v@?").is_err());
+        // Qualifiers are only supported at the start and in block signatures
+        assert!(EncodingBox::from_str("^r*").is_err());
+    }
+
     #[test]
     fn parse_part_of_string() {
         let mut s = "{a}cb0i16";
@@ -4,6 +4,8 @@ use core::write;
 use crate::parse::verify_name;
 use crate::Encoding;
 use crate::EncodingBox;
+use crate::EncodingProfile;
+use crate::MethodArgument;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum NestingLevel {
@@ -23,12 +25,12 @@ impl NestingLevel {
         self
     }
 
-    const fn atomic(self) -> Self {
+    pub(crate) const fn atomic(self) -> Self {
         // Move all the way down
         Self::Bottom
     }
 
-    const fn pointer(self) -> Self {
+    pub(crate) const fn pointer(self) -> Self {
         // Move one step down
         match self {
             Self::Top => Self::Within,
@@ -36,12 +38,17 @@ impl NestingLevel {
         }
     }
 
-    const fn array(self) -> Self {
+    pub(crate) const fn array(self) -> Self {
         // TODO: Is this correct?
         self
     }
 
-    const fn container(self) -> Self {
+    pub(crate) const fn vector(self) -> Self {
+        // Vectors can only contain primitive types
+        self
+    }
+
+    pub(crate) const fn container(self) -> Self {
         match self {
             // Move top one step down
             Self::Top | Self::Within => Self::Within,
@@ -49,7 +56,7 @@ impl NestingLevel {
         }
     }
 
-    const fn include_container_fields(self) -> bool {
+    pub(crate) const fn include_container_fields(self) -> bool {
         match self {
             Self::Top | Self::Within => true,
             Self::Bottom => false,
@@ -63,6 +70,7 @@ pub(crate) fn compare_encodings<E1: EncodingType, E2: EncodingType>(
     enc2: &E2,
     level2: NestingLevel,
     include_all: bool,
+    profile: Option<EncodingProfile>,
 ) -> bool {
     use Helper::*;
     // Note: Ideally `Block` and sequence of `Object, Unknown` in struct
@@ -82,26 +90,36 @@ pub(crate) fn compare_encodings<E1: EncodingType, E2: EncodingType>(
 
     // TODO: Are level1 and level2 ever be different?
 
-    match (enc1.helper(level1), enc2.helper(level2)) {
-        (Primitive(p1), Primitive(p2)) => p1 == p2,
+    match (enc1.helper(level1).loose(), enc2.helper(level2).loose()) {
+        (Primitive(p1), Primitive(p2)) => {
+            p1 == p2 || profile.map_or(false, |profile| profile.primitives_equivalent(p1, p2))
+        }
         (
             BitField(size1, Some((offset1, type1)), level1),
             BitField(size2, Some((offset2, type2)), level2),
         ) => {
             size1 == size2
                 && offset1 == offset2
-                && compare_encodings(type1, level1, type2, level2, include_all)
+                && compare_encodings(type1, level1, type2, level2, include_all, profile)
         }
         (BitField(size1, None, _level1), BitField(size2, None, _level2)) => size1 == size2,
         // The type-encoding of a bitfield is always either available, or it
         // is not (depends on platform); so if it was available in one, but
-        // not the other, we should compare the encodings unequal.
-        (BitField(_, _, _), BitField(_, _, _)) => false,
+        // not the other, we should compare the encodings unequal - unless
+        // we know that the runtime may add this information.
+        (BitField(size1, _, _), BitField(size2, _, _)) => {
+            size1 == size2 && profile.map_or(false, |profile| profile.gnu_bitfields())
+        }
         (Indirection(kind1, t1, level1), Indirection(kind2, t2, level2)) => {
-            kind1 == kind2 && compare_encodings(t1, level1, t2, level2, include_all)
+            kind1 == kind2 && compare_encodings(t1, level1, t2, level2, include_all, profile)
         }
         (Array(len1, item1, level1), Array(len2, item2, level2)) => {
-            len1 == len2 && compare_encodings(item1, level1, item2, level2, include_all)
+            len1 == len2 && compare_encodings(item1, level1, item2, level2, include_all, profile)
+        }
+        (Vector(size1, align1, item1, level1), Vector(size2, align2, item2, level2)) => {
+            size1 == size2
+                && align1 == align2
+                && compare_encodings(item1, level1, item2, level2, include_all, profile)
         }
         (Container(kind1, name1, items1, level1), Container(kind2, name2, items2, level2)) => {
             kind1 == kind2
@@ -113,7 +131,14 @@ pub(crate) fn compare_encodings<E1: EncodingType, E2: EncodingType>(
                             return false;
                         }
                         for (item1, item2) in items1.iter().zip(items2.iter()) {
-                            if !compare_encodings(item1, level1, item2, level2, include_all) {
+                            if !compare_encodings(
+                                item1,
+                                level1,
+                                item2,
+                                level2,
+                                include_all,
+                                profile,
+                            ) {
                                 return false;
                             }
                         }
@@ -146,6 +171,8 @@ pub(crate) enum Primitive {
     UInt,
     ULong,
     ULongLong,
+    Int128,
+    UInt128,
     Float,
     Double,
     LongDouble,
@@ -176,6 +203,8 @@ impl Primitive {
             UInt => "I",
             ULong => "L",
             ULongLong => "Q",
+            Int128 => "t",
+            UInt128 => "T",
             Float => "f",
             Double => "d",
             LongDouble => "D",
@@ -263,24 +292,64 @@ pub(crate) enum Helper<'a, E = Encoding> {
     BitField(u8, Option<&'a (u64, E)>, NestingLevel),
     Indirection(IndirectionKind, &'a E, NestingLevel),
     Array(u64, &'a E, NestingLevel),
+    Vector(u64, u64, &'a E, NestingLevel),
     Container(ContainerKind, &'a str, Option<&'a [E]>, NestingLevel),
+    /// An object with the class or protocol name from an extended encoding.
+    NamedObject(&'a str),
+    /// A block with the signature from an extended encoding.
+    BlockSignature(&'a [MethodArgument]),
 }
 
-impl<E: EncodingType> fmt::Display for Helper<'_, E> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl<E> Helper<'_, E> {
+    /// Remove the information from extended encodings, since that is not
+    /// considered when checking equivalence.
+    fn loose(self) -> Self {
+        match self {
+            Self::NamedObject(_) => Self::Primitive(Primitive::Object),
+            Self::BlockSignature(_) => Self::Primitive(Primitive::Block),
+            helper => helper,
+        }
+    }
+}
+
+impl<E: EncodingType> Helper<'_, E> {
+    /// Format the encoding, optionally in the way that the compiler of the
+    /// given runtime would.
+    pub(crate) fn fmt_in(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        profile: Option<EncodingProfile>,
+    ) -> fmt::Result {
         match self {
-            Self::Primitive(primitive) => write!(f, "{}", primitive.to_str()),
+            Self::Primitive(primitive) => match profile {
+                Some(profile) => write!(f, "{}", profile.primitive_str(*primitive)),
+                None => write!(f, "{}", primitive.to_str()),
+            },
             Self::BitField(size, None, _level) => {
                 write!(f, "b{size}")
             }
+            Self::BitField(size, Some(_), _level) if matches!(profile, Some(profile) if !profile.gnu_bitfields()) =>
+            {
+                write!(f, "b{size}")
+            }
             Self::BitField(size, Some((offset, t)), level) => {
-                write!(f, "b{offset}{}{size}", t.helper(*level))
+                write!(f, "b{offset}")?;
+                t.helper(*level).fmt_in(f, profile)?;
+                write!(f, "{size}")
             }
             Self::Indirection(kind, t, level) => {
-                write!(f, "{}{}", kind.prefix(), t.helper(*level))
+                write!(f, "{}", kind.prefix())?;
+                t.helper(*level).fmt_in(f, profile)
             }
             Self::Array(len, item, level) => {
-                write!(f, "[{}{}]", len, item.helper(*level))
+                write!(f, "[{len}")?;
+                item.helper(*level).fmt_in(f, profile)?;
+                write!(f, "]")
+            }
+            Self::Vector(size, alignment, item, level) => {
+                write!(f, "![{size},{alignment}")?;
+                item.helper(*level).fmt_in(f, profile)?;
+                write!(f, "]")
             }
             Self::Container(kind, name, items, level) => {
                 write!(f, "{}", kind.start())?;
@@ -288,15 +357,35 @@ impl<E: EncodingType> fmt::Display for Helper<'_, E> {
                 if let Some(items) = items {
                     write!(f, "=")?;
                     for item in *items {
-                        write!(f, "{}", item.helper(*level))?;
+                        item.helper(*level).fmt_in(f, profile)?;
                     }
                 }
                 write!(f, "{}", kind.end())
             }
+            Self::NamedObject(name) => write!(f, "@\"{name}\""),
+            Self::BlockSignature(signature) => {
+                write!(f, "@?<")?;
+                for item in *signature {
+                    write!(f, "{}", item.qualifiers)?;
+                    item.encoding
+                        .helper(NestingLevel::new())
+                        .fmt_in(f, profile)?;
+                    if let Some(offset) = item.offset {
+                        write!(f, "{offset}")?;
+                    }
+                }
+                write!(f, ">")
+            }
         }
     }
 }
 
+impl<E: EncodingType> fmt::Display for Helper<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_in(f, None)
+    }
+}
+
 impl Helper<'_> {
     pub(crate) const fn new(encoding: &Encoding, level: NestingLevel) -> Self {
         use Encoding::*;
@@ -311,6 +400,8 @@ impl Helper<'_> {
             UInt => Self::Primitive(Primitive::UInt),
             ULong => Self::Primitive(Primitive::ULong),
             ULongLong => Self::Primitive(Primitive::ULongLong),
+            Int128 => Self::Primitive(Primitive::Int128),
+            UInt128 => Self::Primitive(Primitive::UInt128),
             Float => Self::Primitive(Primitive::Float),
             Double => Self::Primitive(Primitive::Double),
             LongDouble => Self::Primitive(Primitive::LongDouble),
@@ -329,6 +420,7 @@ impl Helper<'_> {
             Pointer(t) => Self::Indirection(IndirectionKind::Pointer, t, level.pointer()),
             Atomic(t) => Self::Indirection(IndirectionKind::Atomic, t, level.atomic()),
             Array(len, item) => Self::Array(*len, item, level.array()),
+            Vector(size, alignment, item) => Self::Vector(*size, *alignment, item, level.vector()),
             Struct(name, fields) => {
                 if !verify_name(name) {
                     panic!("Struct name was not a valid identifier");
@@ -369,6 +461,8 @@ impl<'a> Helper<'a, EncodingBox> {
             UInt => Self::Primitive(Primitive::UInt),
             ULong => Self::Primitive(Primitive::ULong),
             ULongLong => Self::Primitive(Primitive::ULongLong),
+            Int128 => Self::Primitive(Primitive::Int128),
+            UInt128 => Self::Primitive(Primitive::UInt128),
             Float => Self::Primitive(Primitive::Float),
             Double => Self::Primitive(Primitive::Double),
             LongDouble => Self::Primitive(Primitive::LongDouble),
@@ -378,8 +472,10 @@ impl<'a> Helper<'a, EncodingBox> {
             Bool => Self::Primitive(Primitive::Bool),
             Void => Self::Primitive(Primitive::Void),
             String => Self::Primitive(Primitive::String),
-            Object => Self::Primitive(Primitive::Object),
-            Block => Self::Primitive(Primitive::Block),
+            Object(None) => Self::Primitive(Primitive::Object),
+            Object(Some(name)) => Self::NamedObject(name),
+            Block(None) => Self::Primitive(Primitive::Block),
+            Block(Some(signature)) => Self::BlockSignature(signature),
             Class => Self::Primitive(Primitive::Class),
             Sel => Self::Primitive(Primitive::Sel),
             Unknown => Self::Primitive(Primitive::Unknown),
@@ -387,6 +483,7 @@ impl<'a> Helper<'a, EncodingBox> {
             Pointer(t) => Self::Indirection(IndirectionKind::Pointer, t, level.pointer()),
             Atomic(t) => Self::Indirection(IndirectionKind::Atomic, t, level.atomic()),
             Array(len, item) => Self::Array(*len, item, level.array()),
+            Vector(size, alignment, item) => Self::Vector(*size, *alignment, item, level.vector()),
             Struct(name, fields) => {
                 if !verify_name(name) {
                     panic!("Struct name was not a valid identifier");
@@ -60,15 +60,25 @@ extern crate std;
 #[cfg(any(feature = "alloc", test))]
 extern crate alloc;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod diff;
 mod encoding;
 mod encoding_box;
 mod helper;
+mod method;
 mod parse;
+mod profile;
+mod qualifiers;
 
-// Will be used at some point when generic constants are available
-#[allow(dead_code)]
 mod static_str;
+#[cfg(feature = "serde")]
+pub mod structured;
 
+pub use self::diff::{EncodingDiff, PathSegment};
 pub use self::encoding::Encoding;
 pub use self::encoding_box::EncodingBox;
+pub use self::method::{MethodArgument, MethodEncoding};
 pub use self::parse::ParseError;
+pub use self::profile::EncodingProfile;
+pub use self::qualifiers::Qualifiers;
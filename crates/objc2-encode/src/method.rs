@@ -0,0 +1,420 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+use core::str::FromStr;
+
+use crate::parse::{ParseError, Parser};
+use crate::static_str::{static_method_str_array, static_method_str_len};
+use crate::{Encoding, EncodingBox, Qualifiers};
+
+/// The return type or an argument in a [`MethodEncoding`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MethodArgument {
+    /// The type qualifiers, e.g. `oneway` or `out`.
+    pub qualifiers: Qualifiers,
+    /// The encoding of the type.
+    pub encoding: EncodingBox,
+    /// The stack offset of the argument, if present.
+    ///
+    /// For the return type, this is the total size of the arguments.
+    pub offset: Option<isize>,
+}
+
+impl MethodArgument {
+    fn new(encoding: EncodingBox, offset: usize) -> Self {
+        Self {
+            qualifiers: Qualifiers::empty(),
+            encoding,
+            offset: Some(offset as isize),
+        }
+    }
+}
+
+/// An argument without qualifiers or stack offset, e.g. for use in a block
+/// signature.
+impl From<EncodingBox> for MethodArgument {
+    fn from(encoding: EncodingBox) -> Self {
+        Self {
+            qualifiers: Qualifiers::empty(),
+            encoding,
+            offset: None,
+        }
+    }
+}
+
+/// Same formatting as in the method encoding, i.e. the qualifiers, the
+/// encoding and then the offset.
+impl fmt::Display for MethodArgument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.qualifiers, self.encoding)?;
+        if let Some(offset) = self.offset {
+            write!(f, "{offset}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The type-encoding of an Objective-C method.
+///
+/// This is the concatenation of the encodings of the return type, the
+/// receiver (`self`), the selector (`_cmd`) and then the remaining
+/// arguments, as returned by `method_getTypeEncoding` and used in
+/// `class_addMethod` and `protocol_addMethodDescription`.
+///
+/// Each encoding may be preceded by [`Qualifiers`] and followed by the stack
+/// offset of the argument, e.g. `Vv24@0:8o^@16` for a method `- (oneway
+/// void)foo:(out id *)bar`.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_encode::{Encoding, EncodingBox, MethodEncoding, Qualifiers};
+///
+/// let method: MethodEncoding = "Vv24@0:8o^@16".parse()?;
+/// assert!(method.return_type.qualifiers.contains(Qualifiers::ONEWAY));
+/// assert!(Encoding::Void.equivalent_to_box(&method.return_type.encoding));
+/// assert_eq!(method.return_type.offset, Some(24));
+///
+/// assert_eq!(method.arguments.len(), 1);
+/// assert_eq!(method.arguments[0].qualifiers, Qualifiers::OUT);
+/// assert_eq!(method.arguments[0].offset, Some(16));
+///
+/// assert_eq!(method.to_string(), "Vv24@0:8o^@16");
+/// # Ok::<(), objc2_encode::ParseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MethodEncoding {
+    /// The return type.
+    pub return_type: MethodArgument,
+    /// The receiver, usually `@`.
+    pub receiver: MethodArgument,
+    /// The selector, usually `:`.
+    pub selector: MethodArgument,
+    /// The arguments after the receiver and selector.
+    pub arguments: Vec<MethodArgument>,
+}
+
+impl MethodEncoding {
+    /// Create the encoding of a method with the given return type and
+    /// arguments (excluding the receiver and selector).
+    ///
+    /// `arg_sizes` contains the size in bytes of each argument, and is used
+    /// to compute the stack offsets in the same way that `clang` does.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if `args` and `arg_sizes` have different lengths.
+    pub fn new(ret: &Encoding, args: &[Encoding], arg_sizes: &[usize]) -> Self {
+        assert_eq!(
+            args.len(),
+            arg_sizes.len(),
+            "must have the same number of arguments and argument sizes",
+        );
+        let ptr_size = mem::size_of::<*const ()>();
+
+        let receiver = MethodArgument::new(EncodingBox::Object(None), 0);
+        let selector = MethodArgument::new(EncodingBox::Sel, ptr_size);
+
+        let mut offset = 2 * ptr_size;
+        let arguments = args
+            .iter()
+            .zip(arg_sizes)
+            .map(|(encoding, size)| {
+                let argument = MethodArgument::new(encoding.into(), offset);
+                offset += argument_size(encoding, *size);
+                argument
+            })
+            .collect();
+
+        Self {
+            return_type: MethodArgument::new(ret.into(), offset),
+            receiver,
+            selector,
+            arguments,
+        }
+    }
+
+    /// The length of the string representation of the method encoding
+    /// created by [`MethodEncoding::new`].
+    ///
+    /// Used together with [`MethodEncoding::__static_str_array`] to build
+    /// method encoding strings at compile-time, e.g. when declaring classes
+    /// in `objc2`.
+    #[doc(hidden)]
+    pub const fn __static_str_len(ret: &Encoding, args: &[Encoding], arg_sizes: &[usize]) -> usize {
+        static_method_str_len(ret, args, arg_sizes)
+    }
+
+    /// The string representation of the method encoding created by
+    /// [`MethodEncoding::new`], in an array of at least length
+    /// [`MethodEncoding::__static_str_len`].
+    #[doc(hidden)]
+    pub const fn __static_str_array<const LEN: usize>(
+        ret: &Encoding,
+        args: &[Encoding],
+        arg_sizes: &[usize],
+    ) -> [u8; LEN] {
+        static_method_str_array(ret, args, arg_sizes)
+    }
+}
+
+/// The number of bytes that an argument with the given encoding and size
+/// takes up on the stack.
+pub(crate) const fn argument_size(encoding: &Encoding, size: usize) -> usize {
+    // Integral types are at least as large as an `int`, which is 32 bits on
+    // all supported platforms.
+    if is_integral(encoding) && size < mem::size_of::<i32>() {
+        mem::size_of::<i32>()
+    } else {
+        size
+    }
+}
+
+const fn is_integral(encoding: &Encoding) -> bool {
+    matches!(
+        encoding,
+        Encoding::Char
+            | Encoding::Short
+            | Encoding::Int
+            | Encoding::Long
+            | Encoding::LongLong
+            | Encoding::UChar
+            | Encoding::UShort
+            | Encoding::UInt
+            | Encoding::ULong
+            | Encoding::ULongLong
+            | Encoding::Bool
+    )
+}
+
+impl fmt::Display for MethodEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.return_type, self.receiver, self.selector)?;
+        for argument in &self.arguments {
+            write!(f, "{argument}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for MethodEncoding {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        parser
+            .parse_method()
+            .map_err(|err| ParseError::new(parser, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn argument(encoding: EncodingBox, offset: Option<isize>) -> MethodArgument {
+        MethodArgument {
+            qualifiers: Qualifiers::empty(),
+            encoding,
+            offset,
+        }
+    }
+
+    #[track_caller]
+    fn assert_method(s: &str, expected: &[(EncodingBox, Option<isize>)]) {
+        let method = MethodEncoding::from_str(s).unwrap_or_else(|e| panic!("{}", e));
+        let actual: Vec<_> = [method.return_type, method.receiver, method.selector]
+            .into_iter()
+            .chain(method.arguments)
+            .map(|argument| (argument.encoding, argument.offset))
+            .collect();
+        assert_eq!(&actual, expected);
+    }
+
+    #[test]
+    fn parse_bitfield() {
+        assert_method(
+            "@48@0:8Ad16r^*24{bitfield=b64b1}32i48",
+            &[
+                (EncodingBox::Object(None), Some(48)),
+                (EncodingBox::Object(None), Some(0)),
+                (EncodingBox::Sel, Some(8)),
+                (EncodingBox::Atomic(Box::new(EncodingBox::Double)), Some(16)),
+                (
+                    EncodingBox::Pointer(Box::new(EncodingBox::String)),
+                    Some(24),
+                ),
+                (
+                    EncodingBox::Struct(
+                        "bitfield".into(),
+                        Some(vec![
+                            EncodingBox::BitField(64, None),
+                            EncodingBox::BitField(1, None),
+                        ]),
+                    ),
+                    Some(32),
+                ),
+                (EncodingBox::Int, Some(48)),
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_complex() {
+        assert_method(
+            "jf16@0:8",
+            &[
+                (EncodingBox::FloatComplex, Some(16)),
+                (EncodingBox::Object(None), Some(0)),
+                (EncodingBox::Sel, Some(8)),
+            ],
+        );
+        assert_method(
+            "jf@:",
+            &[
+                (EncodingBox::FloatComplex, None),
+                (EncodingBox::Object(None), None),
+                (EncodingBox::Sel, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_extended() {
+        assert_method(
+            "v32@0:8@\"NSString\"16@?<v@?@\"NSError\">24",
+            &[
+                (EncodingBox::Void, Some(32)),
+                (EncodingBox::Object(None), Some(0)),
+                (EncodingBox::Sel, Some(8)),
+                (EncodingBox::Object(Some("NSString".into())), Some(16)),
+                (
+                    EncodingBox::Block(Some(vec![
+                        EncodingBox::Void.into(),
+                        EncodingBox::Block(None).into(),
+                        EncodingBox::Object(Some("NSError".into())).into(),
+                    ])),
+                    Some(24),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_offsets() {
+        assert_method(
+            "v-4@-1:+1i1234567890",
+            &[
+                (EncodingBox::Void, Some(-4)),
+                (EncodingBox::Object(None), Some(-1)),
+                // GNU runtime's register parameter hint
+                (EncodingBox::Sel, Some(1)),
+                (EncodingBox::Int, Some(1234567890)),
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_qualifiers() {
+        let method = MethodEncoding::from_str("Vv32@0:8r*16no^@24").unwrap();
+        let expected = MethodEncoding {
+            return_type: MethodArgument {
+                qualifiers: Qualifiers::ONEWAY,
+                encoding: EncodingBox::Void,
+                offset: Some(32),
+            },
+            receiver: argument(EncodingBox::Object(None), Some(0)),
+            selector: argument(EncodingBox::Sel, Some(8)),
+            arguments: vec![
+                MethodArgument {
+                    qualifiers: Qualifiers::CONST,
+                    encoding: EncodingBox::String,
+                    offset: Some(16),
+                },
+                MethodArgument {
+                    qualifiers: Qualifiers::IN | Qualifiers::OUT,
+                    encoding: EncodingBox::Pointer(Box::new(EncodingBox::Object(None))),
+                    offset: Some(24),
+                },
+            ],
+        };
+        assert_eq!(method, expected);
+        assert_eq!(method.to_string(), "Vv32@0:8r*16no^@24");
+    }
+
+    #[test]
+    fn parse_errors() {
+        #[track_caller]
+        fn assert_err(s: &str, expected: &str) {
+            let err = MethodEncoding::from_str(s).unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+
+        assert_err(
+            "",
+            "failed parsing encoding: method encoding must contain the return type at byte-index 0 in \"\"",
+        );
+        assert_err(
+            "v24",
+            "failed parsing encoding: method encoding must contain the receiver at byte-index 3 in \"v24\"",
+        );
+        assert_err(
+            "v@",
+            "failed parsing encoding: method encoding must contain the selector at byte-index 2 in \"v@\"",
+        );
+        assert_err(
+            "v@:-i",
+            "failed parsing encoding: expected integer at byte-index 4 in \"v@:-i\"",
+        );
+        assert_err(
+            "v@:i99999999999999999999",
+            "failed parsing encoding: integer too large at byte-index 24 in \"v@:i99999999999999999999\"",
+        );
+    }
+
+    #[test]
+    fn new() {
+        let ptr_size = mem::size_of::<*const ()>();
+        let method = MethodEncoding::new(&Encoding::Void, &[], &[]);
+        assert_eq!(
+            method.to_string(),
+            format!("v{}@0:{ptr_size}", 2 * ptr_size),
+        );
+
+        let method = MethodEncoding::new(
+            &Encoding::Pointer(&Encoding::Object),
+            &[
+                Encoding::Char,
+                Encoding::Double,
+                Encoding::Struct("S", &[Encoding::Short, Encoding::Short]),
+            ],
+            &[1, 8, 4],
+        );
+        let args = 2 * ptr_size;
+        assert_eq!(
+            method.to_string(),
+            format!(
+                "^@{}@0:{ptr_size}c{args}d{}{{S=ss}}{}",
+                args + 16,
+                args + 4,
+                args + 12,
+            ),
+        );
+        assert_eq!(
+            MethodEncoding::from_str(&method.to_string()).unwrap(),
+            method
+        );
+    }
+
+    #[test]
+    #[should_panic = "must have the same number of arguments and argument sizes"]
+    fn new_mismatched_sizes() {
+        let _ = MethodEncoding::new(&Encoding::Void, &[Encoding::Int], &[]);
+    }
+}
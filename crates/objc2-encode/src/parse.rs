@@ -5,8 +5,8 @@ use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
-use crate::helper::{ContainerKind, Helper, NestingLevel};
-use crate::{Encoding, EncodingBox};
+use crate::helper::{ContainerKind, Helper, NestingLevel, Primitive};
+use crate::{Encoding, EncodingBox, MethodArgument, MethodEncoding, Qualifiers};
 
 /// Check whether a struct or union name is a valid identifier
 pub(crate) const fn verify_name(name: &str) -> bool {
@@ -31,6 +31,26 @@ pub(crate) const fn verify_name(name: &str) -> bool {
     true
 }
 
+/// Whether the name can be used in an extended object encoding, i.e. it
+/// doesn't contain `"`, and any protocols after the class name are each
+/// enclosed in `<` and `>`, like in `NSObject<NSCopying><NSCoding>`.
+pub(crate) fn verify_object_name(name: &str) -> bool {
+    if name.contains('"') {
+        return false;
+    }
+    let (class, mut protocols) = name.split_at(name.find('<').unwrap_or(name.len()));
+    if class.contains('>') {
+        return false;
+    }
+    while let Some(rest) = protocols.strip_prefix('<') {
+        match rest.find('>') {
+            Some(end) if end != 0 && !rest[..end].contains('<') => protocols = &rest[end + 1..],
+            _ => return false,
+        }
+    }
+    protocols.is_empty()
+}
+
 /// The error that was encountered while parsing an encoding string.
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct ParseError {
@@ -70,8 +90,15 @@ pub(crate) enum ErrorKind {
     ExpectedInteger,
     IntegerTooLarge,
     WrongEndArray,
+    ExpectedVectorComma,
+    WrongEndVector,
     WrongEndContainer(ContainerKind),
     InvalidIdentifier(ContainerKind),
+    WrongEndObjectName,
+    WrongEndBlockSignature,
+    MissingMethodReturn,
+    MissingMethodReceiver,
+    MissingMethodSelector,
     NotAllConsumed,
 }
 
@@ -88,12 +115,29 @@ impl fmt::Display for ErrorKind {
             Self::ExpectedInteger => write!(f, "expected integer"),
             Self::IntegerTooLarge => write!(f, "integer too large"),
             Self::WrongEndArray => write!(f, "expected array to be closed"),
+            Self::ExpectedVectorComma => {
+                write!(f, "expected comma between vector size and alignment")
+            }
+            Self::WrongEndVector => write!(f, "expected vector to be closed"),
             Self::WrongEndContainer(kind) => {
                 write!(f, "expected {kind} to be closed")
             }
             Self::InvalidIdentifier(kind) => {
                 write!(f, "got invalid identifier in {kind}")
             }
+            Self::WrongEndObjectName => write!(f, "expected object name to be closed"),
+            Self::WrongEndBlockSignature => {
+                write!(f, "expected block signature to be closed")
+            }
+            Self::MissingMethodReturn => {
+                write!(f, "method encoding must contain the return type")
+            }
+            Self::MissingMethodReceiver => {
+                write!(f, "method encoding must contain the receiver")
+            }
+            Self::MissingMethodSelector => {
+                write!(f, "method encoding must contain the selector")
+            }
             Self::NotAllConsumed => {
                 write!(f, "remaining contents after parsing")
             }
@@ -166,21 +210,20 @@ impl<'a> Parser<'a> {
 }
 
 impl Parser<'_> {
-    /// Strip leading qualifiers, if any.
-    pub(crate) fn strip_leading_qualifiers(&mut self) {
-        // TODO: Add API for accessing and outputting qualifiers.
-        const QUALIFIERS: &[u8] = &[
-            b'r', // const
-            b'n', // in
-            b'N', // inout
-            b'o', // out
-            b'O', // bycopy
-            b'R', // byref
-            b'V', // oneway
-        ];
+    /// Parse leading qualifiers, if any.
+    pub(crate) fn parse_qualifiers(&mut self) -> Qualifiers {
         // TODO: b'|', // GCINVISIBLE
+        let mut qualifiers = Qualifiers::empty();
+        while let Some(qualifier) = self.try_peek().and_then(Qualifiers::from_byte) {
+            qualifiers |= qualifier;
+            self.advance();
+        }
+        qualifiers
+    }
 
-        self.consume_while(|b| QUALIFIERS.contains(&b));
+    /// Strip leading qualifiers, if any.
+    pub(crate) fn strip_leading_qualifiers(&mut self) {
+        let _ = self.parse_qualifiers();
     }
 
     /// Chomp until we hit a non-digit.
@@ -252,7 +295,21 @@ impl Parser<'_> {
     pub(crate) fn expect_encoding(&mut self, enc: &Encoding, level: NestingLevel) -> Option<()> {
         let helper = Helper::new(enc, level);
         match helper {
-            Helper::Primitive(primitive) => self.expect_str(primitive.to_str()),
+            Helper::Primitive(primitive) => {
+                self.expect_str(primitive.to_str())?;
+                // Allow the string to contain extended encodings, since
+                // these are not considered when checking equivalence.
+                match primitive {
+                    Primitive::Object => {
+                        self.try_parse_object_name().ok()?;
+                    }
+                    Primitive::Block => {
+                        self.try_parse_block_signature().ok()?;
+                    }
+                    _ => {}
+                }
+                Some(())
+            }
             Helper::BitField(size, Some((offset, t)), level) => {
                 self.expect_byte(b'b')?;
                 self.expect_u64(*offset)?;
@@ -273,6 +330,14 @@ impl Parser<'_> {
                 self.expect_encoding(item, level)?;
                 self.expect_byte(b']')
             }
+            Helper::Vector(size, alignment, item, level) => {
+                self.expect_str("![")?;
+                self.expect_u64(size)?;
+                self.expect_byte(b',')?;
+                self.expect_u64(alignment)?;
+                self.expect_encoding(item, level)?;
+                self.expect_byte(b']')
+            }
             Helper::Container(kind, name, items, level) => {
                 self.expect_byte(kind.start_byte())?;
                 self.expect_str(name)?;
@@ -284,6 +349,9 @@ impl Parser<'_> {
                 }
                 self.expect_byte(kind.end_byte())
             }
+            Helper::NamedObject(_) | Helper::BlockSignature(_) => {
+                unreachable!("extended encodings are only created by `EncodingBox`")
+            }
         }
     }
 }
@@ -356,6 +424,8 @@ impl Parser<'_> {
             b'I' => EncodingBox::UInt,
             b'L' => EncodingBox::ULong,
             b'Q' => EncodingBox::ULongLong,
+            b't' => EncodingBox::Int128,
+            b'T' => EncodingBox::UInt128,
             b'f' => EncodingBox::Float,
             b'd' => EncodingBox::Double,
             b'D' => EncodingBox::LongDouble,
@@ -376,9 +446,9 @@ impl Parser<'_> {
                 // Special handling for blocks
                 Some(b'?') => {
                     self.advance();
-                    EncodingBox::Block
+                    EncodingBox::Block(self.try_parse_block_signature()?)
                 }
-                _ => EncodingBox::Object,
+                _ => EncodingBox::Object(self.try_parse_object_name()?.map(ToString::to_string)),
             },
             b'#' => EncodingBox::Class,
             b':' => EncodingBox::Sel,
@@ -404,6 +474,16 @@ impl Parser<'_> {
                 self.expect_byte(b']').ok_or(ErrorKind::WrongEndArray)?;
                 EncodingBox::Array(len, Box::new(item))
             }
+            b'!' => {
+                self.expect_byte(b'[').ok_or(ErrorKind::Unknown(b'!'))?;
+                let size = self.parse_u64()?;
+                self.expect_byte(b',')
+                    .ok_or(ErrorKind::ExpectedVectorComma)?;
+                let alignment = self.parse_u64()?;
+                let item = self.parse_encoding()?;
+                self.expect_byte(b']').ok_or(ErrorKind::WrongEndVector)?;
+                EncodingBox::Vector(size, alignment, Box::new(item))
+            }
             b'{' => {
                 let kind = ContainerKind::Struct;
                 let (name, items) = self.parse_container(kind)?;
@@ -418,6 +498,89 @@ impl Parser<'_> {
         })
     }
 
+    /// Parse the class or protocol name in an extended object encoding like
+    /// `@"NSString"`, if present.
+    fn try_parse_object_name(&mut self) -> Result<Option<&str>> {
+        if self.expect_byte(b'"').is_none() {
+            return Ok(None);
+        }
+        let old_split_point = self.split_point;
+        self.consume_while(|b| b != b'"');
+        let name = &self.data[old_split_point..self.split_point];
+        self.expect_byte(b'"')
+            .ok_or(ErrorKind::WrongEndObjectName)?;
+        Ok(Some(name))
+    }
+
+    /// Parse the signature in an extended block encoding like `@?<v@?i>`,
+    /// if present.
+    fn try_parse_block_signature(&mut self) -> Result<Option<Vec<MethodArgument>>> {
+        if self.expect_byte(b'<').is_none() {
+            return Ok(None);
+        }
+        let mut items = Vec::new();
+        loop {
+            let b = self.try_peek().ok_or(ErrorKind::WrongEndBlockSignature)?;
+            if b == b'>' {
+                self.advance();
+                break;
+            }
+            items.push(self.parse_method_argument(ErrorKind::WrongEndBlockSignature)?);
+        }
+        Ok(Some(items))
+    }
+
+    /// Parse the stack offset that follows an encoding in a method
+    /// encoding, if present.
+    fn try_parse_offset(&mut self) -> Result<Option<isize>> {
+        let old_split_point = self.split_point;
+        match self.try_peek() {
+            // `+` is the GNU runtime's register parameter hint
+            Some(b'-' | b'+') => self.advance(),
+            Some(b) if b.is_ascii_digit() => {}
+            _ => return Ok(None),
+        }
+        self.chomp_digits()?;
+        self.data[old_split_point..self.split_point]
+            .parse()
+            .map(Some)
+            .map_err(|_| ErrorKind::IntegerTooLarge)
+    }
+
+    fn parse_method_argument(&mut self, missing: ErrorKind) -> Result<MethodArgument> {
+        let qualifiers = self.parse_qualifiers();
+        if self.is_empty() {
+            return Err(missing);
+        }
+        let encoding = self.parse_encoding()?;
+        let offset = self.try_parse_offset()?;
+        Ok(MethodArgument {
+            qualifiers,
+            encoding,
+            offset,
+        })
+    }
+
+    pub(crate) fn parse_method(&mut self) -> Result<MethodEncoding> {
+        // See also the following other approaches:
+        // objrs: https://gitlab.com/objrs/objrs/-/blob/b4f6598696b3fa622e6fddce7aff281770b0a8c2/src/test.rs
+        // libobjc2: https://github.com/gnustep/libobjc2/blob/v2.1/encoding2.c
+        // objc4: https://github.com/apple-oss-distributions/objc4/blob/objc4-841.13/runtime/objc-typeencoding.mm
+        let return_type = self.parse_method_argument(ErrorKind::MissingMethodReturn)?;
+        let receiver = self.parse_method_argument(ErrorKind::MissingMethodReceiver)?;
+        let selector = self.parse_method_argument(ErrorKind::MissingMethodSelector)?;
+        let mut arguments = Vec::new();
+        while !self.is_empty() {
+            arguments.push(self.parse_method_argument(ErrorKind::UnexpectedEnd)?);
+        }
+        Ok(MethodEncoding {
+            return_type,
+            receiver,
+            selector,
+            arguments,
+        })
+    }
+
     fn try_parse_bitfield_gnustep(&mut self) -> Result<Option<(u8, EncodingBox)>> {
         if let Some((b1, b2)) = self.try_peek2() {
             // Try to parse the encoding.
@@ -434,6 +597,8 @@ impl Parser<'_> {
                 b'I' => EncodingBox::UInt,
                 b'L' => EncodingBox::ULong,
                 b'Q' => EncodingBox::ULongLong,
+                b't' => EncodingBox::Int128,
+                b'T' => EncodingBox::UInt128,
                 b'B' => EncodingBox::Bool,
                 _ => return Ok(None),
             };
@@ -523,5 +688,35 @@ mod tests {
             )),
         );
         assert_bitfield("b2000C257", Err(ErrorKind::IntegerTooLarge));
+        assert_bitfield(
+            "b0T128",
+            Ok(EncodingBox::BitField(
+                128,
+                Some(Box::new((0, EncodingBox::UInt128))),
+            )),
+        );
+    }
+
+    #[test]
+    fn parse_vector() {
+        #[track_caller]
+        fn assert_vector(enc: &str, expected: Result<EncodingBox>) {
+            let mut parser = Parser::new(enc);
+            assert_eq!(parser.parse_encoding(), expected);
+        }
+
+        assert_vector(
+            "![16,4f]",
+            Ok(EncodingBox::Vector(16, 4, Box::new(EncodingBox::Float))),
+        );
+        assert_vector(
+            "![32,16t]",
+            Ok(EncodingBox::Vector(32, 16, Box::new(EncodingBox::Int128))),
+        );
+        assert_vector("!16,4f]", Err(ErrorKind::Unknown(b'!')));
+        assert_vector("![16f]", Err(ErrorKind::ExpectedVectorComma));
+        assert_vector("![16,f]", Err(ErrorKind::ExpectedInteger));
+        assert_vector("![16,4f", Err(ErrorKind::WrongEndVector));
+        assert_vector("![16,4]", Err(ErrorKind::Unknown(b']')));
     }
 }
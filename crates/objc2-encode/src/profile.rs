@@ -0,0 +1,248 @@
+use core::fmt;
+
+use crate::helper::{EncodingType, Helper, NestingLevel, Primitive};
+use crate::{Encoding, EncodingBox};
+
+/// Whether `long` is 64 bits wide on the current target.
+const LONG_IS_64_BIT: bool = cfg!(not(any(target_pointer_width = "32", windows)));
+
+/// Whether GNUstep's `BOOL` is an `int` on the current target.
+const GNUSTEP_BOOL_IS_INT: bool = cfg!(all(
+    windows,
+    not(all(target_pointer_width = "64", target_env = "gnu"))
+));
+
+/// The Objective-C runtime (and the compiler usually used with it) that an
+/// encoding is used with.
+///
+/// The encodings that the runtimes report differ slightly, which the plain
+/// comparison functions like [`Encoding::equivalent_to`] do not account for.
+/// Specifically:
+/// - `BOOL` is a `signed char` on some targets and a `_Bool` on others when
+///   using Apple's runtime, is an `unsigned char` on GNUstep (an `int` on
+///   Windows), and a `signed char` on ObjFW. So [`Encoding::Bool`] is
+///   considered equivalent to [`Encoding::Char`], [`Encoding::UChar`] or
+///   [`Encoding::Int`] respectively.
+/// - GCC encodes `long` as `l` regardless of its size, while `clang` encodes
+///   it as `q` when it is 64 bits wide. So on targets where `long` is 64
+///   bits, [`Encoding::Long`] and [`Encoding::LongLong`] (and their unsigned
+///   counterparts) are considered equivalent.
+/// - The GNU runtimes encode bitfields with their offset and type, while
+///   Apple's runtime only encodes the size. So on GNUstep and ObjFW, a
+///   bitfield without this information is considered equivalent to a
+///   bitfield with it, as long as the sizes match.
+///
+/// The profile can also be used to format an encoding in the way that the
+/// runtime's compiler would.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_encode::{Encoding, EncodingProfile};
+///
+/// // `- (BOOL)isEqual:(id)object` on GNUstep
+/// assert!(!Encoding::Bool.equivalent_to_str("C"));
+/// assert!(Encoding::Bool.equivalent_to_str_in("C", EncodingProfile::GNUstep));
+/// assert!(!Encoding::Bool.equivalent_to_str_in("C", EncodingProfile::Apple));
+///
+/// let bitfield = Encoding::BitField(4, Some(&(0, Encoding::Int)));
+/// assert_eq!(bitfield.display_in(EncodingProfile::Apple).to_string(), "b4");
+/// assert_eq!(bitfield.display_in(EncodingProfile::GNUstep).to_string(), "b0i4");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EncodingProfile {
+    /// Apple's `objc4` runtime, compiled with `clang`.
+    Apple,
+    /// GNUstep's `libobjc2` runtime, compiled with either `clang` or GCC.
+    GNUstep,
+    /// The ObjFW runtime, compiled with either `clang` or GCC.
+    ObjFW,
+}
+
+impl EncodingProfile {
+    /// The encoding that `BOOL` may be reported as, apart from
+    /// [`Encoding::Bool`].
+    const fn bool_primitive(self) -> Primitive {
+        match self {
+            Self::Apple | Self::ObjFW => Primitive::Char,
+            Self::GNUstep if GNUSTEP_BOOL_IS_INT => Primitive::Int,
+            Self::GNUstep => Primitive::UChar,
+        }
+    }
+
+    /// Whether the runtime may have been compiled with GCC.
+    const fn gcc_compatible(self) -> bool {
+        match self {
+            Self::Apple => false,
+            Self::GNUstep | Self::ObjFW => true,
+        }
+    }
+
+    /// Whether the runtime encodes the offset and type of bitfields.
+    pub(crate) const fn gnu_bitfields(self) -> bool {
+        self.gcc_compatible()
+    }
+
+    pub(crate) fn primitives_equivalent(self, p1: Primitive, p2: Primitive) -> bool {
+        let normalize = |p| match p {
+            Primitive::Bool => self.bool_primitive(),
+            p => long_as_long_long(p),
+        };
+        normalize(p1) == normalize(p2)
+    }
+
+    /// The primitive as the runtime's compiler would output it.
+    pub(crate) const fn primitive_str(self, p: Primitive) -> &'static str {
+        if self.gcc_compatible() {
+            p.to_str()
+        } else {
+            long_as_long_long(p).to_str()
+        }
+    }
+}
+
+/// `clang` encodes `long` as `long long` when they have the same size.
+const fn long_as_long_long(p: Primitive) -> Primitive {
+    match p {
+        Primitive::Long if LONG_IS_64_BIT => Primitive::LongLong,
+        Primitive::ULong if LONG_IS_64_BIT => Primitive::ULongLong,
+        p => p,
+    }
+}
+
+/// Helper for formatting encodings according to an [`EncodingProfile`].
+pub(crate) struct ProfileDisplay<'a, E> {
+    pub(crate) helper: Helper<'a, E>,
+    pub(crate) profile: EncodingProfile,
+}
+
+impl<'a> ProfileDisplay<'a, Encoding> {
+    pub(crate) const fn new(encoding: &'a Encoding, profile: EncodingProfile) -> Self {
+        Self {
+            helper: Helper::new(encoding, NestingLevel::new()),
+            profile,
+        }
+    }
+}
+
+impl<'a> ProfileDisplay<'a, EncodingBox> {
+    pub(crate) fn from_box(encoding: &'a EncodingBox, profile: EncodingProfile) -> Self {
+        Self {
+            helper: Helper::from_box(encoding, NestingLevel::new()),
+            profile,
+        }
+    }
+}
+
+impl<E: EncodingType> fmt::Display for ProfileDisplay<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.helper.fmt_in(f, Some(self.profile))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use core::str::FromStr;
+
+    use EncodingProfile::*;
+
+    const ALL: [EncodingProfile; 3] = [Apple, GNUstep, ObjFW];
+
+    #[test]
+    fn bool() {
+        assert!(Encoding::Bool.equivalent_to_str_in("c", Apple));
+        assert!(!Encoding::Bool.equivalent_to_str_in("C", Apple));
+        assert_eq!(
+            Encoding::Bool.equivalent_to_str_in("C", GNUstep),
+            !GNUSTEP_BOOL_IS_INT
+        );
+        assert_eq!(
+            Encoding::Bool.equivalent_to_str_in("i", GNUstep),
+            GNUSTEP_BOOL_IS_INT
+        );
+        assert!(!Encoding::Bool.equivalent_to_str_in("c", GNUstep));
+        assert!(Encoding::Bool.equivalent_to_str_in("c", ObjFW));
+        assert!(Encoding::Char.equivalent_to_in(&Encoding::Bool, Apple));
+
+        // Behind pointers and in structs too
+        let enc = Encoding::Struct("S", &[Encoding::Pointer(&Encoding::Bool)]);
+        let expected = if GNUSTEP_BOOL_IS_INT {
+            "{S=^i}"
+        } else {
+            "{S=^C}"
+        };
+        assert!(enc.equivalent_to_str_in(expected, GNUstep));
+        assert!(!enc.equivalent_to_str_in("{S=^c}", GNUstep));
+
+        for profile in ALL {
+            assert!(Encoding::Bool.equivalent_to_str_in("B", profile));
+            assert!(!Encoding::Bool.equivalent_to_str_in("s", profile));
+            assert_eq!(Encoding::Bool.display_in(profile).to_string(), "B");
+        }
+    }
+
+    #[test]
+    fn long() {
+        for profile in ALL {
+            assert!(Encoding::Long.equivalent_to_str_in("l", profile));
+            assert_eq!(
+                Encoding::Long.equivalent_to_str_in("q", profile),
+                LONG_IS_64_BIT
+            );
+            assert_eq!(
+                Encoding::ULongLong.equivalent_to_str_in("L", profile),
+                LONG_IS_64_BIT
+            );
+            assert!(!Encoding::Long.equivalent_to_str_in("Q", profile));
+
+            // What the profile formats `long` as is equivalent to it
+            let s = Encoding::Long.display_in(profile).to_string();
+            assert!(Encoding::Long.equivalent_to_str_in(&s, profile), "{s}");
+        }
+        assert_eq!(Encoding::Long.display_in(GNUstep).to_string(), "l");
+        assert_eq!(Encoding::Long.display_in(ObjFW).to_string(), "l");
+
+        let expected = if LONG_IS_64_BIT { "^q" } else { "^l" };
+        assert_eq!(
+            Encoding::Pointer(&Encoding::Long)
+                .display_in(Apple)
+                .to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn bitfield() {
+        let with_type = Encoding::BitField(8, Some(&(2, Encoding::UChar)));
+        let without_type = Encoding::BitField(8, None);
+
+        assert!(!with_type.equivalent_to_in(&without_type, Apple));
+        assert!(with_type.equivalent_to_in(&without_type, GNUstep));
+        assert!(without_type.equivalent_to_str_in("b2C8", ObjFW));
+        assert!(!without_type.equivalent_to_str_in("b2C7", ObjFW));
+
+        assert_eq!(with_type.display_in(Apple).to_string(), "b8");
+        assert_eq!(with_type.display_in(GNUstep).to_string(), "b2C8");
+        assert_eq!(without_type.display_in(GNUstep).to_string(), "b8");
+    }
+
+    #[test]
+    fn display_box() {
+        let enc = EncodingBox::from_str("{A=b0l1@\"NSString\"}").unwrap();
+        assert_eq!(enc.display_in(GNUstep).to_string(), "{A=b0l1@\"NSString\"}");
+        assert_eq!(enc.display_in(Apple).to_string(), "{A=b1@\"NSString\"}");
+    }
+
+    #[test]
+    fn invalid_str() {
+        for profile in ALL {
+            assert!(!Encoding::Int.equivalent_to_str_in("i!", profile));
+            assert!(!Encoding::Int.equivalent_to_str_in("", profile));
+            assert!(Encoding::Int.equivalent_to_str_in("ri", profile));
+        }
+    }
+}
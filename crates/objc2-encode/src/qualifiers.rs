@@ -0,0 +1,186 @@
+use core::fmt;
+use core::ops::{BitOr, BitOrAssign};
+
+use crate::parse::Parser;
+
+/// The type qualifiers that can precede an encoding.
+///
+/// These are emitted by the compiler for `const` types, and for the
+/// distributed objects keywords `in`, `out`, `inout`, `bycopy`, `byref` and
+/// `oneway` on method return and argument types.
+///
+/// Qualifiers are ignored when checking equivalence of encodings.
+///
+/// The qualifiers are stored on each argument in a [`MethodEncoding`] and on
+/// each item in the signature of an [`EncodingBox::Block`]. The qualifiers at
+/// the start of an encoding are skipped when parsing an [`EncodingBox`], so
+/// parse them with [`from_start_of_str`] first if they're needed.
+/// Qualifiers in other places, like the `r` in `^r*`, are not supported, and
+/// make parsing fail.
+///
+/// [`MethodEncoding`]: crate::MethodEncoding
+/// [`EncodingBox::Block`]: crate::EncodingBox::Block
+/// [`EncodingBox`]: crate::EncodingBox
+/// [`from_start_of_str`]: Self::from_start_of_str
+///
+/// The [`Display`][`fmt::Display`] implementation outputs the qualifiers in
+/// the same order that `clang` does, so that they can be directly followed
+/// by an encoding.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_encode::{EncodingBox, Qualifiers};
+///
+/// let mut s = "Vv";
+/// let qualifiers = Qualifiers::from_start_of_str(&mut s);
+/// assert!(qualifiers.contains(Qualifiers::ONEWAY));
+/// assert_eq!(qualifiers.to_string(), "V");
+///
+/// let encoding = EncodingBox::from_start_of_str(&mut s)?;
+/// assert_eq!(encoding, EncodingBox::Void);
+/// # Ok::<(), objc2_encode::ParseError>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Qualifiers(u8);
+
+/// The qualifier codes, in the order they're output.
+const CODES: [(Qualifiers, u8); 7] = [
+    (Qualifiers::CONST, b'r'),
+    (Qualifiers::IN, b'n'),
+    (Qualifiers::INOUT, b'N'),
+    (Qualifiers::OUT, b'o'),
+    (Qualifiers::BYCOPY, b'O'),
+    (Qualifiers::BYREF, b'R'),
+    (Qualifiers::ONEWAY, b'V'),
+];
+
+impl Qualifiers {
+    /// The type is `const`. Corresponds to the `"r"` code.
+    pub const CONST: Self = Self(1 << 0);
+    /// The `in` qualifier. Corresponds to the `"n"` code.
+    pub const IN: Self = Self(1 << 1);
+    /// The `inout` qualifier. Corresponds to the `"N"` code.
+    pub const INOUT: Self = Self(1 << 2);
+    /// The `out` qualifier. Corresponds to the `"o"` code.
+    pub const OUT: Self = Self(1 << 3);
+    /// The `bycopy` qualifier. Corresponds to the `"O"` code.
+    pub const BYCOPY: Self = Self(1 << 4);
+    /// The `byref` qualifier. Corresponds to the `"R"` code.
+    pub const BYREF: Self = Self(1 << 5);
+    /// The `oneway` qualifier. Corresponds to the `"V"` code.
+    pub const ONEWAY: Self = Self(1 << 6);
+
+    /// No qualifiers.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Whether there are no qualifiers.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether all the qualifiers in `other` are also present in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The qualifiers present in either `self` or `other`.
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub(crate) fn from_byte(b: u8) -> Option<Self> {
+        CODES
+            .iter()
+            .find(|(_, code)| *code == b)
+            .map(|(qualifier, _)| *qualifier)
+    }
+
+    /// Parse and consume the qualifiers at the start of a string.
+    ///
+    /// This is usually followed by [`EncodingBox::from_start_of_str`] to
+    /// parse the encoding that the qualifiers apply to.
+    ///
+    /// [`EncodingBox::from_start_of_str`]: crate::EncodingBox::from_start_of_str
+    pub fn from_start_of_str(s: &mut &str) -> Self {
+        let mut parser = Parser::new(s);
+        let qualifiers = parser.parse_qualifiers();
+        *s = parser.remaining();
+        qualifiers
+    }
+}
+
+impl BitOr for Qualifiers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for Qualifiers {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = self.union(other);
+    }
+}
+
+impl fmt::Display for Qualifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (qualifier, code) in CODES {
+            if self.contains(qualifier) {
+                write!(f, "{}", code as char)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse_and_display() {
+        #[track_caller]
+        fn assert_qualifiers(mut s: &str, expected: Qualifiers, display: &str, rest: &str) {
+            let qualifiers = Qualifiers::from_start_of_str(&mut s);
+            assert_eq!(qualifiers, expected);
+            assert_eq!(qualifiers.to_string(), display);
+            assert_eq!(s, rest);
+        }
+
+        assert_qualifiers("", Qualifiers::empty(), "", "");
+        assert_qualifiers("i", Qualifiers::empty(), "", "i");
+        assert_qualifiers("r*", Qualifiers::CONST, "r", "*");
+        assert_qualifiers("Vv", Qualifiers::ONEWAY, "V", "v");
+        assert_qualifiers("ro^@", Qualifiers::CONST | Qualifiers::OUT, "ro", "^@");
+        // Output in the canonical order
+        assert_qualifiers(
+            "VRONonr@",
+            Qualifiers::CONST
+                | Qualifiers::IN
+                | Qualifiers::INOUT
+                | Qualifiers::OUT
+                | Qualifiers::BYCOPY
+                | Qualifiers::BYREF
+                | Qualifiers::ONEWAY,
+            "rnNoORV",
+            "@",
+        );
+    }
+
+    #[test]
+    fn contains() {
+        let qualifiers = Qualifiers::IN | Qualifiers::BYCOPY;
+        assert!(qualifiers.contains(Qualifiers::IN));
+        assert!(qualifiers.contains(Qualifiers::IN | Qualifiers::BYCOPY));
+        assert!(!qualifiers.contains(Qualifiers::OUT));
+        assert!(qualifiers.contains(Qualifiers::empty()));
+        assert!(!qualifiers.is_empty());
+        assert!(Qualifiers::default().is_empty());
+    }
+}
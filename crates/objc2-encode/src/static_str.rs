@@ -1,4 +1,7 @@
+use core::mem;
+
 use crate::helper::{Helper, NestingLevel};
+use crate::method::argument_size;
 
 use super::Encoding;
 
@@ -32,7 +35,6 @@ pub(crate) const fn static_int_str_array<const RES: usize>(mut n: u64) -> [u8; R
     while 0 < i {
         i -= 1;
         rev[rev_i] = res[i];
-        n /= 10;
         rev_i += 1;
     }
     rev
@@ -53,6 +55,13 @@ pub(crate) const fn static_encoding_str_len(encoding: &Encoding, level: NestingL
         Array(len, item, level) => {
             1 + static_int_str_len(len) + static_encoding_str_len(item, level) + 1
         }
+        Vector(size, alignment, item, level) => {
+            2 + static_int_str_len(size)
+                + 1
+                + static_int_str_len(alignment)
+                + static_encoding_str_len(item, level)
+                + 1
+        }
         Container(_, name, items, level) => {
             let mut res = 1 + name.len();
             if let Some(items) = items {
@@ -65,6 +74,8 @@ pub(crate) const fn static_encoding_str_len(encoding: &Encoding, level: NestingL
             }
             res + 1
         }
+        // Extended encodings are only created by `EncodingBox`
+        NamedObject(_) | BlockSignature(_) => unreachable!(),
     }
 }
 
@@ -170,6 +181,46 @@ pub(crate) const fn static_encoding_str_array<const LEN: usize>(
 
             res[res_i] = b']';
         }
+        Vector(size, alignment, item, level) => {
+            let mut res_i = 0;
+
+            res[res_i] = b'!';
+            res_i += 1;
+            res[res_i] = b'[';
+            res_i += 1;
+
+            let mut i = 0;
+            // We use 20 even though it creates an oversized array
+            let arr = static_int_str_array::<20>(size);
+            while i < static_int_str_len(size) {
+                res[res_i] = arr[i];
+                res_i += 1;
+                i += 1;
+            }
+
+            res[res_i] = b',';
+            res_i += 1;
+
+            let mut i = 0;
+            // We use 20 even though it creates an oversized array
+            let arr = static_int_str_array::<20>(alignment);
+            while i < static_int_str_len(alignment) {
+                res[res_i] = arr[i];
+                res_i += 1;
+                i += 1;
+            }
+
+            let mut i = 0;
+            // We use LEN even though it creates an oversized array
+            let arr = static_encoding_str_array::<LEN>(item, level);
+            while i < static_encoding_str_len(item, level) {
+                res[res_i] = arr[i];
+                res_i += 1;
+                i += 1;
+            }
+
+            res[res_i] = b']';
+        }
         Container(kind, name, items, level) => {
             let mut res_i = 0;
 
@@ -205,10 +256,115 @@ pub(crate) const fn static_encoding_str_array<const LEN: usize>(
 
             res[res_i] = kind.end_byte();
         }
+        NamedObject(_) | BlockSignature(_) => unreachable!(),
     };
     res
 }
 
+/// The length of the method encoding created by `MethodEncoding::new`.
+pub(crate) const fn static_method_str_len(
+    ret: &Encoding,
+    args: &[Encoding],
+    arg_sizes: &[usize],
+) -> usize {
+    assert!(
+        args.len() == arg_sizes.len(),
+        "must have the same number of arguments and argument sizes",
+    );
+    let level = NestingLevel::new();
+    let ptr_size = mem::size_of::<*const ()>();
+
+    // The receiver at offset 0 and the selector after it
+    let mut res = 1 + 1 + 1 + static_int_str_len(ptr_size as u64);
+
+    let mut offset = 2 * ptr_size;
+    let mut i = 0;
+    while i < args.len() {
+        res += static_encoding_str_len(&args[i], level) + static_int_str_len(offset as u64);
+        offset += argument_size(&args[i], arg_sizes[i]);
+        i += 1;
+    }
+
+    res + static_encoding_str_len(ret, level) + static_int_str_len(offset as u64)
+}
+
+/// The method encoding created by `MethodEncoding::new`, in an array of at
+/// least length `static_method_str_len`.
+pub(crate) const fn static_method_str_array<const LEN: usize>(
+    ret: &Encoding,
+    args: &[Encoding],
+    arg_sizes: &[usize],
+) -> [u8; LEN] {
+    assert!(
+        args.len() == arg_sizes.len(),
+        "must have the same number of arguments and argument sizes",
+    );
+    let level = NestingLevel::new();
+    let ptr_size = mem::size_of::<*const ()>();
+
+    let mut res: [u8; LEN] = [0; LEN];
+    let mut res_i = 0;
+
+    macro_rules! push {
+        ($arr:expr, $len:expr) => {{
+            let arr = $arr;
+            let len = $len;
+            let mut i = 0;
+            while i < len {
+                res[res_i] = arr[i];
+                res_i += 1;
+                i += 1;
+            }
+        }};
+    }
+
+    // The return type is followed by the total size of the arguments
+    let mut total = 2 * ptr_size;
+    let mut i = 0;
+    while i < args.len() {
+        total += argument_size(&args[i], arg_sizes[i]);
+        i += 1;
+    }
+    // We use LEN even though it creates an oversized array
+    push!(
+        static_encoding_str_array::<LEN>(ret, level),
+        static_encoding_str_len(ret, level)
+    );
+    // We use 20 even though it creates an oversized array
+    push!(
+        static_int_str_array::<20>(total as u64),
+        static_int_str_len(total as u64)
+    );
+
+    res[res_i] = b'@';
+    res_i += 1;
+    res[res_i] = b'0';
+    res_i += 1;
+    res[res_i] = b':';
+    res_i += 1;
+    push!(
+        static_int_str_array::<20>(ptr_size as u64),
+        static_int_str_len(ptr_size as u64)
+    );
+
+    let mut offset = 2 * ptr_size;
+    let mut i = 0;
+    while i < args.len() {
+        push!(
+            static_encoding_str_array::<LEN>(&args[i], level),
+            static_encoding_str_len(&args[i], level)
+        );
+        push!(
+            static_int_str_array::<20>(offset as u64),
+            static_int_str_len(offset as u64)
+        );
+        offset += argument_size(&args[i], arg_sizes[i]);
+        i += 1;
+    }
+
+    res
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,5 +393,34 @@ mod tests {
         assert_eq!(STR_1236018655, "1236018655");
     }
 
+    #[test]
+    fn test_const_method_str() {
+        use alloc::string::ToString;
+
+        use crate::MethodEncoding;
+
+        macro_rules! const_method_str {
+            ($ret:expr, $args:expr, $sizes:expr) => {{
+                const LEN: usize = static_method_str_len(&$ret, $args, $sizes);
+                const X: [u8; LEN] = static_method_str_array(&$ret, $args, $sizes);
+                let s = unsafe { core::str::from_utf8_unchecked(&X) };
+                assert_eq!(s, MethodEncoding::new(&$ret, $args, $sizes).to_string());
+            }};
+        }
+
+        const_method_str!(Encoding::Void, &[], &[]);
+        const_method_str!(Encoding::Char, &[Encoding::Bool], &[1]);
+        const_method_str!(
+            Encoding::Struct("CGPoint", &[Encoding::Double, Encoding::Double]),
+            &[Encoding::Object, Encoding::Short, Encoding::LongLong],
+            &[8, 2, 8]
+        );
+        const_method_str!(
+            Encoding::Pointer(&Encoding::Sel),
+            &[Encoding::Array(2, &Encoding::Int), Encoding::Float],
+            &[8, 4]
+        );
+    }
+
     // static encoding tests are in `encoding.rs`
 }
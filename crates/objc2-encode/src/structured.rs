@@ -0,0 +1,432 @@
+//! Structured (de)serialization of [`EncodingBox`].
+//!
+//! By default, [`EncodingBox`] is serialized as its canonical string, e.g.
+//! `"{CGPoint=dd}"`. This module can instead be used with
+//! `#[serde(with = "objc2_encode::structured")]` to serialize it as a tree,
+//! where each encoding is a map with a `"type"` key containing the name of
+//! the variant, along with the contents of the variant.
+//!
+//! The names of the variants are the same as in [`EncodingBox`], and their
+//! contents are named after what they contain, e.g. `"pointee"` for
+//! [`EncodingBox::Pointer`] and `"offset"` and `"bit_type"` for
+//! [`EncodingBox::BitField`]. Optional contents are omitted when they are
+//! not present.
+//!
+//! The items in a block signature are maps with the `"encoding"`, along with
+//! the `"qualifiers"` as a string like `"rn"` and the `"offset"`, if present.
+//!
+//!
+//! # Example
+//!
+//! ```
+//! use objc2_encode::EncodingBox;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Signature {
+//!     canonical: EncodingBox,
+//!     #[serde(with = "objc2_encode::structured")]
+//!     structured: EncodingBox,
+//! }
+//!
+//! let enc: EncodingBox = "{CGPoint=dd}".parse()?;
+//! let signature = Signature {
+//!     canonical: enc.clone(),
+//!     structured: enc,
+//! };
+//! assert_eq!(
+//!     serde_json::to_string(&signature).unwrap(),
+//!     concat!(
+//!         r#"{"canonical":"{CGPoint=dd}","#,
+//!         r#""structured":{"type":"Struct","name":"CGPoint","fields":[{"type":"Double"},{"type":"Double"}]}}"#,
+//!     ),
+//! );
+//! # Ok::<(), objc2_encode::ParseError>(())
+//! ```
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::parse::{verify_name, verify_object_name};
+use crate::{EncodingBox, MethodArgument, Qualifiers};
+
+/// Serialize an [`EncodingBox`] in the structured form.
+pub fn serialize<S: Serializer>(encoding: &EncodingBox, serializer: S) -> Result<S::Ok, S::Error> {
+    Structured::from(encoding).serialize(serializer)
+}
+
+/// Deserialize an [`EncodingBox`] from the structured form.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<EncodingBox, D::Error> {
+    Structured::deserialize(deserializer)?
+        .into_box()
+        .map_err(D::Error::custom)
+}
+
+/// Mirror of [`EncodingBox`] with named contents.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", deny_unknown_fields)]
+enum Structured {
+    Char,
+    Short,
+    Int,
+    Long,
+    LongLong,
+    UChar,
+    UShort,
+    UInt,
+    ULong,
+    ULongLong,
+    Int128,
+    UInt128,
+    Float,
+    Double,
+    LongDouble,
+    FloatComplex,
+    DoubleComplex,
+    LongDoubleComplex,
+    Bool,
+    Void,
+    String,
+    Object {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    Block {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<Vec<Argument>>,
+    },
+    Class,
+    Sel,
+    Unknown,
+    BitField {
+        size: u8,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        offset: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bit_type: Option<Box<Structured>>,
+    },
+    Pointer {
+        pointee: Box<Structured>,
+    },
+    Atomic {
+        value: Box<Structured>,
+    },
+    Array {
+        len: u64,
+        item: Box<Structured>,
+    },
+    Struct {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fields: Option<Vec<Structured>>,
+    },
+    Union {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        members: Option<Vec<Structured>>,
+    },
+    Vector {
+        size: u64,
+        alignment: u64,
+        item: Box<Structured>,
+    },
+}
+
+/// Mirror of [`MethodArgument`], for the items in a block signature.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Argument {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    qualifiers: String,
+    encoding: Structured,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    offset: Option<isize>,
+}
+
+impl From<&MethodArgument> for Argument {
+    fn from(argument: &MethodArgument) -> Self {
+        Self {
+            qualifiers: argument.qualifiers.to_string(),
+            encoding: Structured::from(&argument.encoding),
+            offset: argument.offset,
+        }
+    }
+}
+
+impl Argument {
+    fn into_argument(self) -> Result<MethodArgument, &'static str> {
+        let mut s = &*self.qualifiers;
+        let qualifiers = Qualifiers::from_start_of_str(&mut s);
+        if !s.is_empty() {
+            return Err("qualifiers must only contain qualifier codes");
+        }
+        Ok(MethodArgument {
+            qualifiers,
+            encoding: self.encoding.into_box()?,
+            offset: self.offset,
+        })
+    }
+}
+
+impl From<&EncodingBox> for Structured {
+    fn from(encoding: &EncodingBox) -> Self {
+        let boxed = |t: &EncodingBox| Box::new(Self::from(t));
+        let items = |items: &Option<Vec<EncodingBox>>| {
+            items
+                .as_ref()
+                .map(|items| items.iter().map(Self::from).collect())
+        };
+        match encoding {
+            EncodingBox::Char => Self::Char,
+            EncodingBox::Short => Self::Short,
+            EncodingBox::Int => Self::Int,
+            EncodingBox::Long => Self::Long,
+            EncodingBox::LongLong => Self::LongLong,
+            EncodingBox::UChar => Self::UChar,
+            EncodingBox::UShort => Self::UShort,
+            EncodingBox::UInt => Self::UInt,
+            EncodingBox::ULong => Self::ULong,
+            EncodingBox::ULongLong => Self::ULongLong,
+            EncodingBox::Int128 => Self::Int128,
+            EncodingBox::UInt128 => Self::UInt128,
+            EncodingBox::Float => Self::Float,
+            EncodingBox::Double => Self::Double,
+            EncodingBox::LongDouble => Self::LongDouble,
+            EncodingBox::FloatComplex => Self::FloatComplex,
+            EncodingBox::DoubleComplex => Self::DoubleComplex,
+            EncodingBox::LongDoubleComplex => Self::LongDoubleComplex,
+            EncodingBox::Bool => Self::Bool,
+            EncodingBox::Void => Self::Void,
+            EncodingBox::String => Self::String,
+            EncodingBox::Object(name) => Self::Object { name: name.clone() },
+            EncodingBox::Block(signature) => Self::Block {
+                signature: signature
+                    .as_ref()
+                    .map(|signature| signature.iter().map(Argument::from).collect()),
+            },
+            EncodingBox::Class => Self::Class,
+            EncodingBox::Sel => Self::Sel,
+            EncodingBox::Unknown => Self::Unknown,
+            EncodingBox::BitField(size, t) => Self::BitField {
+                size: *size,
+                offset: t.as_ref().map(|t| t.0),
+                bit_type: t.as_ref().map(|t| boxed(&t.1)),
+            },
+            EncodingBox::Pointer(t) => Self::Pointer { pointee: boxed(t) },
+            EncodingBox::Atomic(t) => Self::Atomic { value: boxed(t) },
+            EncodingBox::Array(len, item) => Self::Array {
+                len: *len,
+                item: boxed(item),
+            },
+            EncodingBox::Struct(name, fields) => Self::Struct {
+                name: name.clone(),
+                fields: items(fields),
+            },
+            EncodingBox::Union(name, members) => Self::Union {
+                name: name.clone(),
+                members: items(members),
+            },
+            EncodingBox::Vector(size, alignment, item) => Self::Vector {
+                size: *size,
+                alignment: *alignment,
+                item: boxed(item),
+            },
+        }
+    }
+}
+
+impl Structured {
+    fn into_box(self) -> Result<EncodingBox, &'static str> {
+        let boxed = |t: Box<Self>| t.into_box().map(Box::new);
+        let items = |items: Option<Vec<Self>>| {
+            items
+                .map(|items| items.into_iter().map(Self::into_box).collect())
+                .transpose()
+        };
+        Ok(match self {
+            Self::Char => EncodingBox::Char,
+            Self::Short => EncodingBox::Short,
+            Self::Int => EncodingBox::Int,
+            Self::Long => EncodingBox::Long,
+            Self::LongLong => EncodingBox::LongLong,
+            Self::UChar => EncodingBox::UChar,
+            Self::UShort => EncodingBox::UShort,
+            Self::UInt => EncodingBox::UInt,
+            Self::ULong => EncodingBox::ULong,
+            Self::ULongLong => EncodingBox::ULongLong,
+            Self::Int128 => EncodingBox::Int128,
+            Self::UInt128 => EncodingBox::UInt128,
+            Self::Float => EncodingBox::Float,
+            Self::Double => EncodingBox::Double,
+            Self::LongDouble => EncodingBox::LongDouble,
+            Self::FloatComplex => EncodingBox::FloatComplex,
+            Self::DoubleComplex => EncodingBox::DoubleComplex,
+            Self::LongDoubleComplex => EncodingBox::LongDoubleComplex,
+            Self::Bool => EncodingBox::Bool,
+            Self::Void => EncodingBox::Void,
+            Self::String => EncodingBox::String,
+            Self::Object { name } => {
+                if !name.as_deref().map_or(true, verify_object_name) {
+                    return Err("object name must not contain quotes, and protocols must be enclosed in `<>`");
+                }
+                EncodingBox::Object(name)
+            }
+            Self::Block { signature } => EncodingBox::Block(
+                signature
+                    .map(|signature| signature.into_iter().map(Argument::into_argument).collect())
+                    .transpose()?,
+            ),
+            Self::Class => EncodingBox::Class,
+            Self::Sel => EncodingBox::Sel,
+            Self::Unknown => EncodingBox::Unknown,
+            Self::BitField {
+                size,
+                offset,
+                bit_type,
+            } => match (offset, bit_type) {
+                (Some(offset), Some(t)) => {
+                    EncodingBox::BitField(size, Some(Box::new((offset, t.into_box()?))))
+                }
+                (None, None) => EncodingBox::BitField(size, None),
+                _ => return Err("bitfield must have both an offset and a type, or neither"),
+            },
+            Self::Pointer { pointee } => EncodingBox::Pointer(boxed(pointee)?),
+            Self::Atomic { value } => EncodingBox::Atomic(boxed(value)?),
+            Self::Array { len, item } => EncodingBox::Array(len, boxed(item)?),
+            Self::Struct { name, fields } => {
+                if !verify_name(&name) {
+                    return Err("struct name must be a valid identifier");
+                }
+                EncodingBox::Struct(name, items(fields)?)
+            }
+            Self::Union { name, members } => {
+                if !verify_name(&name) {
+                    return Err("union name must be a valid identifier");
+                }
+                EncodingBox::Union(name, items(members)?)
+            }
+            Self::Vector {
+                size,
+                alignment,
+                item,
+            } => EncodingBox::Vector(size, alignment, boxed(item)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Wrapper(#[serde(with = "crate::structured")] EncodingBox);
+
+    #[track_caller]
+    fn assert_roundtrip(s: &str, json: &str) {
+        let enc = Wrapper(s.parse().unwrap());
+        assert_eq!(serde_json::to_string(&enc).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Wrapper>(json).unwrap(), enc);
+    }
+
+    #[test]
+    fn roundtrip() {
+        assert_roundtrip("i", r#"{"type":"Int"}"#);
+        assert_roundtrip("^v", r#"{"type":"Pointer","pointee":{"type":"Void"}}"#);
+        assert_roundtrip(
+            "A[4T]",
+            r#"{"type":"Atomic","value":{"type":"Array","len":4,"item":{"type":"UInt128"}}}"#,
+        );
+        assert_roundtrip("{A}", r#"{"type":"Struct","name":"A"}"#);
+        assert_roundtrip(
+            "(U=b2c4b3)",
+            concat!(
+                r#"{"type":"Union","name":"U","members":["#,
+                r#"{"type":"BitField","size":4,"offset":2,"bit_type":{"type":"Char"}},"#,
+                r#"{"type":"BitField","size":3}]}"#,
+            ),
+        );
+        assert_roundtrip(
+            "@?<v@?r*@\"NSData\">",
+            concat!(
+                r#"{"type":"Block","signature":["#,
+                r#"{"encoding":{"type":"Void"}},{"encoding":{"type":"Block"}},"#,
+                r#"{"qualifiers":"r","encoding":{"type":"String"}},"#,
+                r#"{"encoding":{"type":"Object","name":"NSData"}}]}"#,
+            ),
+        );
+        assert_roundtrip(
+            "@\"NSObject<NSCopying><NSCoding>\"",
+            r#"{"type":"Object","name":"NSObject<NSCopying><NSCoding>"}"#,
+        );
+        assert_roundtrip(
+            "@\"<NSCopying>\"",
+            r#"{"type":"Object","name":"<NSCopying>"}"#,
+        );
+        assert_roundtrip(
+            "![16,16f]",
+            r#"{"type":"Vector","size":16,"alignment":16,"item":{"type":"Float"}}"#,
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let err = serde_json::from_str::<Wrapper>(r#"{"type":"BitField","size":1,"offset":0}"#)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "bitfield must have both an offset and a type, or neither"
+        );
+
+        let err = serde_json::from_str::<Wrapper>(r#"{"type":"Struct","name":"A=i"}"#).unwrap_err();
+        assert_eq!(err.to_string(), "struct name must be a valid identifier");
+        let err = serde_json::from_str::<Wrapper>(
+            r#"{"type":"Pointer","pointee":{"type":"Union","name":"","members":[]}}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "union name must be a valid identifier");
+
+        for name in [
+            "NS\"String",
+            "NSObject<NSCopying",
+            "<>",
+            "A>",
+            "<A<B>>",
+            "<A>B",
+        ] {
+            let json = serde_json::json!({"type": "Object", "name": name}).to_string();
+            let err = serde_json::from_str::<Wrapper>(&json).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "object name must not contain quotes, and protocols must be enclosed in `<>`"
+            );
+        }
+
+        let err = serde_json::from_str::<Wrapper>(
+            r#"{"type":"Block","signature":[{"qualifiers":"x","encoding":{"type":"Int"}}]}"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "qualifiers must only contain qualifier codes"
+        );
+
+        assert!(serde_json::from_str::<Wrapper>(
+            r#"{"type":"Array","len":1,"item":{"type":"Int"},"size":2}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Wrapper>(r#"{"type":"Foo"}"#).is_err());
+        assert!(serde_json::from_str::<Wrapper>(r#""i""#).is_err());
+    }
+
+    #[test]
+    fn nested_in_other() {
+        let enc = vec![EncodingBox::Struct("A".to_string(), Some(vec![]))];
+        let json = serde_json::to_string(&enc).unwrap();
+        assert_eq!(json, r#"["{A=}"]"#);
+    }
+}
@@ -0,0 +1,429 @@
+//! Implementation of `#[derive(Encode, RefEncode)]`.
+//!
+//! This parses the item by hand, since we don't want to depend on `syn`.
+use std::iter::FromIterator;
+
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+
+pub(crate) struct Error {
+    span: Span,
+    msg: &'static str,
+}
+
+impl Error {
+    fn new(span: Span, msg: &'static str) -> Self {
+        Self { span, msg }
+    }
+
+    /// Emit `compile_error!("...")` at the given span.
+    pub(crate) fn into_compile_error(self) -> TokenStream {
+        let mut literal = proc_macro::Literal::string(self.msg);
+        literal.set_span(self.span);
+        let mut args = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+        args.set_span(self.span);
+        let mut bang = Punct::new('!', Spacing::Alone);
+        bang.set_span(self.span);
+        let mut semi = Punct::new(';', Spacing::Alone);
+        semi.set_span(self.span);
+        TokenStream::from_iter([
+            TokenTree::Ident(Ident::new("compile_error", self.span)),
+            TokenTree::Punct(bang),
+            TokenTree::Group(args),
+            TokenTree::Punct(semi),
+        ])
+    }
+}
+
+#[derive(PartialEq, Eq)]
+enum Kind {
+    Struct,
+    Union,
+}
+
+pub(crate) struct Input {
+    kind: Kind,
+    ident: Ident,
+    /// The generic parameters, without their defaults.
+    params: Vec<TokenStream>,
+    /// The names of the generic parameters.
+    args: Vec<String>,
+    where_clause: TokenStream,
+    /// The types of the fields.
+    fields: Vec<TokenStream>,
+    transparent: bool,
+    /// The name literals from `#[encoding(...)]`.
+    name: Option<String>,
+    apple: Option<String>,
+    gnustep: Option<String>,
+}
+
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == c)
+}
+
+fn is_ident(token: Option<&TokenTree>, s: &str) -> bool {
+    matches!(token, Some(TokenTree::Ident(ident)) if ident.to_string() == s)
+}
+
+/// Split the tokens at commas that are not nested inside `<...>`.
+fn split_commas(tokens: Vec<TokenTree>) -> Vec<Vec<TokenTree>> {
+    let mut res = vec![Vec::new()];
+    let mut depth = 0usize;
+    let mut prev_minus = false;
+    for token in tokens {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                // Don't count the arrow in `fn() -> T`
+                '>' if !prev_minus => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    res.push(Vec::new());
+                    prev_minus = false;
+                    continue;
+                }
+                _ => {}
+            }
+            prev_minus = punct.as_char() == '-';
+        } else {
+            prev_minus = false;
+        }
+        res.last_mut().unwrap().push(token);
+    }
+    res.retain(|tokens| !tokens.is_empty());
+    res
+}
+
+/// Skip attributes and visibility at the start of the tokens.
+fn skip_attrs_and_vis(tokens: &[TokenTree]) -> &[TokenTree] {
+    let mut tokens = tokens;
+    loop {
+        match tokens {
+            [TokenTree::Punct(punct), TokenTree::Group(_), rest @ ..] if punct.as_char() == '#' => {
+                tokens = rest;
+            }
+            [TokenTree::Ident(ident), TokenTree::Group(group), rest @ ..]
+                if ident.to_string() == "pub" && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                tokens = rest;
+            }
+            [TokenTree::Ident(ident), rest @ ..] if ident.to_string() == "pub" => {
+                tokens = rest;
+            }
+            _ => return tokens,
+        }
+    }
+}
+
+fn parse_fields(group: &Group, named: bool) -> Result<Vec<TokenStream>, Error> {
+    split_commas(group.stream().into_iter().collect())
+        .into_iter()
+        .map(|field| {
+            let mut ty = skip_attrs_and_vis(&field);
+            if named {
+                match ty {
+                    [TokenTree::Ident(_), colon, rest @ ..] if is_punct(Some(colon), ':') => {
+                        ty = rest;
+                    }
+                    _ => return Err(Error::new(group.span(), "failed parsing field")),
+                }
+            }
+            Ok(TokenStream::from_iter(ty.iter().cloned()))
+        })
+        .collect()
+}
+
+/// Whether the type is `PhantomData<...>`, or a path ending in it.
+fn is_phantom_data(ty: &TokenStream) -> bool {
+    let mut last_ident = None;
+    for token in ty.clone() {
+        match token {
+            TokenTree::Ident(ident) => last_ident = Some(ident.to_string()),
+            TokenTree::Punct(punct) if punct.as_char() == '<' => break,
+            _ => {}
+        }
+    }
+    last_ident.as_deref() == Some("PhantomData")
+}
+
+impl Input {
+    fn parse_attr(&mut self, group: &Group, repr_c: &mut bool) -> Result<(), Error> {
+        let tokens: Vec<_> = group.stream().into_iter().collect();
+        let args = match tokens.get(1) {
+            Some(TokenTree::Group(args)) if args.delimiter() == Delimiter::Parenthesis => args,
+            _ => return Ok(()),
+        };
+        if is_ident(tokens.first(), "repr") {
+            for token in args.stream() {
+                if let TokenTree::Ident(ident) = token {
+                    match &*ident.to_string() {
+                        "C" => *repr_c = true,
+                        "transparent" => self.transparent = true,
+                        _ => {}
+                    }
+                }
+            }
+        } else if is_ident(tokens.first(), "encoding") {
+            for arg in split_commas(args.stream().into_iter().collect()) {
+                let (key, value) =
+                    match &*arg {
+                        [TokenTree::Ident(key), eq, TokenTree::Literal(value)]
+                            if is_punct(Some(eq), '=') =>
+                        {
+                            (key, value)
+                        }
+                        _ => return Err(Error::new(
+                            args.span(),
+                            "expected `name = \"...\"`, `apple = \"...\"` or `gnustep = \"...\"`",
+                        )),
+                    };
+                let value_str = value.to_string();
+                if !value_str.starts_with('"') {
+                    return Err(Error::new(value.span(), "expected a string literal"));
+                }
+                let slot = match &*key.to_string() {
+                    "name" => &mut self.name,
+                    "apple" => &mut self.apple,
+                    "gnustep" => &mut self.gnustep,
+                    _ => {
+                        return Err(Error::new(
+                            key.span(),
+                            "unknown key, expected `name`, `apple` or `gnustep`",
+                        ))
+                    }
+                };
+                *slot = Some(value_str);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn parse(input: TokenStream) -> Result<Self, Error> {
+        let tokens: Vec<_> = input.into_iter().collect();
+        let mut this = Self {
+            kind: Kind::Struct,
+            ident: Ident::new("_", Span::call_site()),
+            params: Vec::new(),
+            args: Vec::new(),
+            where_clause: TokenStream::new(),
+            fields: Vec::new(),
+            transparent: false,
+            name: None,
+            apple: None,
+            gnustep: None,
+        };
+        let mut repr_c = false;
+
+        // Attributes
+        let mut i = 0;
+        while is_punct(tokens.get(i), '#') {
+            if let Some(TokenTree::Group(group)) = tokens.get(i + 1) {
+                this.parse_attr(group, &mut repr_c)?;
+            }
+            i += 2;
+        }
+        let rest = skip_attrs_and_vis(&tokens[i..]);
+        let mut rest = rest.iter().peekable();
+
+        // `struct Foo` or `union Foo`
+        let keyword = match rest.next() {
+            Some(TokenTree::Ident(ident)) => ident,
+            _ => return Err(Error::new(Span::call_site(), "expected struct or union")),
+        };
+        this.kind = match &*keyword.to_string() {
+            "struct" => Kind::Struct,
+            "union" => Kind::Union,
+            _ => {
+                return Err(Error::new(
+                    keyword.span(),
+                    "#[derive(Encode)] is only supported on structs and unions",
+                ))
+            }
+        };
+        this.ident = match rest.next() {
+            Some(TokenTree::Ident(ident)) => ident.clone(),
+            _ => return Err(Error::new(keyword.span(), "expected identifier")),
+        };
+
+        if !repr_c && !this.transparent {
+            return Err(Error::new(
+                this.ident.span(),
+                "#[derive(Encode)] requires #[repr(C)] or #[repr(transparent)]",
+            ));
+        }
+
+        // Generics
+        if is_punct(rest.peek().copied(), '<') {
+            rest.next();
+            let mut depth = 1usize;
+            let mut prev_minus = false;
+            let mut generics = Vec::new();
+            for token in rest.by_ref() {
+                if let TokenTree::Punct(punct) = token {
+                    match punct.as_char() {
+                        '<' => depth += 1,
+                        '>' if !prev_minus => depth -= 1,
+                        _ => {}
+                    }
+                    prev_minus = punct.as_char() == '-';
+                } else {
+                    prev_minus = false;
+                }
+                if depth == 0 {
+                    break;
+                }
+                generics.push(token.clone());
+            }
+            for param in split_commas(generics) {
+                // Strip the default value
+                let mut depth = 0usize;
+                let param: Vec<_> = param
+                    .into_iter()
+                    .take_while(|token| {
+                        if let TokenTree::Punct(punct) = token {
+                            match punct.as_char() {
+                                '<' => depth += 1,
+                                '>' => depth = depth.saturating_sub(1),
+                                '=' if depth == 0 => return false,
+                                _ => {}
+                            }
+                        }
+                        true
+                    })
+                    .collect();
+                let arg = match &*param {
+                    [TokenTree::Punct(tick), TokenTree::Ident(ident), ..]
+                        if tick.as_char() == '\'' =>
+                    {
+                        format!("'{ident}")
+                    }
+                    [TokenTree::Ident(kw), TokenTree::Ident(ident), ..]
+                        if kw.to_string() == "const" =>
+                    {
+                        ident.to_string()
+                    }
+                    [TokenTree::Ident(ident), ..] => ident.to_string(),
+                    _ => return Err(Error::new(this.ident.span(), "failed parsing generics")),
+                };
+                this.args.push(arg);
+                this.params.push(TokenStream::from_iter(param));
+            }
+        }
+
+        // Where clause and fields
+        let mut where_clause = Vec::new();
+        let mut in_where = false;
+        for token in rest {
+            match token {
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                    this.fields = parse_fields(group, true)?;
+                    break;
+                }
+                TokenTree::Group(group)
+                    if group.delimiter() == Delimiter::Parenthesis && !in_where =>
+                {
+                    this.fields = parse_fields(group, false)?;
+                }
+                TokenTree::Ident(ident) if ident.to_string() == "where" => in_where = true,
+                TokenTree::Punct(punct) if punct.as_char() == ';' => break,
+                token if in_where => where_clause.push(token.clone()),
+                _ => {}
+            }
+        }
+        // Ensure a trailing comma
+        if !where_clause.is_empty() && !is_punct(where_clause.last(), ',') {
+            where_clause.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+        }
+        this.where_clause = TokenStream::from_iter(where_clause);
+
+        Ok(this)
+    }
+
+    /// The field that a `#[repr(transparent)]` type delegates to.
+    fn transparent_field(&self) -> Result<&TokenStream, Error> {
+        let mut fields = self.fields.iter().filter(|ty| !is_phantom_data(ty));
+        match (fields.next(), fields.next()) {
+            (Some(field), None) => Ok(field),
+            _ => Err(Error::new(
+                self.ident.span(),
+                "#[repr(transparent)] types must have exactly one field that is not PhantomData",
+            )),
+        }
+    }
+
+    /// `impl<...> Trait for Name<...> where ...`
+    fn impl_header(&self, trait_: &str, bound: &str, bounded: &[&TokenStream]) -> String {
+        let params: Vec<_> = self.params.iter().map(|p| p.to_string()).collect();
+        let mut where_clause = self.where_clause.to_string();
+        // Only generic types need the bounds on the fields
+        if !self.params.is_empty() {
+            for ty in bounded {
+                where_clause.push_str(&format!("{ty}: {bound},"));
+            }
+        }
+        format!(
+            "unsafe impl<{}> {trait_} for {}<{}> where {where_clause}",
+            params.join(", "),
+            self.ident,
+            self.args.join(", "),
+        )
+    }
+
+    pub(crate) fn encode(&self) -> Result<TokenStream, Error> {
+        let encode = "::objc2::encode::Encode";
+        let encoding = "::objc2::encode::Encoding";
+        let body = if self.transparent {
+            let field = self.transparent_field()?;
+            let header = self.impl_header(encode, encode, &[field]);
+            format!("{header} {{ const ENCODING: {encoding} = <{field} as {encode}>::ENCODING; }}")
+        } else {
+            let ident = self.ident.to_string();
+            let default = format!("{:?}", ident.trim_start_matches("r#"));
+            let name = self.name.as_ref().unwrap_or(&default);
+            let option = |s: &Option<String>| match s {
+                Some(s) => format!("::objc2::__macro_helpers::Some({s})"),
+                None => "::objc2::__macro_helpers::None".to_string(),
+            };
+            let variant = match self.kind {
+                Kind::Struct => "Struct",
+                Kind::Union => "Union",
+            };
+            let fields: Vec<_> = self
+                .fields
+                .iter()
+                .map(|ty| format!("<{ty} as {encode}>::ENCODING,"))
+                .collect();
+            let header = self.impl_header(encode, encode, &self.fields.iter().collect::<Vec<_>>());
+            format!(
+                "{header} {{ const ENCODING: {encoding} = {encoding}::{variant}(\
+                    ::objc2::__macro_helpers::encoding_name({name}, {}, {}), \
+                    &[{}],\
+                ); }}",
+                option(&self.apple),
+                option(&self.gnustep),
+                fields.concat(),
+            )
+        };
+        Ok(body.parse().expect("generated invalid tokens"))
+    }
+
+    pub(crate) fn ref_encode(&self) -> Result<TokenStream, Error> {
+        let ref_encode = "::objc2::encode::RefEncode";
+        let encoding = "::objc2::encode::Encoding";
+        let body = if self.transparent {
+            let field = self.transparent_field()?;
+            let header = self.impl_header(ref_encode, ref_encode, &[field]);
+            format!(
+                "{header} {{ const ENCODING_REF: {encoding} = <{field} as {ref_encode}>::ENCODING_REF; }}"
+            )
+        } else {
+            let header = self.impl_header(ref_encode, "::objc2::encode::Encode", &[]);
+            format!(
+                "{header} Self: ::objc2::encode::Encode, {{ \
+                    const ENCODING_REF: {encoding} = \
+                        {encoding}::Pointer(&<Self as ::objc2::encode::Encode>::ENCODING); \
+                }}"
+            )
+        };
+        Ok(body.parse().expect("generated invalid tokens"))
+    }
+}
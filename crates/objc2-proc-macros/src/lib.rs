@@ -17,6 +17,8 @@
 #[doc = include_str!("../README.md")]
 extern "C" {}
 
+mod derive_encode;
+
 use core::hash::{Hash, Hasher};
 
 use proc_macro::Ident;
@@ -72,3 +74,25 @@ pub fn __hash_idents(input: TokenStream) -> TokenStream {
     let s = format!("{:016x}", hasher.finish());
     TokenTree::Literal(Literal::string(&s)).into()
 }
+
+/// Derive `Encode` for a `#[repr(C)]` or `#[repr(transparent)]` struct or
+/// union.
+///
+/// See `objc2::encode` for details.
+#[proc_macro_derive(Encode, attributes(encoding))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    derive_encode::Input::parse(input)
+        .and_then(|input| input.encode())
+        .unwrap_or_else(|err| err.into_compile_error())
+}
+
+/// Derive `RefEncode` for a `#[repr(C)]` or `#[repr(transparent)]` struct
+/// or union.
+///
+/// See `objc2::encode` for details.
+#[proc_macro_derive(RefEncode, attributes(encoding))]
+pub fn derive_ref_encode(input: TokenStream) -> TokenStream {
+    derive_encode::Input::parse(input)
+        .and_then(|input| input.ref_encode())
+        .unwrap_or_else(|err| err.into_compile_error())
+}
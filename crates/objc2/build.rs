@@ -0,0 +1,25 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    // Only rerun if this file changes; the script doesn't depend on our code
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // Declare the cfg below, so that `unexpected_cfgs` doesn't warn about it
+    println!("cargo:rustc-check-cfg=cfg(i128_aligned)");
+
+    // Before Rust 1.77, the alignment of `i128` and `u128` did not match that
+    // of `__int128` on x86, see https://github.com/rust-lang/rust/issues/54341
+    if matches!(rustc_minor_version(), Some(minor) if minor >= 77) {
+        println!("cargo:rustc-cfg=i128_aligned");
+    }
+}
+
+/// The minor version of the compiler, e.g. `77` for `rustc 1.77.0`.
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = String::from_utf8(output.stdout).ok()?;
+    let mut parts = version.strip_prefix("rustc 1.")?.split('.');
+    parts.next()?.parse().ok()
+}
@@ -1,9 +1,17 @@
+#[cfg(all(debug_assertions, feature = "verify"))]
+use alloc::format;
+#[cfg(all(debug_assertions, feature = "verify"))]
+use alloc::string::String;
+#[cfg(all(debug_assertions, feature = "verify"))]
+use core::cell::Cell;
+use core::marker::PhantomData;
 use core::mem::ManuallyDrop;
 use core::ptr;
 
-use crate::declare::__IdReturnValue;
+use crate::declare::{RequiresSuper, __IdReturnValue};
 use crate::rc::{Allocated, Id, Ownership};
-use crate::{Message, MessageReceiver};
+use crate::runtime::{Class, Sel};
+use crate::{ClassType, Message, MessageReceiver};
 
 use super::{CopyOrMutCopy, Init, MaybeUnwrap, New, Other};
 
@@ -111,3 +119,228 @@ impl<T: Message, O: Ownership> MaybeOptionId for Option<Id<T, O>> {
         __IdReturnValue(ptr.cast())
     }
 }
+
+/// Helper for looking up [`RequiresSuper`] on a type that may or may not
+/// implement it, using autoref-based specialization.
+///
+/// Use as `(&&RequiresSuperLookup::<T>::new()).requires_super(selector)`
+/// with both [`RequiresSuperSpecific`] and [`RequiresSuperFallback`] in
+/// scope.
+pub struct RequiresSuperLookup<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> RequiresSuperLookup<T> {
+    #[inline]
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait RequiresSuperSpecific {
+    fn requires_super(&self, selector: &str) -> bool;
+}
+
+impl<T: RequiresSuper> RequiresSuperSpecific for &RequiresSuperLookup<T> {
+    #[inline]
+    fn requires_super(&self, selector: &str) -> bool {
+        T::requires_super(selector)
+    }
+}
+
+pub trait RequiresSuperFallback {
+    #[inline]
+    fn requires_super(&self, _selector: &str) -> bool {
+        false
+    }
+}
+
+impl<T: ?Sized> RequiresSuperFallback for RequiresSuperLookup<T> {}
+
+/// The super call that the innermost active [`RequiresSuperGuard`] expects.
+#[cfg(all(debug_assertions, feature = "verify"))]
+#[derive(Clone, Copy)]
+struct ExpectedSuper {
+    /// The class whose method is being run.
+    class: &'static Class,
+    sel: Sel,
+    called: bool,
+}
+
+#[cfg(all(debug_assertions, feature = "verify"))]
+std::thread_local! {
+    static EXPECTED_SUPER: Cell<Option<ExpectedSuper>> = const { Cell::new(None) };
+}
+
+/// Whether `superclass` is a superclass of `class` or of its metaclass.
+#[cfg(all(debug_assertions, feature = "verify"))]
+fn is_superclass_of(superclass: &Class, class: &Class) -> bool {
+    let mut cls = class.superclass();
+    let mut metacls = class.metaclass().superclass();
+    while cls.is_some() || metacls.is_some() {
+        if cls.map_or(false, |cls| ptr::eq(cls, superclass))
+            || metacls.map_or(false, |metacls| ptr::eq(metacls, superclass))
+        {
+            return true;
+        }
+        cls = cls.and_then(Class::superclass);
+        metacls = metacls.and_then(Class::superclass);
+    }
+    false
+}
+
+/// Called whenever a message is sent to `super`.
+///
+/// Only marks the innermost guard as satisfied if the message was the
+/// overridden method on one of the class' superclasses, so that unrelated
+/// super calls (e.g. from other methods called in the body) don't count.
+#[inline]
+#[allow(unused_variables)]
+pub(crate) fn called_super(superclass: &Class, sel: Sel) {
+    #[cfg(all(debug_assertions, feature = "verify"))]
+    EXPECTED_SUPER.with(|expected| {
+        if let Some(mut data) = expected.get() {
+            if !data.called && data.sel == sel && is_superclass_of(superclass, data.class) {
+                data.called = true;
+                expected.set(Some(data));
+            }
+        }
+    });
+}
+
+/// Guard that is put around the body of methods in `declare_class!`, and
+/// checks that the method called `super` if the superclass requires that.
+///
+/// This is a no-op unless the `verify` feature and debug assertions are
+/// enabled.
+pub struct RequiresSuperGuard {
+    /// The class name, the selector and the previous expectation.
+    #[cfg(all(debug_assertions, feature = "verify"))]
+    data: Option<(&'static str, &'static str, Option<ExpectedSuper>)>,
+}
+
+impl RequiresSuperGuard {
+    #[inline]
+    #[allow(unused_variables)]
+    pub fn new<T: ClassType>(
+        selector: &'static str,
+        requires_super: impl FnOnce(&str) -> bool,
+    ) -> Self {
+        #[cfg(all(debug_assertions, feature = "verify"))]
+        {
+            // The selector data is NUL-terminated
+            let selector = selector.trim_end_matches('\0');
+            let data = requires_super(selector).then(|| {
+                let current = ExpectedSuper {
+                    class: T::class(),
+                    sel: Sel::register(selector),
+                    called: false,
+                };
+                let previous = EXPECTED_SUPER.with(|expected| expected.replace(Some(current)));
+                (T::NAME, selector, previous)
+            });
+            Self { data }
+        }
+        #[cfg(not(all(debug_assertions, feature = "verify")))]
+        {
+            Self {}
+        }
+    }
+
+    /// Restore the previous expectation, and return a warning if `super`
+    /// was not called.
+    #[cfg(all(debug_assertions, feature = "verify"))]
+    fn finish(&mut self) -> Option<String> {
+        let (name, selector, previous) = self.data.take()?;
+        let current = EXPECTED_SUPER.with(|expected| expected.replace(previous));
+        if current.map_or(true, |current| current.called) {
+            None
+        } else {
+            Some(format!(
+                "-[{name} {selector}] is overriding a method marked \
+                `NS_REQUIRES_SUPER`, but did not call `msg_send![super(...), ...]`",
+            ))
+        }
+    }
+}
+
+#[cfg(all(debug_assertions, feature = "verify"))]
+impl Drop for RequiresSuperGuard {
+    fn drop(&mut self) {
+        let warning = self.finish();
+        // Panicking here would abort, since we're inside an `extern "C"`
+        // function, so we only emit a warning.
+        if let Some(warning) = warning {
+            if !std::thread::panicking() {
+                std::eprintln!("warning: {warning}");
+            }
+        }
+    }
+}
+
+#[cfg(all(test, debug_assertions, feature = "verify"))]
+mod tests {
+    use super::*;
+
+    use crate::rc::__RcTestObject;
+    use crate::runtime::NSObject;
+    use crate::sel;
+
+    fn guard(selector: &'static str) -> RequiresSuperGuard {
+        RequiresSuperGuard::new::<__RcTestObject>(selector, |_| true)
+    }
+
+    #[test]
+    fn test_not_required() {
+        let mut guard = RequiresSuperGuard::new::<__RcTestObject>("init\0", |_| false);
+        assert_eq!(guard.finish(), None);
+    }
+
+    #[test]
+    fn test_called_super() {
+        let mut guard = guard("init\0");
+        called_super(NSObject::class(), sel!(init));
+        assert_eq!(guard.finish(), None);
+
+        // Class methods call super on the metaclass
+        let mut guard = self::guard("new\0");
+        called_super(NSObject::class().metaclass(), sel!(new));
+        assert_eq!(guard.finish(), None);
+    }
+
+    #[test]
+    fn test_missing_super() {
+        let mut guard = guard("init\0");
+        assert_eq!(
+            guard.finish().unwrap(),
+            "-[__RcTestObject init] is overriding a method marked `NS_REQUIRES_SUPER`, \
+            but did not call `msg_send![super(...), ...]`",
+        );
+    }
+
+    #[test]
+    fn test_unrelated_super_call() {
+        let mut guard = guard("init\0");
+        // Different selector
+        called_super(NSObject::class(), sel!(hash));
+        // Not a superclass
+        called_super(__RcTestObject::class(), sel!(init));
+        assert!(guard.finish().is_some());
+    }
+
+    #[test]
+    fn test_nested() {
+        let mut outer = guard("init\0");
+        let mut inner = guard("hash\0");
+        // Only applies to the innermost guard
+        called_super(NSObject::class(), sel!(init));
+        assert!(inner.finish().is_some());
+        assert!(outer.finish().is_some());
+
+        let mut outer = guard("init\0");
+        let mut inner = guard("hash\0");
+        called_super(NSObject::class(), sel!(hash));
+        assert_eq!(inner.finish(), None);
+        called_super(NSObject::class(), sel!(init));
+        assert_eq!(outer.finish(), None);
+    }
+}
@@ -22,9 +22,10 @@ pub use core::marker::{PhantomData, Sized};
 pub use core::mem::{needs_drop, size_of, ManuallyDrop};
 pub use core::ops::{Deref, DerefMut};
 pub use core::option::Option::{self, None, Some};
-pub use core::primitive::{bool, str, u8};
+pub use core::primitive::{bool, str, u8, usize};
 pub use core::ptr::drop_in_place;
 pub use core::{compile_error, concat, panic, stringify};
+pub use std::ffi::CStr;
 // TODO: Use `core::cell::LazyCell`
 pub use std::sync::Once;
 
@@ -32,7 +33,11 @@ mod cache;
 mod declare_class;
 
 pub use self::cache::{CachedClass, CachedSel};
-pub use self::declare_class::{MaybeOptionId, MessageRecieveId};
+pub(crate) use self::declare_class::called_super;
+pub use self::declare_class::{
+    MaybeOptionId, MessageRecieveId, RequiresSuperFallback, RequiresSuperGuard,
+    RequiresSuperLookup, RequiresSuperSpecific,
+};
 
 // Common selectors.
 //
@@ -83,6 +88,35 @@ pub type Alloc = RetainSemantics<2>;
 pub type Init = RetainSemantics<3>;
 pub type CopyOrMutCopy = RetainSemantics<4>;
 pub type Other = RetainSemantics<5>;
+/// Methods marked with `NS_RETURNS_RETAINED`, regardless of which selector
+/// family they are in.
+pub type ReturnsRetained = RetainSemantics<6>;
+/// Methods marked with `NS_RETURNS_NOT_RETAINED` or
+/// `NS_RETURNS_AUTORELEASED`, regardless of which selector family they are
+/// in.
+///
+/// These have the same semantics as methods in no family.
+pub type ReturnsNotRetained = Other;
+
+/// Select the name of a struct or union for the current runtime, used by
+/// `#[derive(Encode)]`.
+pub const fn encoding_name(
+    name: &'static str,
+    apple: Option<&'static str>,
+    gnustep: Option<&'static str>,
+) -> &'static str {
+    #[cfg(feature = "apple")]
+    let specific = apple;
+    #[cfg(feature = "gnustep-1-7")]
+    let specific = gnustep;
+    #[cfg(not(any(feature = "apple", feature = "gnustep-1-7")))]
+    let specific = None;
+    let _ = (apple, gnustep);
+    match specific {
+        Some(name) => name,
+        None => name,
+    }
+}
 
 pub const fn retain_semantics(selector: &str) -> u8 {
     let selector = selector.as_bytes();
@@ -258,6 +292,32 @@ impl<T: MessageReceiver, U: Message, O: Ownership> MsgSendId<T, Id<U, O>> for Ot
     }
 }
 
+// `U: ?Sized` like for `New` and `CopyOrMutCopy`, since this only uses
+// `Id::new`; `Other` requires `U: Sized` because `Id::retain_autoreleased`
+// does.
+impl<T: MessageReceiver, U: ?Sized + Message, O: Ownership> MsgSendId<T, Id<U, O>>
+    for ReturnsRetained
+{
+    #[inline]
+    unsafe fn send_message_id<A: MessageArguments, R: MaybeUnwrap<Input = Id<U, O>>>(
+        obj: T,
+        sel: Sel,
+        args: A,
+    ) -> R {
+        let ptr = obj.__as_raw_receiver();
+        // SAFETY: Checked by caller
+        let obj = unsafe { MessageReceiver::send_message(ptr, sel, args) };
+        // SAFETY: The method is marked `NS_RETURNS_RETAINED`, so this has +1
+        // retain count
+        let obj = unsafe { Id::new(obj) };
+
+        // SAFETY: The object is still valid after the message send, since
+        // the method does not consume its receiver (that is only the case
+        // for `init` methods, or ones marked with `NS_CONSUMES_SELF`).
+        R::maybe_unwrap::<Self>(obj, (unsafe { ptr.as_ref() }, sel))
+    }
+}
+
 pub trait MaybeUnwrap {
     type Input;
     #[track_caller]
@@ -382,6 +442,15 @@ impl MsgSendIdFailed<'_> for CopyOrMutCopy {
     }
 }
 
+impl<'a> MsgSendIdFailed<'a> for ReturnsRetained {
+    type Args = (Option<&'a Object>, Sel);
+
+    #[cold]
+    fn failed(args: Self::Args) -> ! {
+        Other::failed(args)
+    }
+}
+
 impl<'a> MsgSendIdFailed<'a> for Other {
     type Args = (Option<&'a Object>, Sel);
 
@@ -774,6 +843,48 @@ mod tests {
         expected.assert_current();
     }
 
+    #[test]
+    fn test_overridden_retain_semantics() {
+        let mut expected = __ThreadTestData::current();
+        let obj = __RcTestObject::new();
+        expected.alloc += 1;
+        expected.init += 1;
+
+        let retained: Id<__RcTestObject> = unsafe {
+            crate::__msg_send_id_helper! {
+                @(send_message_id)
+                @(&obj)
+                @(ReturnsRetained)
+                @(methodReturningRetained)
+                @()
+            }
+        };
+        expected.alloc += 1;
+        expected.init += 1;
+        expected.assert_current();
+
+        let not_retained: Id<__RcTestObject> = unsafe {
+            crate::__msg_send_id_helper! {
+                @(send_message_id)
+                @(&obj)
+                @(ReturnsNotRetained)
+                @(copyReturningNotRetained)
+                @()
+            }
+        };
+        expected.retain += 1;
+        expected.assert_current();
+
+        drop(retained);
+        expected.release += 1;
+        expected.dealloc += 1;
+        expected.assert_current();
+
+        drop(not_retained);
+        expected.release += 1;
+        expected.assert_current();
+    }
+
     #[test]
     #[should_panic = "failed creating new instance of NSValue"]
     // GNUStep instead returns an invalid instance that panics on accesses
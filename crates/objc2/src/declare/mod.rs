@@ -115,17 +115,19 @@ mod ivar_bool;
 mod ivar_drop;
 mod ivar_encode;
 mod ivar_forwarding_impls;
+mod requires_super;
 
-use alloc::format;
 use alloc::string::ToString;
+use core::marker::PhantomData;
 use core::mem;
 use core::mem::ManuallyDrop;
 use core::ptr;
 use core::ptr::NonNull;
 use std::ffi::CString;
+use std::os::raw::c_char;
 
 use crate::encode::__unstable::{EncodeArguments, EncodeReturn};
-use crate::encode::{Encode, Encoding, RefEncode};
+use crate::encode::{Encode, Encoding, MethodEncoding, RefEncode};
 use crate::ffi;
 use crate::rc::Allocated;
 use crate::runtime::{Bool, Class, Imp, Object, Protocol, Sel};
@@ -136,6 +138,7 @@ pub use ivar::{InnerIvarType, Ivar, IvarType};
 pub use ivar_bool::IvarBool;
 pub use ivar_drop::IvarDrop;
 pub use ivar_encode::IvarEncode;
+pub use requires_super::RequiresSuper;
 
 pub(crate) mod private {
     pub trait Sealed {}
@@ -284,14 +287,58 @@ unsafe impl Encode for __IdReturnValue {
     const ENCODING: Encoding = <*mut Object>::ENCODING;
 }
 
-fn method_type_encoding(ret: &Encoding, args: &[Encoding]) -> CString {
-    // First two arguments are always self and the selector
-    let mut types = format!("{ret}{}{}", <*mut Object>::ENCODING, Sel::ENCODING);
-    for enc in args {
-        use core::fmt::Write;
-        write!(&mut types, "{enc}").unwrap();
+/// The capacity of method type-encodings that are computed at compile-time.
+///
+/// This fits the encoding of methods with the maximum of 16 arguments, as
+/// long as these are pointers or primitives; e.g. `v144@0:8@16...@136` is
+/// 61 bytes long. Longer encodings, e.g. of methods taking large structs,
+/// are instead formatted at runtime. The length is computed before the
+/// array is filled (see `StaticTypes::TYPES`), so a capacity that is too
+/// small only costs an allocation, and can never truncate the encoding.
+const STATIC_TYPES_CAPACITY: usize = 128;
+
+struct StaticTypes<Args, Ret>(PhantomData<(Args, Ret)>);
+
+impl<Args: EncodeArguments, Ret: EncodeReturn> StaticTypes<Args, Ret> {
+    const LEN: usize =
+        MethodEncoding::__static_str_len(&Ret::ENCODING_RETURN, Args::ENCODINGS, Args::__SIZES);
+
+    /// The NUL-terminated type-encoding, if it fits.
+    const TYPES: Option<[u8; STATIC_TYPES_CAPACITY]> = if Self::LEN < STATIC_TYPES_CAPACITY {
+        Some(MethodEncoding::__static_str_array(
+            &Ret::ENCODING_RETURN,
+            Args::ENCODINGS,
+            Args::__SIZES,
+        ))
+    } else {
+        None
+    };
+}
+
+/// The type-encoding of a method, as passed to `class_addMethod` and
+/// `protocol_addMethodDescription` (which both copy the string).
+enum MethodTypes {
+    Static([u8; STATIC_TYPES_CAPACITY]),
+    Formatted(CString),
+}
+
+impl MethodTypes {
+    fn as_ptr(&self) -> *const c_char {
+        match self {
+            // The array is zero-padded, so it is always NUL-terminated
+            Self::Static(types) => types.as_ptr().cast(),
+            Self::Formatted(types) => types.as_ptr(),
+        }
+    }
+}
+
+fn method_type_encoding<Args: EncodeArguments, Ret: EncodeReturn>() -> MethodTypes {
+    match StaticTypes::<Args, Ret>::TYPES {
+        Some(types) => MethodTypes::Static(types),
+        None => MethodTypes::Formatted(
+            CString::new(Args::method_encoding::<Ret>().to_string()).unwrap(),
+        ),
     }
-    CString::new(types).unwrap()
 }
 
 fn log2_align_of<T>() -> u8 {
@@ -433,7 +480,7 @@ impl ClassBuilder {
             }
         }
 
-        let types = method_type_encoding(&enc_ret, enc_args);
+        let types = method_type_encoding::<F::Args, F::Ret>();
         let success = Bool::from_raw(unsafe {
             ffi::class_addMethod(
                 self.as_mut_ptr(),
@@ -490,7 +537,7 @@ impl ClassBuilder {
             }
         }
 
-        let types = method_type_encoding(&enc_ret, enc_args);
+        let types = method_type_encoding::<F::Args, F::Ret>();
         let success = Bool::from_raw(unsafe {
             ffi::class_addMethod(
                 self.metaclass_mut(),
@@ -642,7 +689,7 @@ impl ProtocolBuilder {
             sel_args,
             encs.len(),
         );
-        let types = method_type_encoding(&Ret::ENCODING_RETURN, encs);
+        let types = method_type_encoding::<Args, Ret>();
         unsafe {
             ffi::protocol_addMethodDescription(
                 self.as_mut_ptr(),
@@ -836,6 +883,31 @@ mod tests {
         assert!(is_present(cls));
     }
 
+    #[test]
+    fn test_method_type_encoding() {
+        struct Large;
+
+        unsafe impl Encode for Large {
+            const ENCODING: Encoding = Encoding::Struct(
+                "AStructWithAVeryLongNameThatDoesNotFitInTheEncodingComputedAtCompileTime",
+                &[Encoding::Double, Encoding::Double, Encoding::Double],
+            );
+        }
+
+        fn check<Args: EncodeArguments, Ret: EncodeReturn>(is_static: bool) {
+            let types = method_type_encoding::<Args, Ret>();
+            assert_eq!(matches!(types, MethodTypes::Static(_)), is_static);
+            let types = unsafe { std::ffi::CStr::from_ptr(types.as_ptr()) };
+            let expected = Args::method_encoding::<Ret>().to_string();
+            assert_eq!(types.to_str().unwrap(), expected);
+        }
+
+        check::<(), ()>(true);
+        check::<(i8, &Object, Sel, bool), Bool>(true);
+        check::<(u16, Large), u32>(false);
+        check::<(Large,), Large>(false);
+    }
+
     #[test]
     fn test_class_method() {
         let cls = test_utils::custom_class();
@@ -0,0 +1,43 @@
+use crate::ClassType;
+
+/// Marks methods that overriders must call the superclass' implementation
+/// from.
+///
+/// This is the equivalent of the `NS_REQUIRES_SUPER` attribute in
+/// Objective-C, and is implemented by `icrate` for classes that have methods
+/// marked with that.
+///
+/// [`declare_class!`] implements this trait for the declared class by
+/// forwarding to the superclass, and if the `verify` feature and debug
+/// assertions are enabled, it uses it to warn when an overridden method
+/// returns without having called `msg_send![super(...), ...]`.
+///
+/// [`declare_class!`]: crate::declare_class
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::declare::RequiresSuper;
+/// use objc2::runtime::NSObject;
+/// use objc2::{extern_class, ClassType};
+///
+/// extern_class!(
+///     pub struct NSViewController;
+///
+///     unsafe impl ClassType for NSViewController {
+///         type Super = NSObject;
+///     }
+/// );
+///
+/// impl RequiresSuper for NSViewController {
+///     fn requires_super(selector: &str) -> bool {
+///         matches!(selector, "viewDidLoad" | "viewWillAppear")
+///     }
+/// }
+/// ```
+pub trait RequiresSuper: ClassType {
+    /// Whether an override of the method with the given selector must call
+    /// the superclass' implementation.
+    fn requires_super(selector: &str) -> bool;
+}
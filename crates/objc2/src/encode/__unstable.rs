@@ -17,7 +17,9 @@
     doc(cfg(feature = "unstable-encode-internals"))
 )]
 
-use crate::encode::{Encode, Encoding};
+use core::mem;
+
+use crate::encode::{Encode, Encoding, MethodEncoding};
 use crate::rc::{Id, Ownership};
 use crate::runtime::Bool;
 use crate::Message;
@@ -177,6 +179,16 @@ mod args_private {
 pub trait EncodeArguments: args_private::Sealed {
     /// The encodings for the arguments.
     const ENCODINGS: &'static [Encoding];
+
+    /// The sizes of the arguments.
+    #[doc(hidden)]
+    const __SIZES: &'static [usize];
+
+    /// The type-encoding of a method that takes these arguments and returns
+    /// `Ret`, including the stack offsets of the arguments.
+    fn method_encoding<Ret: EncodeReturn>() -> MethodEncoding {
+        MethodEncoding::new(&Ret::ENCODING_RETURN, Self::ENCODINGS, Self::__SIZES)
+    }
 }
 
 macro_rules! encode_args_impl {
@@ -189,6 +201,8 @@ macro_rules! encode_args_impl {
                 // bool::__Inner::ENCODING => Bool::ENCODING
                 $($Arg::__Inner::ENCODING),*
             ];
+
+            const __SIZES: &'static [usize] = &[$(mem::size_of::<$Arg::__Inner>()),*];
         }
     };
 }
@@ -275,4 +289,24 @@ mod tests {
         assert_eq!(<(i8,)>::ENCODINGS, &[i8::ENCODING]);
         assert_eq!(<(i8, u32)>::ENCODINGS, &[i8::ENCODING, u32::ENCODING]);
     }
+
+    #[test]
+    fn method_encoding() {
+        use alloc::format;
+        use alloc::string::ToString;
+
+        let ptr_size = mem::size_of::<*const ()>();
+        let args = 2 * ptr_size;
+        let encoding = <(i32, bool, *const u8)>::method_encoding::<()>();
+        assert_eq!(
+            encoding.to_string(),
+            format!(
+                "v{}@0:{ptr_size}i{args}{}{}^C{}",
+                args + 8 + ptr_size,
+                Bool::ENCODING,
+                args + 4,
+                args + 8,
+            ),
+        );
+    }
 }
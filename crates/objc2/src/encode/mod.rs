@@ -62,6 +62,8 @@
 use core::cell::{Cell, UnsafeCell};
 use core::ffi::c_void;
 use core::mem::{self, ManuallyDrop, MaybeUninit};
+#[cfg(i128_aligned)]
+use core::num::{NonZeroI128, NonZeroU128};
 use core::num::{
     NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU16, NonZeroU32,
     NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
@@ -73,7 +75,59 @@ use core::sync::atomic;
 pub mod __unstable;
 
 #[doc(inline)]
-pub use objc2_encode::{Encoding, EncodingBox, ParseError};
+pub use objc2_encode::{
+    Encoding, EncodingBox, EncodingDiff, EncodingProfile, MethodArgument, MethodEncoding,
+    ParseError, PathSegment, Qualifiers,
+};
+
+/// Derive [`Encode`] for a struct or union.
+///
+/// The type must be `#[repr(C)]` or `#[repr(transparent)]`, and all its
+/// fields must implement [`Encode`]. A `#[repr(C)]` struct is encoded as
+/// [`Encoding::Struct`] (and a union as [`Encoding::Union`]) with the
+/// encodings of its fields, while a `#[repr(transparent)]` type has the same
+/// encoding as its non-[`PhantomData`] field.
+///
+/// The name of the struct defaults to the name of the Rust type, and can be
+/// overridden with `#[encoding(name = "...")]`. The name can be further
+/// overridden for a specific runtime with `apple = "..."` or
+/// `gnustep = "..."`, since some structs are named differently there.
+///
+/// Requires the `"derive"` feature.
+///
+/// [`PhantomData`]: core::marker::PhantomData
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::encode::{Encode, RefEncode};
+///
+/// #[repr(C)]
+/// #[derive(Encode, RefEncode)]
+/// #[encoding(name = "CGPoint", gnustep = "_NSPoint")]
+/// struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// # #[cfg(feature = "apple")]
+/// assert!(Point::ENCODING.equivalent_to_str("{CGPoint=dd}"));
+/// # #[cfg(feature = "apple")]
+/// assert!(Point::ENCODING_REF.equivalent_to_str("^{CGPoint=dd}"));
+/// ```
+#[cfg(feature = "derive")]
+pub use objc2_proc_macros::Encode;
+
+/// Derive [`RefEncode`] for a struct or union.
+///
+/// A `#[repr(C)]` type is encoded as a pointer to its [`Encode`]
+/// implementation, so this is usually derived together with that, while a
+/// `#[repr(transparent)]` type has the same reference encoding as its field.
+///
+/// Requires the `"derive"` feature.
+#[cfg(feature = "derive")]
+pub use objc2_proc_macros::RefEncode;
 
 /// Types that have an Objective-C type-encoding.
 ///
@@ -295,12 +349,18 @@ encode_impls!(
     u64 => ULongLong,
     f32 => Float,
     f64 => Double,
-
-    // TODO: i128 & u128
-    // https://github.com/rust-lang/rust/issues/54341
 );
 
-// TODO: Structs in core::arch?
+// Note: Before Rust 1.77, the alignment of `i128` and `u128` did not match
+// that of `__int128` on x86, see
+// https://github.com/rust-lang/rust/issues/54341
+//
+// So these are only implemented on newer compilers (detected in `build.rs`).
+#[cfg(i128_aligned)]
+encode_impls!(
+    i128 => Int128,
+    u128 => UInt128,
+);
 
 macro_rules! encode_impls_size {
     ($($t:ty => ($t16:ty, $t32:ty, $t64:ty),)*) => ($(
@@ -331,6 +391,8 @@ macro_rules! pointer_refencode_impl {
 }
 
 pointer_refencode_impl!(i16, i32, i64, isize, u16, u32, u64, usize, f32, f64);
+#[cfg(i128_aligned)]
+pointer_refencode_impl!(i128, u128);
 
 /// Pointers to [`i8`] use the special [`Encoding::String`] encoding.
 unsafe impl RefEncode for i8 {
@@ -371,6 +433,12 @@ encode_impls_nonzero!(
     NonZeroUsize => usize,
 );
 
+#[cfg(i128_aligned)]
+encode_impls_nonzero!(
+    NonZeroI128 => i128,
+    NonZeroU128 => u128,
+);
+
 /// Simple helper for implementing for atomic types.
 macro_rules! encode_atomic_impls {
     ($(
@@ -467,6 +535,88 @@ unsafe impl<T: Encode, const LENGTH: usize> RefEncode for [T; LENGTH] {
     const ENCODING_REF: Encoding = Encoding::Pointer(&Self::ENCODING);
 }
 
+/// SIMD vectors are encoded with their size and alignment, e.g. `f32x4` is
+/// encoded as `![16,16f]`, the same as `simd_float4`.
+#[cfg(feature = "unstable-simd")]
+unsafe impl<T: core::simd::SimdElement + Encode, const LANES: usize> Encode
+    for core::simd::Simd<T, LANES>
+{
+    const ENCODING: Encoding = Encoding::Vector(
+        mem::size_of::<Self>() as u64,
+        mem::align_of::<Self>() as u64,
+        &T::ENCODING,
+    );
+}
+
+#[cfg(feature = "unstable-simd")]
+unsafe impl<T: core::simd::SimdElement + Encode, const LANES: usize> RefEncode
+    for core::simd::Simd<T, LANES>
+{
+    const ENCODING_REF: Encoding = Encoding::Pointer(&Self::ENCODING);
+}
+
+/// Simple helper for implementing [`Encode`] for the SIMD vector types in
+/// `core::arch`, which are available on stable Rust.
+macro_rules! encode_impls_arch_vector {
+    ($($t:ident => $item:ty,)*) => ($(
+        #[doc = concat!("Encoded as a vector of [`", stringify!($item), "`].")]
+        unsafe impl Encode for arch::$t {
+            const ENCODING: Encoding = Encoding::Vector(
+                mem::size_of::<Self>() as u64,
+                mem::align_of::<Self>() as u64,
+                &<$item>::ENCODING,
+            );
+        }
+
+        unsafe impl RefEncode for arch::$t {
+            const ENCODING_REF: Encoding = Encoding::Pointer(&Self::ENCODING);
+        }
+    )*);
+}
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86 as arch;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64 as arch;
+
+// `__m128i` and `__m256i` are vectors of `long long` in C.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+encode_impls_arch_vector!(
+    __m128 => f32,
+    __m128d => f64,
+    __m128i => i64,
+    __m256 => f32,
+    __m256d => f64,
+    __m256i => i64,
+);
+
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64 as arch;
+
+#[cfg(target_arch = "aarch64")]
+encode_impls_arch_vector!(
+    int8x8_t => i8,
+    int8x16_t => i8,
+    int16x4_t => i16,
+    int16x8_t => i16,
+    int32x2_t => i32,
+    int32x4_t => i32,
+    int64x1_t => i64,
+    int64x2_t => i64,
+    uint8x8_t => u8,
+    uint8x16_t => u8,
+    uint16x4_t => u16,
+    uint16x8_t => u16,
+    uint32x2_t => u32,
+    uint32x4_t => u32,
+    uint64x1_t => u64,
+    uint64x2_t => u64,
+    float32x2_t => f32,
+    float32x4_t => f32,
+    float64x1_t => f64,
+    float64x2_t => f64,
+);
+
 macro_rules! encode_impls_transparent {
     ($($t:ident<T $(: ?$b:ident)?>,)*) => ($(
         unsafe impl<T: Encode $(+ ?$b)?> Encode for $t<T> {
@@ -662,6 +812,53 @@ mod tests {
         );
     }
 
+    #[cfg(i128_aligned)]
+    #[test]
+    fn test_i128() {
+        assert_eq!(i128::ENCODING, Encoding::Int128);
+        assert_eq!(<&u128>::ENCODING, Encoding::Pointer(&Encoding::UInt128));
+        assert_eq!(<Option<NonZeroU128>>::ENCODING, Encoding::UInt128);
+    }
+
+    #[cfg(feature = "unstable-simd")]
+    #[test]
+    fn test_simd() {
+        use core::simd::Simd;
+        assert_eq!(
+            <Simd<f32, 4>>::ENCODING,
+            Encoding::Vector(16, 16, &Encoding::Float)
+        );
+        assert_eq!(
+            <&Simd<i32, 2>>::ENCODING,
+            Encoding::Pointer(&Encoding::Vector(8, 8, &Encoding::Int))
+        );
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_arch_vector() {
+        use core::arch::x86_64::{__m128, __m256i};
+        assert_eq!(__m128::ENCODING, Encoding::Vector(16, 16, &Encoding::Float));
+        assert_eq!(
+            <&__m256i>::ENCODING,
+            Encoding::Pointer(&Encoding::Vector(32, 32, &Encoding::LongLong))
+        );
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[test]
+    fn test_arch_vector() {
+        use core::arch::aarch64::{float32x4_t, uint8x8_t};
+        assert_eq!(
+            float32x4_t::ENCODING,
+            Encoding::Vector(16, 16, &Encoding::Float)
+        );
+        assert_eq!(
+            <&uint8x8_t>::ENCODING,
+            Encoding::Pointer(&Encoding::Vector(8, 8, &Encoding::UChar))
+        );
+    }
+
     #[test]
     fn test_atomic() {
         assert_eq!(AtomicI32::ENCODING, Encoding::Atomic(&Encoding::Int));
@@ -752,4 +949,26 @@ mod tests {
         impls_encode(my_fn3 as extern "C" fn(_) -> _);
         impls_encode(my_fn4 as extern "C" fn(_, _) -> _);
     }
+
+    #[test]
+    fn test_encode_str() {
+        use alloc::string::ToString;
+        use std::ffi::CStr;
+
+        const INT: &CStr = crate::encode_str!(i32);
+        assert_eq!(INT.to_bytes(), b"i");
+
+        #[track_caller]
+        fn assert_matches<T: Encode>(s: &CStr) {
+            assert_eq!(s.to_str().unwrap(), T::ENCODING.to_string());
+        }
+
+        assert_matches::<crate::runtime::Bool>(crate::encode_str!(crate::runtime::Bool));
+        assert_matches::<&&u8>(crate::encode_str!(&&u8));
+        assert_matches::<[AtomicU64; 2]>(crate::encode_str!([AtomicU64; 2]));
+        assert_matches::<Option<extern "C" fn()>>(crate::encode_str!(Option<extern "C" fn()>));
+        assert_matches::<NonNull<crate::runtime::Object>>(crate::encode_str!(
+            NonNull<crate::runtime::Object>
+        ));
+    }
 }
@@ -157,6 +157,7 @@
     feature(negative_impls, auto_traits)
 )]
 #![cfg_attr(feature = "unstable-c-unwind", feature(c_unwind))]
+#![cfg_attr(feature = "unstable-simd", feature(portable_simd))]
 #![cfg_attr(feature = "unstable-docsrs", feature(doc_cfg, doc_auto_cfg))]
 #![warn(elided_lifetimes_in_paths)]
 #![warn(missing_docs)]
@@ -584,6 +584,18 @@ macro_rules! __inner_declare_class {
             }
         }
 
+        // Overriders of methods in this class must call `super` in the same
+        // cases as they would have to for the superclass.
+        impl $crate::declare::RequiresSuper for $for {
+            #[inline]
+            fn requires_super(selector: &$crate::__macro_helpers::str) -> $crate::__macro_helpers::bool {
+                #[allow(unused_imports)]
+                use $crate::__macro_helpers::{RequiresSuperFallback as _, RequiresSuperSpecific as _};
+                (&&$crate::__macro_helpers::RequiresSuperLookup::<$superclass>::new())
+                    .requires_super(selector)
+            }
+        }
+
         // Methods
         $crate::__declare_class_methods! {
             $($methods)*
@@ -977,6 +989,7 @@ macro_rules! __declare_class_method_out_inner {
             $($args_converted)*
         ) $(-> <$ret as $crate::encode::__unstable::EncodeConvertReturn>::__Inner)? {
             $($body_prefix)*
+            let __objc2_guard = $crate::__requires_super_guard!($($__sel)*);
             $crate::__convert_result! {
                 $body $(; $ret)?
             }
@@ -1009,6 +1022,7 @@ macro_rules! __declare_class_method_out_inner {
         ) -> $crate::declare::__IdReturnValue {
             $($body_prefix)*
 
+            let __objc2_guard = $crate::__requires_super_guard!($($sel)*);
             let __objc2_result = $body;
 
             #[allow(unreachable_code)]
@@ -1051,6 +1065,27 @@ macro_rules! __declare_class_method_out_inner {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __requires_super_guard {
+    ($($sel:tt)*) => {
+        $crate::__macro_helpers::RequiresSuperGuard::new::<Self>(
+            $crate::__sel_helper! {
+                @()
+                $($sel)*
+            },
+            |selector| {
+                #[allow(unused_imports)]
+                use $crate::__macro_helpers::{RequiresSuperFallback as _, RequiresSuperSpecific as _};
+                (&&$crate::__macro_helpers::RequiresSuperLookup::<
+                    <Self as $crate::ClassType>::Super,
+                >::new())
+                    .requires_super(selector)
+            },
+        )
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __convert_result {
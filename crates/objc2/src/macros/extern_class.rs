@@ -265,12 +265,12 @@ macro_rules! __impl_as_ref_borrow {
 macro_rules! __inner_extern_class {
     (
         $(#[$m:meta])*
-        $v:vis struct $name:ident<$($t_struct:ident $(: $b_struct:ident $(= $default:ty)?)?),* $(,)?> {
+        $v:vis struct $name:ident<$($t_struct:ident $(: $b_struct:ident $(+ $rest_struct:ident $(<$rest_arg_struct:ty>)?)* $(= $default:ty)?)?),* $(,)?> {
             $($fields:tt)*
         }
 
         $(#[$impl_m:meta])*
-        unsafe impl<$($t_for:ident $(: $b_for:ident)?),* $(,)?> ClassType for $for:ty {
+        unsafe impl<$($t_for:ident $(: $b_for:ident $(+ $rest_for:ident $(<$rest_arg_for:ty>)?)*)?),* $(,)?> ClassType for $for:ty {
             $(#[inherits($($inheritance_rest:ty),+ $(,)?)])?
             type Super = $superclass:ty;
 
@@ -280,7 +280,7 @@ macro_rules! __inner_extern_class {
         $crate::__emit_struct! {
             ($(#[$m])*)
             ($v)
-            ($name<$($t_struct $(: $b_struct $(= $default)?)?),*>)
+            ($name<$($t_struct $(: $b_struct $(+ $rest_struct $(<$rest_arg_struct>)?)* $(= $default)?)?),*>)
             (
                 __inner: $superclass,
                 $($fields)*
@@ -289,13 +289,13 @@ macro_rules! __inner_extern_class {
 
         $crate::__extern_class_impl_traits! {
             $(#[$impl_m])*
-            unsafe impl ($($t_for $(: $b_for)?),*) for $for {
+            unsafe impl ($($t_for $(: $b_for $(+ $rest_for $(<$rest_arg_for>)?)*)?),*) for $for {
                 INHERITS = [$superclass, $($($inheritance_rest,)+)? $crate::runtime::Object];
             }
         }
 
         $(#[$impl_m])*
-        unsafe impl<$($t_for $(: $b_for)?),*> ClassType for $for {
+        unsafe impl<$($t_for $(: $b_for $(+ $rest_for $(<$rest_arg_for>)?)*)?),*> ClassType for $for {
             type Super = $superclass;
             const NAME: &'static $crate::__macro_helpers::str = $crate::__select_name!($name; $($name_const)?);
 
@@ -171,14 +171,14 @@ macro_rules! extern_methods {
     (
         $(
             $(#[$impl_m:meta])*
-            unsafe impl<$($t:ident $(: $b:ident $(+ $rest:ident)*)?),* $(,)?> $type:ty {
+            unsafe impl<$($t:ident $(: $b:ident $(+ $rest:ident $(<$rest_arg:ty>)?)*)?),* $(,)?> $type:ty {
                 $($methods:tt)*
             }
         )+
     ) => {
         $(
             $(#[$impl_m])*
-            impl<$($t $(: $b $(+ $rest)*)?),*> $type {
+            impl<$($t $(: $b $(+ $rest $(<$rest_arg>)?)*)?),*> $type {
                 $crate::__extern_methods_rewrite_methods! {
                     $($methods)*
                 }
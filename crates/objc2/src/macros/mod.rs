@@ -676,6 +676,69 @@ macro_rules! __class_inner {
     }};
 }
 
+/// Gets the type-encoding of a type as a `&'static CStr`.
+///
+/// The string is computed at compile-time from [`Encode::ENCODING`], and can
+/// be passed directly to runtime functions like `class_addIvar`, without
+/// allocating. The macro can also be used in `const` and `static` items.
+///
+/// Generic constants are not yet available on stable Rust, so the type must
+/// be fully known; in generic code, use `T::ENCODING.to_string()` instead.
+///
+/// [`Encode::ENCODING`]: crate::encode::Encode::ENCODING
+///
+///
+/// # Examples
+///
+/// ```
+/// use std::ffi::CStr;
+/// use objc2::encode::{Encode, Encoding};
+/// use objc2::encode_str;
+///
+/// const NUMBER: &CStr = encode_str!(i32);
+/// assert_eq!(NUMBER.to_str().unwrap(), "i");
+///
+/// let s = encode_str!(*const *mut u8);
+/// assert_eq!(s.to_str().unwrap(), "^*");
+///
+/// #[repr(C)]
+/// struct MyStruct {
+///     a: f32,
+///     b: i16,
+/// }
+///
+/// unsafe impl Encode for MyStruct {
+///     const ENCODING: Encoding = Encoding::Struct("MyStruct", &[f32::ENCODING, i16::ENCODING]);
+/// }
+///
+/// assert_eq!(encode_str!(MyStruct).to_str().unwrap(), "{MyStruct=fs}");
+/// ```
+///
+/// Using a generic type parameter fails to compile:
+///
+/// ```compile_fail
+/// use objc2::encode::Encode;
+/// use objc2::encode_str;
+///
+/// fn generic<T: Encode>() {
+///     let _ = encode_str!(T);
+/// }
+/// ```
+#[macro_export]
+macro_rules! encode_str {
+    ($t:ty) => {{
+        const ENCODING: $crate::encode::Encoding = <$t as $crate::encode::Encode>::ENCODING;
+        const LEN: $crate::__macro_helpers::usize = ENCODING.__static_str_len() + 1;
+        // The last byte is left as NUL
+        const DATA: [$crate::__macro_helpers::u8; LEN] = ENCODING.__static_str_array::<LEN>();
+        // SAFETY: The encoding string contains no interior NUL bytes, and is
+        // followed by a NUL byte.
+        let s: &'static $crate::__macro_helpers::CStr =
+            unsafe { $crate::__macro_helpers::CStr::from_bytes_with_nul_unchecked(&DATA) };
+        s
+    }};
+}
+
 /// Send a message to an object or class.
 ///
 /// This is wildly `unsafe`, even more so than sending messages in
@@ -271,6 +271,7 @@ pub unsafe trait MessageReceiver: private::Sealed + Sized {
                 panic_verify(superclass, sel, err);
             }
         }
+        crate::__macro_helpers::called_super(superclass, sel);
         unsafe {
             EncodeConvertReturn::__from_return(send_super_unverified(this, superclass, sel, args))
         }
@@ -1,4 +1,5 @@
 use core::cell::RefCell;
+use core::mem::ManuallyDrop;
 use core::ptr;
 
 use super::{Allocated, Id, Owned};
@@ -158,6 +159,18 @@ declare_class!(
             None
         }
 
+        /// Returns +1, like methods marked `NS_RETURNS_RETAINED`.
+        #[method(methodReturningRetained)]
+        fn method_returning_retained(&self) -> *mut Self {
+            Id::consume_as_ptr(ManuallyDrop::new(Self::new()))
+        }
+
+        /// Returns +0, like methods marked `NS_RETURNS_NOT_RETAINED`.
+        #[method(copyReturningNotRetained)]
+        fn copy_returning_not_retained(&self) -> *const Self {
+            self
+        }
+
         #[method_id(aMethod:)]
         fn a_method(&self, param: bool) -> Option<Id<Self, Owned>> {
             param.then(Self::new)
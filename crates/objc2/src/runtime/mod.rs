@@ -22,17 +22,15 @@ use std::os::raw::c_uint;
 #[doc(hidden)]
 pub mod __nsstring;
 mod bool;
-mod method_encoding_iter;
 mod nsobject;
 mod nsproxy;
 mod nszone;
 mod protocol_object;
 
-pub(crate) use self::method_encoding_iter::{EncodingParseError, MethodEncodingIter};
 use crate::encode::__unstable::{EncodeArguments, EncodeConvertReturn, EncodeReturn};
-use crate::encode::{Encode, Encoding, OptionEncode, RefEncode};
+use crate::encode::{Encode, Encoding, MethodEncoding, OptionEncode, ParseError, RefEncode};
 use crate::ffi;
-use crate::verify::{verify_method_signature, Inner};
+use crate::verify::{verify_method_signature, Inner, PROFILE};
 
 pub use self::bool::Bool;
 pub use self::nsobject::{NSObject, NSObjectProtocol};
@@ -355,20 +353,9 @@ impl Method {
         }
     }
 
-    /// An iterator over the method's types.
-    ///
-    /// It is approximately equivalent to:
-    ///
-    /// ```ignore
-    /// let types = method.types();
-    /// assert_eq!(types.next()?, method.return_type());
-    /// for i in 0..method.arguments_count() {
-    ///    assert_eq!(types.next()?, method.argument_type(i)?);
-    /// }
-    /// assert!(types.next().is_none());
-    /// ```
+    /// The method's type-encoding.
     #[doc(alias = "method_getTypeEncoding")]
-    pub(crate) fn types(&self) -> MethodEncodingIter<'_> {
+    pub(crate) fn types(&self) -> Result<MethodEncoding, ParseError> {
         // SAFETY: The method pointer is valid and non-null
         let cstr = unsafe { ffi::method_getTypeEncoding(self.as_ptr()) };
         if cstr.is_null() {
@@ -378,7 +365,7 @@ impl Method {
         // checked that it is non-null.
         let encoding = unsafe { CStr::from_ptr(cstr) };
         let s = str::from_utf8(encoding.to_bytes()).expect("method type encoding to be UTF-8");
-        MethodEncodingIter::new(s)
+        s.parse()
     }
 
     /// Returns the number of arguments accepted by self.
@@ -615,6 +602,12 @@ impl Class {
     /// and return a [`VerificationError`] if any encodings differ for the
     /// arguments `A` and return type `R`.
     ///
+    /// The encodings are compared using the [`EncodingProfile`] of the
+    /// runtime that is linked against, so that e.g. a `BOOL` reported as
+    /// `C` on GNUstep is considered equivalent to a C `_Bool`.
+    ///
+    /// [`EncodingProfile`]: crate::encode::EncodingProfile
+    ///
     ///
     /// # Example
     ///
@@ -781,7 +774,7 @@ pub(crate) fn ivar_offset(cls: &Class, name: &str, expected: &Encoding) -> isize
         Some(ivar) => {
             let encoding = ivar.type_encoding();
             assert!(
-                expected.equivalent_to_str(encoding),
+                expected.equivalent_to_str_in(encoding, PROFILE),
                 "wrong encoding. Tried to retrieve ivar with encoding {encoding}, but the encoding of the given type was {expected}",
             );
             ivar.offset()
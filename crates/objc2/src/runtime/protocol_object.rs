@@ -115,6 +115,72 @@ impl<P: ?Sized + ProtocolType> ProtocolObject<P> {
         //   let's just be on the safe side)!
         unsafe { Id::cast::<Self>(obj) }
     }
+
+    /// Get an immutable type-erased reference to an object, if its class
+    /// conforms to the protocol.
+    ///
+    /// Unlike [`from_ref`], this checks at runtime whether the object's
+    /// class (or one of its superclasses) conforms to the protocol, and is
+    /// useful for objects whose conformance isn't known statically.
+    ///
+    /// Returns `None` if the object doesn't conform to the protocol, or if
+    /// the protocol is not registered with the runtime (see
+    /// [`ProtocolType::protocol`]).
+    ///
+    /// [`from_ref`]: Self::from_ref
+    pub fn try_from_object<T: Message>(obj: &T) -> Option<&Self> {
+        if Self::conforms(obj) {
+            let ptr: NonNull<T> = NonNull::from(obj);
+            // SAFETY: The runtime has checked that the object conforms to
+            // the protocol.
+            Some(unsafe { ptr.cast().as_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Get a type-erased object, if its class conforms to the protocol.
+    ///
+    /// This is the owned version of [`try_from_object`], and returns the
+    /// object back in the error case.
+    ///
+    /// [`try_from_object`]: Self::try_from_object
+    pub fn try_from_id<T, O: Ownership>(obj: Id<T, O>) -> Result<Id<Self, O>, Id<T, O>>
+    where
+        P: 'static,
+        T: Message + 'static,
+    {
+        if Self::conforms(&*obj) {
+            // SAFETY:
+            // - The runtime has checked that the object conforms to the
+            //   protocol.
+            // - Both types are `'static`, see `from_id`.
+            Ok(unsafe { Id::cast::<Self>(obj) })
+        } else {
+            Err(obj)
+        }
+    }
+
+    fn conforms<T: Message>(obj: &T) -> bool {
+        let protocol = match P::protocol() {
+            Some(protocol) => protocol,
+            None => return false,
+        };
+        let ptr: NonNull<T> = NonNull::from(obj);
+        // SAFETY: Any type that implements `Message` can be viewed as an
+        // `Object`.
+        let object: &Object = unsafe { ptr.cast().as_ref() };
+
+        // `class_conformsToProtocol` doesn't check superclasses.
+        let mut cls = Some(object.class());
+        while let Some(current) = cls {
+            if current.conforms_to(protocol) {
+                return true;
+            }
+            cls = current.superclass();
+        }
+        false
+    }
 }
 
 impl<P: ?Sized + ProtocolType + NSObjectProtocol> PartialEq for ProtocolObject<P> {
@@ -333,6 +399,23 @@ mod tests {
         let _foobar: Id<ProtocolObject<dyn FooBar>, _> = ProtocolObject::from_id(obj);
     }
 
+    #[test]
+    fn try_from_object() {
+        let obj = DummyClass::new();
+
+        // Conformance is inherited from `NSObject`
+        let nsobject: Option<&ProtocolObject<NSObject>> = ProtocolObject::try_from_object(&*obj);
+        assert!(nsobject.is_some());
+
+        // `Foo` is not registered with the runtime
+        let foo: Option<&ProtocolObject<dyn Foo>> = ProtocolObject::try_from_object(&*obj);
+        assert!(foo.is_none());
+
+        let obj: Id<ProtocolObject<NSObject>, _> = ProtocolObject::try_from_id(obj).unwrap();
+        let obj: Id<DummyClass, _> = unsafe { Id::cast(obj) };
+        let _obj: Id<DummyClass, _> = ProtocolObject::<dyn Foo>::try_from_id(obj).err().unwrap();
+    }
+
     #[test]
     fn test_traits() {
         use core::hash::Hasher;
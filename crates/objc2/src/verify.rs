@@ -2,13 +2,32 @@ use core::fmt;
 use core::hash::Hash;
 use std::error::Error;
 
-use crate::encode::{Encoding, EncodingBox};
-use crate::runtime::{EncodingParseError, Method};
+use crate::encode::{Encode, Encoding, EncodingBox, EncodingProfile, ParseError};
+use crate::runtime::{Bool, Method};
+
+/// The profile of the runtime that we're linking against, used to avoid
+/// false positives when comparing encodings.
+pub(crate) const PROFILE: EncodingProfile = if cfg!(feature = "gnustep-1-7") {
+    // libobjc2 compiled with strict Apple compatibility has a `signed char`
+    // `BOOL`, which is otherwise only the case on ObjFW; the remaining quirks
+    // of the two runtimes are the same.
+    if matches!(Bool::ENCODING, Encoding::Char) {
+        EncodingProfile::ObjFW
+    } else {
+        EncodingProfile::GNUstep
+    }
+} else if cfg!(feature = "unstable-objfw") {
+    EncodingProfile::ObjFW
+} else {
+    EncodingProfile::Apple
+};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub(crate) enum Inner {
     MethodNotFound,
-    EncodingParseError(EncodingParseError),
+    ParseError(ParseError),
+    InvalidReceiver(EncodingBox),
+    InvalidSel(EncodingBox),
     MismatchedReturn(EncodingBox, Encoding),
     MismatchedArgumentsCount(usize, usize),
     MismatchedArgument(usize, EncodingBox, Encoding),
@@ -18,12 +37,27 @@ impl fmt::Display for Inner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::MethodNotFound => write!(f, "method not found"),
-            Self::EncodingParseError(e) => write!(f, "{e}"),
+            Self::ParseError(e) => {
+                write!(f, "{e}. This is likely a bug, please report it!")
+            }
+            Self::InvalidReceiver(enc) => {
+                write!(
+                    f,
+                    "failed parsing encoding: receiver encoding must be '@', but it was '{enc}'. This is likely a bug, please report it!",
+                )
+            }
+            Self::InvalidSel(enc) => {
+                write!(
+                    f,
+                    "failed parsing encoding: selector encoding must be ':', but it was '{enc}'. This is likely a bug, please report it!",
+                )
+            }
             Self::MismatchedReturn(expected, actual) => {
                 write!(
                     f,
                     "expected return to have type code '{expected}', but found '{actual}'",
-                )
+                )?;
+                fmt_diff(f, &"return", expected, actual)
             }
             Self::MismatchedArgumentsCount(expected, actual) => {
                 write!(f, "expected {expected} arguments, but {actual} were given",)
@@ -32,12 +66,27 @@ impl fmt::Display for Inner {
                 write!(
                     f,
                     "expected argument at index {i} to have type code '{expected}', but found '{actual}'",
-                )
+                )?;
+                fmt_diff(f, &format_args!("arg {i}"), expected, actual)
             }
         }
     }
 }
 
+/// Point out where exactly the encodings differ, if it is not obvious from
+/// the top-level encodings.
+fn fmt_diff(
+    f: &mut fmt::Formatter<'_>,
+    start: &dyn fmt::Display,
+    expected: &EncodingBox,
+    actual: &Encoding,
+) -> fmt::Result {
+    match actual.diff_box_in(expected, PROFILE) {
+        Some(diff) if !diff.path().is_empty() => write!(f, " (at {start} -> {diff})"),
+        _ => Ok(()),
+    }
+}
+
 /// Failed verifying selector on a class.
 ///
 /// This is returned in the error case of [`Class::verify_sel`], see that for
@@ -50,9 +99,9 @@ impl fmt::Display for Inner {
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct VerificationError(Inner);
 
-impl From<EncodingParseError> for VerificationError {
-    fn from(e: EncodingParseError) -> Self {
-        Self(Inner::EncodingParseError(e))
+impl From<ParseError> for VerificationError {
+    fn from(e: ParseError) -> Self {
+        Self(Inner::ParseError(e))
     }
 }
 
@@ -76,34 +125,32 @@ pub(crate) fn verify_method_signature(
     args: &[Encoding],
     ret: &Encoding,
 ) -> Result<(), VerificationError> {
-    let mut iter = method.types();
-
     // TODO: Verify stack layout
-    let (expected, _stack_layout) = iter.extract_return()?;
-    if !ret.equivalent_to_box(&expected) {
+    let encoding = method.types()?;
+
+    let expected = encoding.return_type.encoding;
+    if !ret.equivalent_to_box_in(&expected, PROFILE) {
         return Err(Inner::MismatchedReturn(expected, ret.clone()).into());
     }
 
-    iter.verify_receiver()?;
-    iter.verify_sel()?;
+    if !Encoding::Object.equivalent_to_box_in(&encoding.receiver.encoding, PROFILE) {
+        return Err(Inner::InvalidReceiver(encoding.receiver.encoding).into());
+    }
+    if !Encoding::Sel.equivalent_to_box_in(&encoding.selector.encoding, PROFILE) {
+        return Err(Inner::InvalidSel(encoding.selector.encoding).into());
+    }
 
     let actual_count = args.len();
+    let expected_count = encoding.arguments.len();
 
-    for (i, actual) in args.iter().enumerate() {
-        if let Some(res) = iter.next() {
-            // TODO: Verify stack layout
-            let (expected, _stack_layout) = res?;
-            if !actual.equivalent_to_box(&expected) {
-                return Err(Inner::MismatchedArgument(i, expected, actual.clone()).into());
-            }
-        } else {
-            return Err(Inner::MismatchedArgumentsCount(i, actual_count).into());
+    for (i, (actual, expected)) in args.iter().zip(encoding.arguments).enumerate() {
+        if !actual.equivalent_to_box_in(&expected.encoding, PROFILE) {
+            return Err(Inner::MismatchedArgument(i, expected.encoding, actual.clone()).into());
         }
     }
 
-    let remaining = iter.count();
-    if remaining != 0 {
-        return Err(Inner::MismatchedArgumentsCount(actual_count + remaining, actual_count).into());
+    if expected_count != actual_count {
+        return Err(Inner::MismatchedArgumentsCount(expected_count, actual_count).into());
     }
 
     let expected_count = method.name().number_of_arguments();
@@ -136,6 +183,34 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_profile() {
+        // `BOOL` is equivalent to `_Bool` on all runtimes
+        assert!(Bool::ENCODING.equivalent_to_in(&Encoding::Bool, PROFILE));
+        assert!(Encoding::Bool.equivalent_to_str_in(&Bool::ENCODING.to_string(), PROFILE));
+    }
+
+    #[test]
+    fn test_mismatch_path() {
+        const SIZE: Encoding = Encoding::Struct("CGSize", &[Encoding::Float, Encoding::Double]);
+        const RECT: Encoding = Encoding::Struct("CGRect", &[SIZE, SIZE]);
+        let expected: EncodingBox = "{CGRect={CGSize=dd}{CGSize=dd}}".parse().unwrap();
+
+        let err = VerificationError::from(Inner::MismatchedArgument(2, expected.clone(), RECT));
+        assert_eq!(
+            err.to_string(),
+            "expected argument at index 2 to have type code '{CGRect={CGSize=dd}{CGSize=dd}}', \
+            but found '{CGRect={CGSize=fd}{CGSize=fd}}' (at arg 2 -> struct CGRect -> field 0 \
+            -> struct CGSize -> field 0: expected 'd', but found 'f')"
+        );
+
+        let err = VerificationError::from(Inner::MismatchedReturn(expected, Encoding::Int));
+        assert_eq!(
+            err.to_string(),
+            "expected return to have type code '{CGRect={CGSize=dd}{CGSize=dd}}', but found 'i'"
+        );
+    }
+
     #[test]
     fn test_verify_message_errors() {
         let cls = test_utils::custom_class();
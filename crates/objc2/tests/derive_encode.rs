@@ -0,0 +1,120 @@
+//! Tests for `#[derive(Encode, RefEncode)]`.
+#![cfg(feature = "derive")]
+#![allow(dead_code)]
+use core::ffi::c_void;
+use core::marker::PhantomData;
+
+use objc2::encode::{Encode, Encoding, RefEncode};
+
+#[repr(C)]
+#[derive(Encode, RefEncode)]
+struct Empty {}
+
+#[repr(C)]
+#[derive(Encode, RefEncode)]
+pub struct Point {
+    /// Doc comments and visibility are allowed.
+    pub x: f64,
+    pub(crate) y: f64,
+}
+
+#[repr(C)]
+#[derive(Encode, RefEncode)]
+#[encoding(name = "CGRect")]
+struct Rect {
+    origin: Point,
+    size: [Point; 1],
+}
+
+#[repr(C)]
+#[derive(Encode)]
+#[encoding(name = "Default", apple = "Apple", gnustep = "GNUStep")]
+struct RuntimeSpecific(i8, u8);
+
+#[repr(C)]
+#[derive(Encode, RefEncode)]
+union Union {
+    a: i32,
+    b: *const c_void,
+}
+
+#[repr(C)]
+#[derive(Encode, RefEncode)]
+struct Generic<'a, T: Copy, const N: usize = 2> {
+    array: [T; N],
+    func: Option<extern "C" fn(i32) -> T>,
+    reference: &'a u8,
+}
+
+#[repr(transparent)]
+#[derive(Encode, RefEncode)]
+struct Transparent<T> {
+    inner: u32,
+    p: PhantomData<T>,
+}
+
+#[test]
+fn test_struct() {
+    assert_eq!(Empty::ENCODING, Encoding::Struct("Empty", &[]));
+    assert_eq!(
+        Point::ENCODING,
+        Encoding::Struct("Point", &[Encoding::Double, Encoding::Double]),
+    );
+    assert_eq!(Point::ENCODING_REF, Encoding::Pointer(&Point::ENCODING));
+    assert_eq!(
+        Rect::ENCODING,
+        Encoding::Struct(
+            "CGRect",
+            &[Point::ENCODING, Encoding::Array(1, &Point::ENCODING)],
+        ),
+    );
+}
+
+#[test]
+fn test_runtime_specific_name() {
+    let name = if cfg!(feature = "apple") {
+        "Apple"
+    } else if cfg!(feature = "gnustep-1-7") {
+        "GNUStep"
+    } else {
+        "Default"
+    };
+    assert_eq!(
+        RuntimeSpecific::ENCODING,
+        Encoding::Struct(name, &[Encoding::Char, Encoding::UChar]),
+    );
+}
+
+#[test]
+fn test_union() {
+    assert_eq!(
+        Union::ENCODING,
+        Encoding::Union(
+            "Union",
+            &[Encoding::Int, Encoding::Pointer(&Encoding::Void)]
+        ),
+    );
+    assert_eq!(Union::ENCODING_REF, Encoding::Pointer(&Union::ENCODING));
+}
+
+#[test]
+fn test_generic() {
+    assert_eq!(
+        <Generic<'static, u16>>::ENCODING,
+        Encoding::Struct(
+            "Generic",
+            &[
+                Encoding::Array(2, &Encoding::UShort),
+                Encoding::Pointer(&Encoding::Unknown),
+                // Pointers to `u8` are encoded as C strings
+                Encoding::String,
+            ],
+        ),
+    );
+}
+
+#[test]
+fn test_transparent() {
+    assert_eq!(<Transparent<String>>::ENCODING, Encoding::UInt);
+    assert_eq!(<Transparent<String>>::ENCODING_REF, u32::ENCODING_REF);
+}
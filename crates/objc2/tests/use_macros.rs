@@ -1,5 +1,8 @@
+use core::marker::PhantomData;
+
+use objc2::rc::{Ownership, Shared};
 use objc2::runtime::{Class, NSObject, Object};
-use objc2::{class, declare_class, msg_send, sel, ClassType};
+use objc2::{class, declare_class, msg_send, sel, ClassType, Message};
 
 declare_class!(
     struct MyObject;
@@ -57,3 +60,34 @@ fn test_msg_send_comma_handling(obj: &MyObject, superclass: &Class) {
         let _: () = msg_send![super(obj), a: 32i32, b: 32i32,];
     }
 }
+
+// Generic classes whose parameter is bounded by a class, as emitted by
+// `header-translator`.
+objc2::__inner_extern_class!(
+    pub struct GenericWithBound<
+        T: Message + AsRef<NSObject> = NSObject,
+        TOwnership: Ownership = Shared,
+    > {
+        _inner0: PhantomData<*mut (T, TOwnership)>,
+    }
+
+    unsafe impl<T: Message + AsRef<NSObject>, TOwnership: Ownership> ClassType
+        for GenericWithBound<T, TOwnership>
+    {
+        type Super = NSObject;
+        const NAME: &'static str = "NSObject";
+    }
+);
+
+objc2::extern_methods!(
+    unsafe impl<T: Message + AsRef<NSObject>, TOwnership: Ownership> GenericWithBound<T, TOwnership> {
+        #[method(hash)]
+        fn hash_generic(&self) -> usize;
+    }
+);
+
+#[allow(unused)]
+fn test_generic_with_bound(obj: &GenericWithBound, other: &GenericWithBound<MyObject>) {
+    let _ = obj.hash_generic();
+    let _ = other.hash_generic();
+}
@@ -52,8 +52,24 @@ pub fn invoke_large_struct_block(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::boxed::Box;
+    use alloc::format;
     use alloc::string::ToString;
-    use block2::{global_block, ConcreteBlock, RcBlock};
+    use alloc::sync::Arc;
+    use core::cell::Cell;
+    use core::future::Future;
+    use core::mem::ManuallyDrop;
+    use core::pin::Pin;
+    use core::ptr;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::task::{Context, Poll, Waker};
+    use std::task::Wake;
+
+    use block2::{
+        completion, global_block, BlockByRef, ByRef, CapturingBlock, ConcreteBlock, NoEscapeBlock,
+        RcBlock, SafeBlock, ScopedBlock,
+    };
+    use objc2::rc::{__RcTestObject, __ThreadTestData, Id, Owned, Shared};
 
     global_block! {
         /// Test `global_block` in an external crate
@@ -145,4 +161,292 @@ mod tests {
         let block = block.copy();
         assert_eq!(invoke_large_struct_block(&block, data), new_data);
     }
+
+    #[test]
+    fn test_block_signature() {
+        global_block! {
+            static BLOCK = |x: i32, _y: *const u8| -> i32 {
+                x
+            };
+        }
+        assert!(format!("{BLOCK:?}").contains(r#"encoding: Some("i@?i*")"#));
+
+        let s = "Hello!".to_string();
+        let block = ConcreteBlock::new(move |x: i32| x + s.len() as i32);
+        assert!(format!("{:?}", &*block).contains(r#"encoding: Some("i@?i")"#));
+        let block = block.copy();
+        assert!(format!("{block:?}").contains(r#"encoding: Some("i@?i")"#));
+
+        let block = ConcreteBlock::new(|mut x: LargeStruct| {
+            x.mutate();
+            x
+        });
+        let expected = r#"encoding: Some("{LargeStruct=f[100C]}@?{LargeStruct=f[100C]}")"#;
+        assert!(format!("{:?}", &*block).contains(expected));
+    }
+
+    #[test]
+    fn test_verify_block() {
+        let block = get_add_block_with(13);
+        block.verify().unwrap();
+
+        let ptr: *const Block<(i32,), i32> = &*block;
+        let block: &Block<(i64,), i32> = unsafe { &*ptr.cast() };
+        let err = block.verify().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected argument at index 0 to have type code 'i', but found 'q'"
+        );
+
+        let block = ConcreteBlock::new(|a: i32| a + 5);
+        block.verify().unwrap();
+        MY_BLOCK.verify().unwrap();
+    }
+
+    #[test]
+    fn test_capturing_block() {
+        let obj: Id<__RcTestObject, Shared> = __RcTestObject::new().into();
+        let mut expected = __ThreadTestData::current();
+
+        let block = CapturingBlock::new((obj.clone(),), |_: &(Id<__RcTestObject, Shared>,)| 7);
+        expected.retain += 1;
+        expected.assert_current();
+
+        // Copying retains the captured object through the runtime
+        let copied = block.copy();
+        expected.retain += 1;
+        expected.assert_current();
+        assert_eq!(invoke_int_block(&block), 7);
+        assert_eq!(invoke_int_block(&copied), 7);
+
+        drop(block);
+        expected.release += 1;
+        expected.assert_current();
+
+        // Nested blocks are copied as well
+        let nested_stack = CapturingBlock::new((copied,), |(inner,): &(RcBlock<(), i32>,)| {
+            invoke_int_block(inner) + 1
+        });
+        let nested = nested_stack.copy();
+        drop(nested_stack);
+        assert_eq!(invoke_int_block(&nested), 8);
+        expected.assert_current();
+
+        drop(nested);
+        expected.release += 1;
+        expected.assert_current();
+
+        drop(obj);
+        expected.release += 1;
+        expected.dealloc += 1;
+        expected.assert_current();
+    }
+
+    #[test]
+    fn test_block_byref() {
+        let counter = BlockByRef::new(0);
+        let block = CapturingBlock::new((counter.capture(),), |(counter,): &(ByRef<'_, i32>,)| {
+            counter.set(counter.get() + 1);
+            counter.get()
+        });
+        assert_eq!(invoke_int_block(&block), 1);
+        assert_eq!(counter.get(), 1);
+
+        // Copying moves the variable to the heap, the value is still shared
+        let copied = block.copy();
+        assert_eq!(invoke_int_block(&copied), 2);
+        assert_eq!(invoke_int_block(&block), 3);
+        assert_eq!(counter.get(), 3);
+        counter.set(10);
+        assert_eq!(invoke_int_block(&copied), 11);
+
+        // The copy keeps the variable alive
+        drop(counter);
+        assert_eq!(invoke_int_block(&copied), 12);
+    }
+
+    #[test]
+    fn test_block_byref_object() {
+        let obj = __RcTestObject::new();
+        let mut expected = __ThreadTestData::current();
+
+        let var = BlockByRef::new(obj);
+        let block = CapturingBlock::new(
+            (var.capture(),),
+            |_: &(ByRef<'_, Id<__RcTestObject, Owned>>,)| 0,
+        );
+        let copied = block.copy();
+        // The object is moved to the heap, not retained
+        drop(var);
+        expected.assert_current();
+
+        drop(copied);
+        expected.release += 1;
+        expected.dealloc += 1;
+        expected.assert_current();
+
+        // Not copied
+        let var = BlockByRef::new(__RcTestObject::new());
+        expected.alloc += 1;
+        expected.init += 1;
+        drop(var);
+        expected.release += 1;
+        expected.dealloc += 1;
+        expected.assert_current();
+    }
+
+    #[test]
+    fn test_once_block() {
+        let obj = __RcTestObject::new();
+        let mut expected = __ThreadTestData::current();
+
+        let block = RcBlock::new_once(move |a: i32| {
+            drop(obj);
+            a + 1
+        });
+        expected.assert_current();
+
+        assert_eq!(invoke_add_block(&block, 5), 6);
+        expected.release += 1;
+        expected.dealloc += 1;
+        expected.assert_current();
+
+        // Dropping the block after it has been called doesn't drop the
+        // closure again
+        drop(block);
+        expected.assert_current();
+    }
+
+    #[test]
+    fn test_once_block_not_called() {
+        let obj = __RcTestObject::new();
+        let mut expected = __ThreadTestData::current();
+
+        let block = RcBlock::new_once(move || {
+            drop(obj);
+            0
+        });
+        drop(block);
+        expected.release += 1;
+        expected.dealloc += 1;
+        expected.assert_current();
+    }
+
+    #[test]
+    fn test_mut_block() {
+        let mut sum = 0;
+        let block = RcBlock::new_mut(move |a: i32| {
+            sum += a;
+            sum
+        });
+        assert_eq!(invoke_add_block(&block, 2), 2);
+        assert_eq!(invoke_add_block(&block, 3), 5);
+
+        let cloned = block.clone();
+        assert_eq!(invoke_add_block(&cloned, 4), 9);
+    }
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn poll<F: Future + Unpin>(future: &mut F, waker: &Arc<CountingWaker>) -> Poll<F::Output> {
+        let waker = Waker::from(waker.clone());
+        Pin::new(future).poll(&mut Context::from_waker(&waker))
+    }
+
+    #[test]
+    fn test_completion_handler() {
+        let waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let (block, mut completion) = completion::handler(|a: i32| a + 1);
+        assert!(poll(&mut completion, &waker).is_pending());
+
+        unsafe { block.call((2,)) };
+        assert_eq!(waker.0.load(Ordering::Relaxed), 1);
+        assert_eq!(poll(&mut completion, &waker), Poll::Ready(3));
+    }
+
+    #[test]
+    #[should_panic = "completion handler was dropped without being called"]
+    fn test_completion_handler_dropped() {
+        let waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let (block, mut completion) = completion::handler(|| {});
+        drop(block);
+        let _ = poll(&mut completion, &waker);
+    }
+
+    #[test]
+    fn test_result_handler() {
+        let waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let obj = __RcTestObject::new();
+        let obj_ptr = Id::as_ptr(&obj) as *mut __RcTestObject;
+
+        let (block, mut completion) =
+            completion::result_handler::<__RcTestObject, __RcTestObject>();
+        let mut expected = __ThreadTestData::current();
+        unsafe { block.call((obj_ptr, ptr::null_mut())) };
+        expected.retain += 1;
+        expected.assert_current();
+        let res = poll(&mut completion, &waker);
+        assert!(matches!(res, Poll::Ready(Ok(res)) if Id::as_ptr(&res) == obj_ptr));
+
+        let (block, mut completion) =
+            completion::result_handler::<__RcTestObject, __RcTestObject>();
+        unsafe { block.call((ptr::null_mut(), obj_ptr)) };
+        let res = poll(&mut completion, &waker);
+        assert!(matches!(res, Poll::Ready(Err(err)) if Id::as_ptr(&err) == obj_ptr));
+
+        let (block, mut completion) = completion::error_handler::<__RcTestObject>();
+        unsafe { block.call((ptr::null_mut(),)) };
+        assert!(matches!(poll(&mut completion, &waker), Poll::Ready(Ok(()))));
+    }
+
+    #[test]
+    fn test_scoped_block() {
+        fn call_twice(block: &NoEscapeBlock<(i32,), i32>) -> i32 {
+            unsafe { block.call((1,)) + block.call((2,)) }
+        }
+
+        // Borrows from the stack
+        let calls = Cell::new(0);
+        let block = ScopedBlock::new(|a: i32| {
+            calls.set(calls.get() + 1);
+            a * 10
+        });
+        assert_eq!(block.signature().unwrap().to_string(), "@?<i@?i>");
+        assert_eq!(call_twice(&block), 30);
+        assert_eq!(calls.get(), 2);
+
+        // Other blocks can be used as well
+        assert_eq!(call_twice(&get_add_block_with(5)), 13);
+    }
+
+    #[test]
+    fn test_safe_block() {
+        let block = SafeBlock::new(|a: i32, b: i32| a * b);
+        assert_eq!(block.call(3, 4), 12);
+
+        let block = unsafe { SafeBlock::verified(get_add_block_with(7)) };
+        assert_eq!(block.call(2), 9);
+        assert_eq!(invoke_add_block(&block, 3), 10);
+
+        let f: Box<dyn Fn(i32) -> i32> = block.clone().into();
+        assert_eq!(f(4), 11);
+        drop(block);
+        assert_eq!(f(5), 12);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "invalid block signature"]
+    fn test_safe_block_invalid() {
+        let block = ManuallyDrop::new(get_add_block_with(7));
+        let ptr: *const Block<(i64,), i32> = (&**block as *const Block<(i32,), i32>).cast();
+        let block: RcBlock<(i64,), i32> = unsafe { RcBlock::new(ptr as *mut _) };
+        let _ = unsafe { SafeBlock::verified(block) };
+    }
 }
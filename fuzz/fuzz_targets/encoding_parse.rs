@@ -18,6 +18,7 @@ fuzz_target!(|s: &str| {
 
         // Test roundtrip
         let enc2 = EncodingBox::from_str(&s2).expect("parsing valid encoding string");
+        assert_eq!(enc, enc2, "parsing output again");
         let s3 = enc2.to_string();
         assert_eq!(s2, s3);
     }
@@ -0,0 +1,24 @@
+#![no_main]
+use std::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+use objc2_encode::EncodingBox;
+
+fuzz_target!(|input: (EncodingBox, EncodingBox)| {
+    let (enc1, enc2) = input;
+    let s1 = enc1.to_string();
+    let s2 = enc2.to_string();
+
+    // Check that the printer and the parser agree
+    assert_eq!(EncodingBox::from_str(&s1), Ok(enc1.clone()), "roundtrip");
+
+    // Check that exactly one encoding is consumed from the start of the
+    // string (`@` followed by `?` is parsed as a block).
+    if !(s1.ends_with('@') && s2.starts_with('?')) {
+        let concatenated = format!("{s1}{s2}");
+        let mut remaining = &*concatenated;
+        let enc = EncodingBox::from_start_of_str(&mut remaining).expect("parsing first");
+        assert_eq!(enc, enc1, "first encoding");
+        assert_eq!(remaining, s2, "remaining");
+    }
+});